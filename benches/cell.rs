@@ -0,0 +1,132 @@
+//! Throughput of the core cell operations under contention
+//!
+//! Every operation is measured with each preset of `Semantics` and with 1, 2 and 8 threads working
+//! on the same cell. The reported time is the wall-clock time for every thread to perform one
+//! operation, so lower numbers under more threads mean better scaling
+//!
+//! Run with `cargo bench`. Filter with e.g. `cargo bench -- replace/coupled`
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ptr_cell::{node::Node, PtrCell, Semantics};
+use std::{
+    hint::black_box,
+    sync::Barrier,
+    time::{Duration, Instant},
+};
+
+/// Numbers of threads to measure each operation with
+const THREADS: [usize; 3] = [1, 2, 8];
+
+/// Runs `operation` `iterations` times on each of `threads` threads at once, returning the
+/// wall-clock time it took
+fn contended<F>(threads: usize, iterations: u64, operation: F) -> Duration
+where
+    F: Fn() + Sync,
+{
+    let barrier = Barrier::new(threads + 1);
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                barrier.wait();
+
+                for _ in 0..iterations {
+                    operation();
+                }
+            });
+        }
+
+        barrier.wait();
+        let start = Instant::now();
+
+        // Leaving the scope joins the threads
+        start
+    })
+    .elapsed()
+}
+
+/// Measures [`PtrCell::replace`] of a small value
+fn replace(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("replace");
+
+    for order in Semantics::ALL {
+        for threads in THREADS {
+            let id = BenchmarkId::new(order.to_string(), threads);
+
+            group.bench_function(id, |bencher| {
+                bencher.iter_custom(|iterations| {
+                    let cell = PtrCell::from(0_u64);
+
+                    contended(threads, iterations, || {
+                        black_box(cell.replace(Some(black_box(1)), order));
+                    })
+                });
+            });
+        }
+    }
+
+    group.finish();
+}
+
+/// Measures [`PtrCell::take`] paired with [`PtrCell::set`], so that there's something to take
+fn take(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("take");
+
+    for order in Semantics::ALL {
+        for threads in THREADS {
+            let id = BenchmarkId::new(order.to_string(), threads);
+
+            group.bench_function(id, |bencher| {
+                bencher.iter_custom(|iterations| {
+                    let cell = PtrCell::default();
+
+                    contended(threads, iterations, || {
+                        cell.set(Some(black_box(1_u64)), order);
+                        black_box(cell.take(order));
+                    })
+                });
+            });
+        }
+    }
+
+    group.finish();
+}
+
+/// Measures [`PtrCell::map_owner`] pushing onto a shared linked list
+fn map_owner(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("map_owner");
+
+    for order in Semantics::ALL {
+        for threads in THREADS {
+            let id = BenchmarkId::new(order.to_string(), threads);
+
+            group.bench_function(id, |bencher| {
+                bencher.iter_custom(|iterations| {
+                    let list = PtrCell::default();
+
+                    let elapsed = contended(threads, iterations, || {
+                        list.map_owner(
+                            |next| Node {
+                                value: black_box(1_u64),
+                                next,
+                            },
+                            order,
+                        );
+                    });
+
+                    // Dropping a long list recursively could overflow the stack
+                    if let Some(head) = list.take(Semantics::Relaxed) {
+                        head.into_iter().for_each(drop);
+                    }
+
+                    elapsed
+                });
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, replace, take, map_owner);
+criterion_main!(benches);
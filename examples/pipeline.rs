@@ -0,0 +1,51 @@
+//! Worker threads that process jobs and report back over channels
+//!
+//! Each worker gets its job over a oneshot channel of its own, and all of them report their results
+//! over a single shared channel. Run with `cargo run --example pipeline`
+
+use ptr_cell::{channel, TakeError};
+
+/// Number of worker threads
+const WORKERS: u64 = 4;
+
+fn main() {
+    let (results, mut collected) = channel::channel();
+
+    let jobs: Vec<_> = (0..WORKERS)
+        .map(|worker| {
+            let (job, mut assigned) = channel::oneshot::<u64>();
+            let results = results.clone();
+
+            std::thread::spawn(move || loop {
+                match assigned.try_recv() {
+                    Ok(limit) => {
+                        let sum: u64 = (1..=limit).sum();
+                        let _ = results.send((worker, sum));
+                        break;
+                    }
+                    Err(TakeError::Empty) => std::thread::yield_now(),
+                    Err(TakeError::Closed) => break,
+                }
+            });
+
+            job
+        })
+        .collect();
+
+    // The workers hold the remaining senders, so the channel closes once they're all done
+    drop(results);
+
+    for (limit, job) in (1..).map(|step| step * 1000).zip(jobs) {
+        if job.send(limit).is_err() {
+            eprintln!("A worker has stopped early");
+        }
+    }
+
+    loop {
+        match collected.try_recv() {
+            Ok((worker, sum)) => println!("Worker {worker} summed up to {sum}"),
+            Err(TakeError::Empty) => std::thread::yield_now(),
+            Err(TakeError::Closed) => break,
+        }
+    }
+}
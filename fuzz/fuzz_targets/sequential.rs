@@ -0,0 +1,168 @@
+//! Runs random sequences of operations on a pair of cells and compares every result with a
+//! reference implementation built on plain options
+//!
+//! Values are boxed, so the sanitizer reports any allocation that's used after it's freed, freed
+//! twice, or leaked along the way
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use ptr_cell::{Pool, PtrCell, Semantics};
+
+/// Operation on one of the cells
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Set(Option<u16>),
+    Take,
+    Replace(Option<u16>),
+    SetReusing(u16),
+    ReplaceInPlace(u16),
+    TakePtrAndSetPtr,
+    Swap,
+    SwapShared,
+    TransferTo,
+    Rcu(u16),
+    SetPooled(Option<u16>),
+    TakePooled,
+    ReplacePooled(Option<u16>),
+    IsEmpty,
+}
+
+/// Operation along with the cell that it's performed on
+#[derive(Arbitrary, Debug)]
+struct Step {
+    op: Op,
+    on_second: bool,
+    order: Semantics,
+}
+
+/// Reference implementation of the cells
+#[derive(Debug, Default)]
+struct Reference {
+    cells: [Option<u16>; 2],
+}
+
+impl Reference {
+    /// Performs an operation and returns the value it hands back, if any
+    fn apply(&mut self, op: &Op, this: usize) -> Option<u16> {
+        let other = 1 - this;
+
+        match *op {
+            Op::Set(slot) | Op::SetPooled(slot) => {
+                self.cells[this] = slot;
+                None
+            }
+            Op::Take | Op::TakePooled => self.cells[this].take(),
+            Op::Replace(slot) | Op::ReplacePooled(slot) => {
+                core::mem::replace(&mut self.cells[this], slot)
+            }
+            Op::SetReusing(value) => {
+                self.cells[this] = Some(value);
+                None
+            }
+            Op::ReplaceInPlace(value) => self.cells[this].replace(value),
+            Op::TakePtrAndSetPtr | Op::IsEmpty => None,
+            Op::Swap | Op::SwapShared => {
+                self.cells.swap(0, 1);
+                None
+            }
+            Op::TransferTo => {
+                if self.cells[other].is_none() {
+                    self.cells[other] = self.cells[this].take();
+                }
+
+                None
+            }
+            Op::Rcu(addend) => {
+                let current = self.cells[this];
+                self.cells[this] = Some(current.map_or(addend, |value| value.wrapping_add(addend)));
+
+                current
+            }
+        }
+    }
+}
+
+fuzz_target!(|steps: Vec<Step>| {
+    let pool: Pool<Box<u16>, 4> = Pool::new();
+    let mut cells = [PtrCell::default(), PtrCell::default()];
+    let mut reference = Reference::default();
+
+    for Step {
+        op,
+        on_second,
+        order,
+    } in steps
+    {
+        let this = usize::from(on_second);
+        let other = 1 - this;
+        let expected_empty = reference.cells[this].is_none();
+        let expected_transfer = reference.cells[this].is_some() && reference.cells[other].is_none();
+
+        let cell = &cells[this];
+        let boxed = |slot: Option<u16>| slot.map(Box::new);
+
+        let returned = match op {
+            Op::Set(slot) => {
+                cell.set(boxed(slot), order);
+                None
+            }
+            Op::Take => cell.take(order),
+            Op::Replace(slot) => cell.replace(boxed(slot), order),
+            Op::SetReusing(value) => {
+                cell.set_reusing(Box::new(value), order);
+                None
+            }
+            Op::ReplaceInPlace(value) => cell.replace_in_place(Box::new(value), order),
+            Op::TakePtrAndSetPtr => {
+                let ptr = cell.take_ptr(order);
+                unsafe { cell.set_ptr(ptr, order) };
+                None
+            }
+            Op::Swap => {
+                let [first, second] = &mut cells;
+
+                if on_second {
+                    second.swap(first, order);
+                } else {
+                    first.swap(second, order);
+                }
+
+                None
+            }
+            Op::SwapShared => {
+                cell.swap_shared(&cells[other], order);
+                None
+            }
+            Op::TransferTo => {
+                assert_eq!(cell.transfer_to(&cells[other], order), expected_transfer);
+                None
+            }
+            Op::Rcu(addend) => cell.rcu(
+                |current| {
+                    let value = current.map_or(addend, |value| value.wrapping_add(addend));
+                    Some(Box::new(value))
+                },
+                order,
+            ),
+            Op::SetPooled(slot) => {
+                cell.set_pooled(boxed(slot), &pool, order);
+                None
+            }
+            Op::TakePooled => cell.take_pooled(&pool, order),
+            Op::ReplacePooled(slot) => cell.replace_pooled(boxed(slot), &pool, order),
+            Op::IsEmpty => {
+                assert_eq!(cell.is_empty(order), expected_empty);
+                None
+            }
+        };
+
+        let expected = reference.apply(&op, this);
+        assert_eq!(returned.map(|value| *value), expected, "{op:?}");
+    }
+
+    for (cell, expected) in cells.into_iter().zip(reference.cells) {
+        assert_eq!(cell.into_inner().map(|value| *value), expected);
+    }
+});
@@ -0,0 +1,170 @@
+//! Runs random sequences of operations on shared cells from several threads at once
+//!
+//! The interleavings can't be predicted, so the results are checked against a ledger instead: by
+//! the time everything is dropped, every value that was created must have been dropped exactly
+//! once. A value dropped twice or never at all means that the pointer protocol lost track of it
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use ptr_cell::{node::Node, Pool, PtrCell, Semantics};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Maximum number of threads
+const THREADS: usize = 4;
+
+/// Maximum number of operations per thread
+const STEPS: usize = 256;
+
+/// Operation on one of the shared cells
+///
+/// `transfer_to` is left out, as it keeps retrying for as long as both cells are occupied. Once
+/// the other threads are done, nothing would empty them
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Set,
+    Take,
+    Replace,
+    SetReusing,
+    ReplaceInPlace,
+    SwapShared,
+    SetPooled,
+    TakePooled,
+    ReplacePooled,
+    Push,
+    TakeList,
+}
+
+/// Operation along with the cell that it's performed on
+#[derive(Arbitrary, Debug)]
+struct Step {
+    op: Op,
+    on_second: bool,
+    order: Semantics,
+}
+
+/// Record of what happened to every value
+struct Ledger {
+    /// Whether the value with each ID was created
+    created: Vec<AtomicU8>,
+
+    /// How many times the value with each ID was dropped
+    dropped: Vec<AtomicU8>,
+}
+
+impl Ledger {
+    /// Constructs a ledger for `len` values
+    fn new(len: usize) -> Self {
+        let zeroed = || (0..len).map(|_| AtomicU8::new(0)).collect();
+
+        Self {
+            created: zeroed(),
+            dropped: zeroed(),
+        }
+    }
+
+    /// Creates the value with an ID
+    fn create(&self, id: usize) -> Tracked<'_> {
+        self.created[id].store(1, Ordering::Relaxed);
+
+        Tracked { id, ledger: self }
+    }
+
+    /// Checks that every value that was created was dropped exactly once
+    fn check(&self) {
+        for (id, (created, dropped)) in self.created.iter().zip(&self.dropped).enumerate() {
+            let created = created.load(Ordering::Relaxed);
+            let dropped = dropped.load(Ordering::Relaxed);
+
+            assert_eq!(created, dropped, "Value {id} was dropped {dropped} times");
+        }
+    }
+}
+
+/// Value that reports its drop to the ledger
+struct Tracked<'a> {
+    id: usize,
+    ledger: &'a Ledger,
+}
+
+impl Drop for Tracked<'_> {
+    fn drop(&mut self) {
+        self.ledger.dropped[self.id].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Performs the steps of one thread. Every step may create a value whose ID is `first_id` plus
+/// the step's index
+fn run<'a>(
+    steps: &[Step],
+    first_id: usize,
+    ledger: &'a Ledger,
+    cells: &[PtrCell<Tracked<'a>>; 2],
+    list: &PtrCell<Node<Tracked<'a>>>,
+    pool: &Pool<Tracked<'a>, 4>,
+) {
+    for (
+        index,
+        &Step {
+            ref op,
+            on_second,
+            order,
+        },
+    ) in steps.iter().enumerate()
+    {
+        let cell = &cells[usize::from(on_second)];
+        let other = &cells[usize::from(!on_second)];
+        let new = || ledger.create(first_id + index);
+
+        match op {
+            Op::Set => cell.set(Some(new()), order),
+            Op::Take => drop(cell.take(order)),
+            Op::Replace => drop(cell.replace(Some(new()), order)),
+            Op::SetReusing => cell.set_reusing(new(), order),
+            Op::ReplaceInPlace => drop(cell.replace_in_place(new(), order)),
+            Op::SwapShared => cell.swap_shared(other, order),
+            Op::SetPooled => cell.set_pooled(Some(new()), pool, order),
+            Op::TakePooled => drop(cell.take_pooled(pool, order)),
+            Op::ReplacePooled => drop(cell.replace_pooled(Some(new()), pool, order)),
+            Op::Push => Node::push(list, new(), order),
+            Op::TakeList => drop(list.take(order)),
+        }
+    }
+}
+
+fuzz_target!(|threads: Vec<Vec<Step>>| {
+    let threads: Vec<_> = threads
+        .iter()
+        .take(THREADS)
+        .map(|steps| &steps[..steps.len().min(STEPS)])
+        .collect();
+
+    let ledger = Ledger::new(threads.iter().map(|steps| steps.len()).sum());
+
+    {
+        let cells = [PtrCell::default(), PtrCell::default()];
+        let list = PtrCell::default();
+        let pool = Pool::new();
+
+        std::thread::scope(|scope| {
+            let mut first_id = 0;
+            let mut handles = Vec::with_capacity(threads.len());
+
+            for steps in &threads {
+                let (ledger, cells, list, pool) = (&ledger, &cells, &list, &pool);
+                handles.push(scope.spawn(move || run(steps, first_id, ledger, cells, list, pool)));
+
+                first_id += steps.len();
+            }
+
+            // Unlike the implicit join at the end of the scope, this waits until the threads have
+            // freed their own bookkeeping, which the leak checker would report otherwise
+            for handle in handles {
+                handle.join().expect("The thread shouldn't panic");
+            }
+        });
+    }
+
+    ledger.check();
+});
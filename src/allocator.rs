@@ -0,0 +1,260 @@
+//! Support for custom allocators through the unstable `allocator_api`
+//!
+//! Cells with a custom allocator only get the `_in` family of methods defined here: `take_in`,
+//! `set_in`, `replace_in`, and leaking and reclaiming through the allocator. Everything else,
+//! like `map_owner`, `rcu`, or the pointer API, is only implemented for cells that use the
+//! [`Global`](alloc::alloc::Global) allocator
+
+use crate::{non_null, AtomicPtr, PtrCell, Semantics};
+use alloc::{alloc::Allocator, boxed::Box};
+use core::panic::{RefUnwindSafe, UnwindSafe};
+
+// Same as for the global allocator, see the ownership section of the cell's docs. The allocator is
+// used by every thread that operates on the cell
+unsafe impl<T: Send, A: Allocator + Sync> Sync for PtrCell<T, A> {}
+
+// Values are only ever moved in and out whole, so a panic can't leave one half-modified in the cell
+impl<T: UnwindSafe, A: Allocator + UnwindSafe> UnwindSafe for PtrCell<T, A> {}
+
+impl<T: RefUnwindSafe, A: Allocator + RefUnwindSafe> RefUnwindSafe for PtrCell<T, A> {}
+
+// The value is kept on the heap, so moving the cell never moves the value
+impl<T, A: Allocator> Unpin for PtrCell<T, A> {}
+
+impl<T, A: Allocator> PtrCell<T, A> {
+    /// Takes out the value of a cell with a custom allocator
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// #![feature(allocator_api)]
+    ///
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    /// use std::alloc::System;
+    ///
+    /// let cell = PtrCell::new_in(Some(45), System);
+    ///
+    /// assert_eq!(cell.take_in(Relaxed), Some(45));
+    /// assert_eq!(cell.take_in(Relaxed), None)
+    /// ```
+    #[inline]
+    pub fn take_in(&self, order: Semantics) -> Option<T> {
+        self.replace_in(None, order)
+    }
+
+    /// Inserts a value into a cell with a custom allocator
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// #![feature(allocator_api)]
+    ///
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    /// use std::alloc::System;
+    ///
+    /// let cell = PtrCell::new_in(None, System);
+    /// cell.set_in(Some(1776), Relaxed);
+    ///
+    /// assert_eq!(cell.take_in(Relaxed), Some(1776))
+    /// ```
+    #[inline]
+    pub fn set_in(&self, slot: Option<T>, order: Semantics) {
+        let _ = self.replace_in(slot, order);
+    }
+
+    /// Replaces the value of a cell with a custom allocator
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// #![feature(allocator_api)]
+    ///
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    /// use std::alloc::System;
+    ///
+    /// let cell = PtrCell::new_in(Some('a'), System);
+    ///
+    /// assert_eq!(cell.replace_in(Some('b'), Relaxed), Some('a'));
+    /// assert_eq!(cell.take_in(Relaxed), Some('b'))
+    /// ```
+    #[inline]
+    #[must_use = "use `.set_in()` if you don't need the old value"]
+    pub fn replace_in(&self, slot: Option<T>, order: Semantics) -> Option<T> {
+        let new_leak = Self::heap_leak_in(slot, &self.alloc);
+
+        unsafe {
+            let old_leak = self.value.swap(new_leak, order.read_write());
+            Self::heap_reclaim_in(old_leak, &self.alloc)
+        }
+    }
+
+    /// Returns a reference to the cell's allocator
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// #![feature(allocator_api)]
+    ///
+    /// use ptr_cell::PtrCell;
+    /// use std::alloc::System;
+    ///
+    /// let cell = PtrCell::<u8, _>::new_in(None, System);
+    ///
+    /// let _: &System = cell.allocator();
+    /// ```
+    #[inline]
+    pub const fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Constructs a cell that allocates its values with `alloc`
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// #![feature(allocator_api)]
+    ///
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    /// use std::alloc::System;
+    ///
+    /// let cell = PtrCell::new_in(Some(0xFAA), System);
+    ///
+    /// assert_eq!(cell.take_in(Relaxed), Some(0xFAA))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new_in(slot: Option<T>, alloc: A) -> Self {
+        let ptr = Self::heap_leak_in(slot, &alloc);
+
+        unsafe { Self::from_ptr_in(ptr, alloc) }
+    }
+
+    /// Constructs a cell that owns memory [leaked](Self::heap_leak_in) through `alloc`
+    ///
+    /// A null pointer represents [`None`]
+    ///
+    /// # Safety
+    ///
+    /// The memory must have been allocated by `alloc` with the [memory layout][1] used by [`Box`]
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// #![feature(allocator_api)]
+    ///
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    /// use std::alloc::System;
+    ///
+    /// let ptr = PtrCell::heap_leak_in(Some(0xFAA), &System);
+    /// let cell = unsafe { PtrCell::from_ptr_in(ptr, System) };
+    ///
+    /// assert_eq!(cell.take_in(Relaxed), Some(0xFAA))
+    /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    #[inline]
+    pub const unsafe fn from_ptr_in(ptr: *mut T, alloc: A) -> Self {
+        let value = AtomicPtr::new(ptr);
+
+        Self {
+            value,
+            alloc,
+            owns: core::marker::PhantomData,
+        }
+    }
+
+    /// Reclaims ownership of memory [leaked](Self::heap_leak_in) through `alloc`
+    ///
+    /// A null pointer represents [`None`]
+    ///
+    /// # Safety
+    ///
+    /// The memory must have been allocated by `alloc` with the [memory layout][1] used by [`Box`]
+    ///
+    /// Dereferencing `ptr` after this function has been called may cause undefined behavior
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// #![feature(allocator_api)]
+    ///
+    /// use ptr_cell::PtrCell;
+    /// use std::alloc::System;
+    ///
+    /// let ptr = PtrCell::<_, System>::heap_leak_in(Some(1155), &System);
+    ///
+    /// assert_eq!(unsafe { PtrCell::<_, System>::heap_reclaim_in(ptr, &System) }, Some(1155))
+    /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    #[inline]
+    pub unsafe fn heap_reclaim_in(ptr: *mut T, alloc: &A) -> Option<T> {
+        #[cfg(feature = "debug-track")]
+        crate::track::forget(ptr);
+
+        #[cfg(feature = "stats")]
+        crate::stats::deallocated(ptr);
+
+        non_null(ptr).map(|ptr| *unsafe { Box::from_raw_in(ptr, alloc) })
+    }
+
+    /// Leaks a value to memory allocated by `alloc`
+    ///
+    /// [`None`] is represented by a null pointer
+    ///
+    /// The memory will conform to the [memory layout][1] used by [`Box`]
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// #![feature(allocator_api)]
+    ///
+    /// use ptr_cell::PtrCell;
+    /// use std::alloc::System;
+    ///
+    /// let ptr = PtrCell::<_, System>::heap_leak_in(Some(1155), &System);
+    ///
+    /// assert_eq!(unsafe { PtrCell::<_, System>::heap_reclaim_in(ptr, &System) }, Some(1155))
+    /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    #[inline]
+    #[must_use]
+    pub fn heap_leak_in(slot: Option<T>, alloc: &A) -> *mut T {
+        match slot {
+            Some(value) => {
+                let ptr = Box::into_raw_with_allocator(Box::new_in(value, alloc)).0;
+
+                #[cfg(feature = "debug-track")]
+                crate::track::record(ptr);
+
+                #[cfg(feature = "stats")]
+                crate::stats::allocated(ptr);
+
+                ptr
+            }
+            None => core::ptr::null_mut(),
+        }
+    }
+}
+
+#[cfg(not(feature = "may_dangle"))]
+impl<T, A: Allocator> Drop for PtrCell<T, A> {
+    #[inline]
+    fn drop(&mut self) {
+        let ptr = *self.value.get_mut();
+
+        unsafe { Self::heap_reclaim_in(ptr, &self.alloc) };
+    }
+}
+
+// The allocator is used to drop the value, so it must still be alive
+#[cfg(feature = "may_dangle")]
+unsafe impl<#[may_dangle] T, A: Allocator> Drop for PtrCell<T, A> {
+    #[inline]
+    fn drop(&mut self) {
+        let ptr = *self.value.get_mut();
+
+        unsafe { Self::heap_reclaim_in(ptr, &self.alloc) };
+    }
+}
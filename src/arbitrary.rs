@@ -0,0 +1,158 @@
+//! Generation of arbitrary cells and semantics for property-based testing and fuzzing, enabled by
+//! the `arbitrary` and `proptest` features
+
+use crate::Semantics;
+use core::sync::atomic::Ordering;
+
+/// Orderings valid for read operations
+const LOADS: [Ordering; 3] = [Ordering::Relaxed, Ordering::Acquire, Ordering::SeqCst];
+
+/// Orderings valid for write operations
+const STORES: [Ordering; 3] = [Ordering::Relaxed, Ordering::Release, Ordering::SeqCst];
+
+/// Orderings valid for read-write operations
+const SWAPS: [Ordering; 5] = [
+    Ordering::Relaxed,
+    Ordering::Acquire,
+    Ordering::Release,
+    Ordering::AcqRel,
+    Ordering::SeqCst,
+];
+
+/// All presets, which are generated as often as custom semantics combined
+const PRESETS: [Semantics; 5] = Semantics::ALL;
+
+#[cfg(feature = "arbitrary")]
+mod fuzz {
+    use super::{LOADS, PRESETS, STORES, SWAPS};
+    use crate::Semantics;
+    use arbitrary::{Arbitrary, Result, Unstructured};
+
+    #[cfg(feature = "alloc")]
+    use crate::PtrCell;
+
+    #[cfg(feature = "alloc")]
+    impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for PtrCell<T> {
+        /// Generates an empty cell or one with an arbitrary value
+        ///
+        /// # Usage
+        ///
+        /// ```rust
+        /// use arbitrary::{Arbitrary, Unstructured};
+        /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+        ///
+        /// let mut input = Unstructured::new(&[1, 2, 3, 4, 5]);
+        /// let cell = PtrCell::<u16>::arbitrary(&mut input)?;
+        ///
+        /// assert!(cell.take(Relaxed).is_some());
+        /// # Ok::<(), arbitrary::Error>(())
+        /// ```
+        #[inline]
+        fn arbitrary(input: &mut Unstructured<'a>) -> Result<Self> {
+            Option::arbitrary(input).map(Self::from)
+        }
+
+        #[inline]
+        fn size_hint(depth: usize) -> (usize, Option<usize>) {
+            Option::<T>::size_hint(depth)
+        }
+    }
+
+    impl<'a> Arbitrary<'a> for Semantics {
+        /// Generates a preset or custom semantics with valid orderings
+        ///
+        /// # Usage
+        ///
+        /// ```rust
+        /// use arbitrary::{Arbitrary, Unstructured};
+        /// use ptr_cell::{PtrCell, Semantics};
+        ///
+        /// let mut input = Unstructured::new(&[7, 0, 4, 2]);
+        /// let order = Semantics::arbitrary(&mut input)?;
+        ///
+        /// // Never panics, whatever the semantics
+        /// assert_eq!(PtrCell::from(1).take(order), Some(1));
+        /// # Ok::<(), arbitrary::Error>(())
+        /// ```
+        fn arbitrary(input: &mut Unstructured<'a>) -> Result<Self> {
+            if input.arbitrary()? {
+                return input.choose(&PRESETS).copied();
+            }
+
+            let load = *input.choose(&LOADS)?;
+            let store = *input.choose(&STORES)?;
+            let swap = *input.choose(&SWAPS)?;
+
+            Ok(Self::custom(load, store, swap))
+        }
+    }
+}
+
+#[cfg(feature = "proptest")]
+mod strategy {
+    use super::{LOADS, PRESETS, STORES, SWAPS};
+    use crate::Semantics;
+    use proptest::{
+        arbitrary::Arbitrary,
+        prop_oneof,
+        sample::select,
+        strategy::{BoxedStrategy, Strategy},
+    };
+
+    #[cfg(feature = "alloc")]
+    use {crate::PtrCell, proptest::strategy::Map};
+
+    #[cfg(feature = "alloc")]
+    impl<T: Arbitrary> Arbitrary for PtrCell<T> {
+        type Parameters = <Option<T> as Arbitrary>::Parameters;
+        type Strategy = Map<<Option<T> as Arbitrary>::Strategy, fn(Option<T>) -> Self>;
+
+        /// Generates empty cells and cells with arbitrary values
+        ///
+        /// # Usage
+        ///
+        /// ```rust
+        /// use proptest::{prelude::*, test_runner::TestRunner};
+        /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+        ///
+        /// TestRunner::default()
+        ///     .run(&any::<PtrCell<u8>>(), |cell| {
+        ///         let _ = cell.take(Relaxed);
+        ///         prop_assert!(cell.is_empty(Relaxed));
+        ///         Ok(())
+        ///     })
+        ///     .expect("The property should hold")
+        /// ```
+        #[inline]
+        fn arbitrary_with(parameters: Self::Parameters) -> Self::Strategy {
+            Option::<T>::arbitrary_with(parameters).prop_map(Self::from)
+        }
+    }
+
+    impl Arbitrary for Semantics {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        /// Generates presets and custom semantics with valid orderings
+        ///
+        /// # Usage
+        ///
+        /// ```rust
+        /// use proptest::{prelude::*, test_runner::TestRunner};
+        /// use ptr_cell::{PtrCell, Semantics};
+        ///
+        /// TestRunner::default()
+        ///     .run(&any::<Semantics>(), |order| {
+        ///         prop_assert_eq!(PtrCell::from(1).take(order), Some(1));
+        ///         Ok(())
+        ///     })
+        ///     .expect("No semantics should make the operations panic")
+        /// ```
+        fn arbitrary_with((): ()) -> BoxedStrategy<Self> {
+            let custom = (select(&LOADS[..]), select(&STORES[..]), select(&SWAPS[..]))
+                .prop_map(|(load, store, swap)| Semantics::custom(load, store, swap));
+
+            prop_oneof![select(&PRESETS[..]), custom].boxed()
+        }
+    }
+}
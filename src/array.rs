@@ -0,0 +1,214 @@
+//! Fixed-size arrays of independent cells
+
+use crate::{CachePadded, PtrCell, Semantics};
+
+/// Fixed-size array of `N` independent [`PtrCell`]s
+///
+/// Every cell occupies its own cache line, so threads working on different cells don't slow each
+/// other down through [false sharing][1]. This makes the type a good fit for per-thread slots
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{PtrCellArray, Semantics::Relaxed};
+///
+/// let slots: PtrCellArray<&str, 4> = PtrCellArray::new();
+///
+/// slots.set(2, Some("job"), Relaxed);
+///
+/// assert_eq!(slots.first_non_empty(Relaxed), Some(2));
+/// assert_eq!(slots.take(2, Relaxed), Some("job"));
+/// assert_eq!(slots.first_non_empty(Relaxed), None)
+/// ```
+///
+/// [1]: https://en.wikipedia.org/wiki/False_sharing
+pub struct PtrCellArray<T, const N: usize> {
+    /// The cells, each padded to a cache line
+    slots: [CachePadded<PtrCell<T>>; N],
+}
+
+impl<T, const N: usize> PtrCellArray<T, N> {
+    /// Takes out the values of all cells in order, skipping empty ones
+    ///
+    /// Each cell is emptied only when the iterator reaches it
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCellArray, Semantics::Relaxed};
+    ///
+    /// let slots: PtrCellArray<u8, 3> = [Some(1), None, Some(3)].into();
+    ///
+    /// assert!(slots.drain(Relaxed).eq([1, 3]));
+    /// assert_eq!(slots.first_non_empty(Relaxed), None)
+    /// ```
+    #[inline]
+    pub fn drain(&self, order: Semantics) -> impl Iterator<Item = T> + '_ {
+        self.iter().filter_map(move |cell| cell.take(order))
+    }
+
+    /// Returns the index of the first cell that isn't empty
+    ///
+    /// The cells are inspected one by one, so the result may already be outdated when other threads
+    /// are modifying the array
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCellArray, Semantics::Relaxed};
+    ///
+    /// let slots: PtrCellArray<u8, 3> = [None, Some(2), Some(3)].into();
+    ///
+    /// assert_eq!(slots.first_non_empty(Relaxed), Some(1))
+    /// ```
+    #[inline]
+    pub fn first_non_empty(&self, order: Semantics) -> Option<usize> {
+        self.iter().position(|cell| !cell.is_empty(order))
+    }
+
+    /// Takes out the value of the cell at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCellArray, Semantics::Relaxed};
+    ///
+    /// let slots: PtrCellArray<u8, 2> = [Some(45), None].into();
+    ///
+    /// assert_eq!(slots.take(0, Relaxed), Some(45));
+    /// assert_eq!(slots.take(0, Relaxed), None)
+    /// ```
+    #[inline]
+    pub fn take(&self, index: usize, order: Semantics) -> Option<T> {
+        self[index].take(order)
+    }
+
+    /// Inserts a value into the cell at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCellArray, Semantics::Relaxed};
+    ///
+    /// let slots: PtrCellArray<u16, 2> = PtrCellArray::new();
+    /// slots.set(1, Some(1776), Relaxed);
+    ///
+    /// assert_eq!(slots.take(1, Relaxed), Some(1776))
+    /// ```
+    #[inline]
+    pub fn set(&self, index: usize, slot: Option<T>, order: Semantics) {
+        self[index].set(slot, order);
+    }
+
+    /// Replaces the value of the cell at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCellArray, Semantics::Relaxed};
+    ///
+    /// let slots: PtrCellArray<char, 2> = [Some('a'), None].into();
+    ///
+    /// assert_eq!(slots.replace(0, Some('b'), Relaxed), Some('a'));
+    /// assert_eq!(slots.take(0, Relaxed), Some('b'))
+    /// ```
+    #[inline]
+    #[must_use = "use `.set()` if you don't need the old value"]
+    pub fn replace(&self, index: usize, slot: Option<T>, order: Semantics) -> Option<T> {
+        self[index].replace(slot, order)
+    }
+
+    /// Returns an iterator over the cells
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCellArray, Semantics::Relaxed};
+    ///
+    /// let slots: PtrCellArray<u8, 3> = [Some(1), None, Some(3)].into();
+    ///
+    /// assert_eq!(slots.iter().filter(|cell| cell.is_empty(Relaxed)).count(), 1)
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &PtrCell<T>> + ExactSizeIterator {
+        self.slots.iter().map(|slot| &**slot)
+    }
+
+    /// Returns a reference to the cell at `index`, or [`None`] if it's out of bounds
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCellArray, Semantics::Relaxed};
+    ///
+    /// let slots: PtrCellArray<u8, 2> = PtrCellArray::new();
+    ///
+    /// assert!(slots.get(1).is_some());
+    /// assert!(slots.get(2).is_none())
+    /// ```
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&PtrCell<T>> {
+        self.slots.get(index).map(|slot| &**slot)
+    }
+
+    /// Constructs an array of empty cells
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCellArray, Semantics::Relaxed};
+    ///
+    /// let slots: PtrCellArray<u8, 8> = PtrCellArray::new();
+    ///
+    /// assert_eq!(slots.first_non_empty(Relaxed), None)
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::from([(); N].map(|()| None))
+    }
+}
+
+impl<T, const N: usize> core::fmt::Debug for PtrCellArray<T, N> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> Default for PtrCellArray<T, N> {
+    /// Constructs an array of empty cells
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> From<[Option<T>; N]> for PtrCellArray<T, N> {
+    #[inline]
+    fn from(slots: [Option<T>; N]) -> Self {
+        let slots = slots.map(|slot| CachePadded::new(PtrCell::new(slot)));
+
+        Self { slots }
+    }
+}
+
+impl<T, const N: usize> core::ops::Index<usize> for PtrCellArray<T, N> {
+    type Output = PtrCell<T>;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.slots[index]
+    }
+}
@@ -0,0 +1,211 @@
+//! Drop-in replacement for the unmaintained [`atomic-option`][1] crate
+//!
+//! [`AtomicOption`] mirrors that crate's API on top of [`PtrCell`], so switching over takes a
+//! single changed import:
+//!
+#![doc = doctest!()]
+//! // use atomic_option::AtomicOption;
+//! use ptr_cell::atomic_option::AtomicOption;
+//! use std::sync::atomic::Ordering;
+//!
+//! let option = AtomicOption::new(Box::new(1));
+//!
+//! assert_eq!(option.swap(Box::new(2), Ordering::AcqRel), Some(Box::new(1)));
+//! assert_eq!(option.take(Ordering::Acquire), Some(Box::new(2)))
+//! ```
+//!
+//! Values go in and out as boxes and keep their allocation, exactly like in the original crate.
+//! Each [`Ordering`] is mapped to the [`Semantics`] preset whose
+//! [read-write](Semantics::read_write) ordering it is, see [`Semantics::try_from`]. This gives the
+//! same orderings as before:
+//!
+//! | [`Ordering`] | Exchanges | Failed [`try_store`](AtomicOption::try_store)s |
+//! | ------------ | --------- | ---------------------------------------------- |
+//! | `Relaxed`    | `Relaxed` | `Relaxed`                                      |
+//! | `Acquire`    | `Acquire` | `Acquire`                                      |
+//! | `Release`    | `Release` | `Relaxed`                                      |
+//! | `AcqRel`     | `AcqRel`  | `Acquire`                                      |
+//! | `SeqCst`     | `SeqCst`  | `SeqCst`                                       |
+//!
+//! [1]: https://docs.rs/atomic-option/0.1
+
+use crate::{PtrCell, Semantics};
+use alloc::boxed::Box;
+use core::sync::atomic::Ordering;
+
+/// An atomic [`Option<Box<T>>`], with the API of `atomic_option::AtomicOption`
+///
+/// A thin wrapper around a [`PtrCell`]. See the [module docs](self) for how the orderings map
+pub struct AtomicOption<T> {
+    /// The cell
+    cell: PtrCell<T>,
+}
+
+impl<T> AtomicOption<T> {
+    /// Constructs an option holding `data`
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::atomic_option::AtomicOption;
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// let option = AtomicOption::new(Box::new("data"));
+    ///
+    /// assert_eq!(option.take(Relaxed).as_deref(), Some(&"data"))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(data: Box<T>) -> Self {
+        Self { cell: data.into() }
+    }
+
+    /// Constructs an empty option
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::atomic_option::AtomicOption;
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// let option = AtomicOption::<u8>::empty();
+    ///
+    /// assert!(option.take(Relaxed).is_none())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self {
+            cell: PtrCell::empty(),
+        }
+    }
+
+    /// Takes out the value, leaving the option empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::atomic_option::AtomicOption;
+    /// use std::sync::atomic::Ordering::Acquire;
+    ///
+    /// let option = AtomicOption::new(Box::new(7));
+    ///
+    /// assert_eq!(option.take(Acquire), Some(Box::new(7)));
+    /// assert_eq!(option.take(Acquire), None)
+    /// ```
+    #[inline]
+    pub fn take(&self, order: Ordering) -> Option<Box<T>> {
+        self.replace(None, order)
+    }
+
+    /// Stores `new`, returning the previous value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::atomic_option::AtomicOption;
+    /// use std::sync::atomic::Ordering::AcqRel;
+    ///
+    /// let option = AtomicOption::empty();
+    ///
+    /// assert_eq!(option.swap(Box::new('a'), AcqRel), None);
+    /// assert_eq!(option.swap(Box::new('b'), AcqRel), Some(Box::new('a')))
+    /// ```
+    #[inline]
+    pub fn swap(&self, new: Box<T>, order: Ordering) -> Option<Box<T>> {
+        self.replace(Some(new), order)
+    }
+
+    /// Stores `new`, returning the previous value. [`None`] empties the option
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::atomic_option::AtomicOption;
+    /// use std::sync::atomic::Ordering::SeqCst;
+    ///
+    /// let option = AtomicOption::new(Box::new(1));
+    ///
+    /// assert_eq!(option.replace(None, SeqCst), Some(Box::new(1)));
+    /// assert_eq!(option.replace(Some(Box::new(2)), SeqCst), None)
+    /// ```
+    pub fn replace(&self, new: Option<Box<T>>, order: Ordering) -> Option<Box<T>> {
+        let new_ptr = new.map_or(core::ptr::null_mut(), |boxed| PtrCell::from(boxed).leak());
+
+        let old_ptr = self.cell.replace_ptr(new_ptr, semantics(order));
+
+        unsafe { crate::into_box(old_ptr) }
+    }
+
+    /// Stores `new` only if the option is empty
+    ///
+    /// Returns [`None`] on success, or hands `new` back if the option already held a value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::atomic_option::AtomicOption;
+    /// use std::sync::atomic::Ordering::AcqRel;
+    ///
+    /// let option = AtomicOption::empty();
+    ///
+    /// assert_eq!(option.try_store(Box::new(1), AcqRel), None);
+    /// assert_eq!(option.try_store(Box::new(2), AcqRel), Some(Box::new(2)));
+    /// assert_eq!(option.take(AcqRel), Some(Box::new(1)))
+    /// ```
+    pub fn try_store(&self, new: Box<T>, order: Ordering) -> Option<Box<T>> {
+        let order = semantics(order);
+        let new_ptr = PtrCell::from(new).leak();
+
+        let storing = self.cell.value.compare_exchange(
+            core::ptr::null_mut(),
+            new_ptr,
+            order.read_write(),
+            order.read(),
+        );
+
+        match storing {
+            Ok(_) => None,
+            Err(_) => unsafe { crate::into_box(new_ptr) },
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for AtomicOption<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("AtomicOption")
+            .field("cell", &self.cell)
+            .finish()
+    }
+}
+
+impl<T> Default for AtomicOption<T> {
+    /// Constructs an empty option
+    #[inline]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T> From<PtrCell<T>> for AtomicOption<T> {
+    #[inline]
+    fn from(cell: PtrCell<T>) -> Self {
+        Self { cell }
+    }
+}
+
+impl<T> From<AtomicOption<T>> for PtrCell<T> {
+    #[inline]
+    fn from(option: AtomicOption<T>) -> Self {
+        option.cell
+    }
+}
+
+/// Returns the preset matching an ordering of the original crate
+///
+/// Orderings that may be added to the language in the future fall back to the strongest preset
+#[inline]
+fn semantics(order: Ordering) -> Semantics {
+    Semantics::try_from(order).unwrap_or(Semantics::Ordered)
+}
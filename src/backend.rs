@@ -0,0 +1,77 @@
+//! Reporting of the atomics backend
+
+/// Implementation of atomics that the cells are built on
+///
+/// It's selected by the enabled features and the target. Libraries building on the cells can
+/// check it to pick algorithms or assert guarantees
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::Backend;
+///
+/// match Backend::CURRENT {
+///     Backend::CriticalSection => println!("Keep the cell's operations out of hot loops"),
+///     backend => println!("Using {backend:?} atomics"),
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// Atomics of [`core`]
+    Native,
+
+    /// Atomics of the `portable-atomic` crate, enabled by the `portable-atomic` feature
+    PortableAtomic,
+
+    /// Plain values accessed inside `critical_section::with`, enabled by the `critical-section`
+    /// feature
+    CriticalSection,
+
+    /// Plain loads and stores, used on `wasm32` without the `atomics` target feature
+    SingleThreaded,
+
+    /// Model atomics of the `shuttle` crate, enabled by the `shuttle` feature
+    Shuttle,
+}
+
+impl Backend {
+    /// Backend of the current build
+    pub const CURRENT: Self = crate::BACKEND;
+
+    /// Returns `true` if every operation of the backend is lock-free on the current target
+    ///
+    /// `portable-atomic` may only detect lock-free instructions at runtime, in which case this is
+    /// `false` for it. Critical sections are locks, so they're never lock-free. Without the
+    /// `portable-atomic` feature, the crate can't tell whether it would be lock-free and reports
+    /// `false` as well
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::Backend;
+    ///
+    /// assert!(Backend::Native.is_lock_free());
+    /// assert!(!Backend::CriticalSection.is_lock_free())
+    /// ```
+    #[must_use]
+    pub const fn is_lock_free(self) -> bool {
+        match self {
+            Self::Native | Self::SingleThreaded | Self::Shuttle => true,
+            Self::PortableAtomic => portable_lock_free(),
+            Self::CriticalSection => false,
+        }
+    }
+}
+
+/// Whether `portable-atomic` provides lock-free atomic pointers on the current target
+#[cfg(feature = "portable-atomic")]
+const fn portable_lock_free() -> bool {
+    portable_atomic::AtomicPtr::<()>::is_always_lock_free()
+}
+
+/// Whether `portable-atomic` provides lock-free atomic pointers on the current target
+#[cfg(not(feature = "portable-atomic"))]
+const fn portable_lock_free() -> bool {
+    false
+}
@@ -0,0 +1,113 @@
+//! Strategies for waiting between the attempts of contended operations
+//!
+//! Some operations, like [`map_owner`](crate::PtrCell::map_owner), retry until no other thread
+//! interferes with them. By default, they spin with [`Spin`]. Under heavy contention, it's often
+//! better to back off more gently, which can be configured through methods like
+//! [`map_owner_with_backoff`](crate::PtrCell::map_owner_with_backoff)
+//!
+//! Any `FnMut()` closure can also be used as a strategy
+
+/// Strategy for waiting between the attempts of a contended operation
+///
+/// A fresh strategy is used for every operation, so it may keep track of the failed attempts
+///
+/// # Usage
+///
+#[doc = alloc_doctest!()]
+/// use ptr_cell::{backoff::Backoff, node::Node, PtrCell, Semantics::Relaxed};
+///
+/// struct Count(u32);
+///
+/// impl Backoff for Count {
+///     fn backoff(&mut self) {
+///         self.0 += 1;
+///         core::hint::spin_loop()
+///     }
+/// }
+///
+/// let list = PtrCell::default();
+///
+/// list.map_owner_with_backoff(|next| Node { value: 1, next }, Relaxed, Count(0));
+/// ```
+pub trait Backoff {
+    /// Waits after a failed attempt
+    fn backoff(&mut self);
+}
+
+/// Busy-waits with a [spin loop hint](core::hint::spin_loop) after every failed attempt
+///
+/// This is the default strategy
+///
+/// # Usage
+///
+#[doc = alloc_doctest!()]
+/// use ptr_cell::{backoff::Spin, node::Node, PtrCell, Semantics::Relaxed};
+///
+/// let list = PtrCell::default();
+///
+/// list.map_owner_with_backoff(|next| Node { value: 1, next }, Relaxed, Spin);
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+pub struct Spin;
+
+impl Backoff for Spin {
+    #[inline]
+    fn backoff(&mut self) {
+        core::hint::spin_loop();
+    }
+}
+
+/// Spins for an exponentially growing number of iterations, then yields the thread to the OS
+///
+/// Suited for oversubscribed systems, where the thread that blocks progress may not be running
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{backoff::SpinThenYield, node::Node, PtrCell, Semantics::Relaxed};
+///
+/// let list = PtrCell::default();
+///
+/// list.map_owner_with_backoff(|next| Node { value: 1, next }, Relaxed, SpinThenYield::new());
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+pub struct SpinThenYield {
+    /// Number of failed attempts so far
+    step: u32,
+}
+
+#[cfg(feature = "std")]
+impl SpinThenYield {
+    /// Number of failed attempts after which the thread starts yielding
+    const YIELD_AFTER: u32 = 6;
+
+    /// Constructs the strategy for a new operation
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { step: 0 }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Backoff for SpinThenYield {
+    fn backoff(&mut self) {
+        if self.step < Self::YIELD_AFTER {
+            for _ in 0..1 << self.step {
+                core::hint::spin_loop();
+            }
+
+            self.step += 1;
+        } else {
+            std::thread::yield_now();
+        }
+    }
+}
+
+impl<F: FnMut()> Backoff for F {
+    #[inline]
+    fn backoff(&mut self) {
+        self();
+    }
+}
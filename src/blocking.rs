@@ -0,0 +1,74 @@
+//! Blocking operations that wait for a value to be set
+
+use crate::{
+    backoff::{Backoff, SpinThenYield},
+    PtrCell, Semantics,
+};
+use std::time::{Duration, Instant};
+
+/// Longest time that a waiting thread sleeps between two attempts
+const MAX_NAP: Duration = Duration::from_millis(1);
+
+/// Time that a waiting thread sleeps after spinning, before the naps start doubling
+const FIRST_NAP: Duration = Duration::from_micros(10);
+
+/// Number of attempts made while spinning, before the thread starts sleeping
+const SPIN_ATTEMPTS: u32 = 8;
+
+impl<T> PtrCell<T> {
+    /// Takes out the cell's value, waiting up to `timeout` for one to be set
+    ///
+    /// Returns [`None`] if the cell stayed empty until the timeout expired
+    ///
+    /// Setting a value doesn't notify anyone, so the thread polls the cell. It spins briefly at
+    /// first and then sleeps between attempts, for at most a millisecond at a time. A value may
+    /// thus be picked up up to a millisecond after it's set
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    /// use std::time::Duration;
+    ///
+    /// let heartbeat = PtrCell::default();
+    ///
+    /// assert_eq!(heartbeat.take_timeout(Duration::from_millis(5), Coupled), None);
+    ///
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| heartbeat.set(Some("alive"), Coupled));
+    ///
+    ///     assert_eq!(heartbeat.take_timeout(Duration::from_secs(5), Coupled), Some("alive"))
+    /// })
+    /// ```
+    pub fn take_timeout(&self, timeout: Duration, order: Semantics) -> Option<T> {
+        // Timeouts too long to represent never expire
+        let deadline = Instant::now().checked_add(timeout);
+
+        let mut spin = SpinThenYield::new();
+        let mut spins = 0;
+        let mut nap = FIRST_NAP;
+
+        loop {
+            if let Some(value) = self.take(order) {
+                return Some(value);
+            }
+
+            let remaining = match deadline {
+                Some(deadline) => deadline.checked_duration_since(Instant::now())?,
+                None => MAX_NAP,
+            };
+
+            if remaining.is_zero() {
+                return None;
+            }
+
+            if spins < SPIN_ATTEMPTS {
+                spin.backoff();
+                spins += 1;
+            } else {
+                std::thread::park_timeout(nap.min(remaining));
+                nap = (nap * 2).min(MAX_NAP);
+            }
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! Fan-out of the latest value to a fixed number of readers
+//!
+//! A [`Broadcast`] keeps a [`PtrCell`] mailbox for each of up to `N` registered [`Reader`]s.
+//! Publishing a value replaces the contents of every registered mailbox with a clone of it, so each
+//! reader takes the newest value at most once, and a reader that falls behind only ever sees the
+//! newest one. Readers register and unregister themselves, and nothing is allocated besides the
+//! values
+//!
+//! Unlike the [`watch`](crate::watch) channel, this works without `std` and never blocks, but
+//! readers can't wait for values
+//!
+//! # Usage
+//!
+#![doc = doctest!()]
+//! use ptr_cell::{broadcast::Broadcast, Semantics::Coupled};
+//!
+//! let status: Broadcast<&str, 4> = Broadcast::new();
+//!
+//! let first = status.subscribe().expect("There should be room for a reader");
+//! let second = status.subscribe().expect("There should be room for a reader");
+//!
+//! status.publish("loading", Coupled);
+//! status.publish("ready", Coupled);
+//!
+//! assert_eq!(first.take(Coupled), Some("ready"));
+//! assert_eq!(first.take(Coupled), None);
+//! assert_eq!(second.take(Coupled), Some("ready"))
+//! ```
+
+use crate::{AtomicU8, PtrCell, Semantics};
+use core::sync::atomic::Ordering;
+
+/// The mailbox has no reader
+const VACANT: u8 = 0;
+
+/// The mailbox belongs to a reader
+const REGISTERED: u8 = 1;
+
+/// Publisher of the latest value to up to `N` readers
+///
+/// See the [module documentation](self) for an overview
+pub struct Broadcast<T, const N: usize> {
+    /// Mailbox of each reader. Mailboxes without a reader are kept empty
+    mailboxes: [PtrCell<T>; N],
+
+    /// Whether each mailbox is [`VACANT`] or [`REGISTERED`]
+    registered: [AtomicU8; N],
+}
+
+impl<T, const N: usize> Broadcast<T, N> {
+    /// Mailbox without a value
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY: PtrCell<T> = PtrCell::empty();
+
+    /// Mailbox without a reader
+    #[allow(clippy::declare_interior_mutable_const)]
+    const UNREGISTERED: AtomicU8 = AtomicU8::new(VACANT);
+
+    /// Registers a new reader
+    ///
+    /// The reader only sees values published after it was registered, and possibly one published
+    /// at the same time. Returns [`None`] if all `N` readers are registered
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::broadcast::Broadcast;
+    ///
+    /// let broadcast: Broadcast<u8, 1> = Broadcast::new();
+    ///
+    /// let reader = broadcast.subscribe();
+    /// assert!(reader.is_some());
+    /// assert!(broadcast.subscribe().is_none());
+    ///
+    /// drop(reader);
+    /// assert!(broadcast.subscribe().is_some())
+    /// ```
+    pub fn subscribe(&self) -> Option<Reader<'_, T, N>> {
+        let index = self.registered.iter().position(|registered| {
+            registered
+                .compare_exchange(VACANT, REGISTERED, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        })?;
+
+        // A value published to the previous reader may have arrived after it unregistered
+        drop(self.mailboxes[index].take(Semantics::Relaxed));
+
+        Some(Reader {
+            broadcast: self,
+            index,
+        })
+    }
+
+    /// Replaces the value in the mailbox of every registered reader with a clone of `value`
+    ///
+    /// The original value goes to the last reader, and is dropped if there are no readers
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{broadcast::Broadcast, Semantics::Relaxed};
+    ///
+    /// let broadcast: Broadcast<String, 2> = Broadcast::new();
+    /// let reader = broadcast.subscribe().expect("There should be room for a reader");
+    ///
+    /// broadcast.publish("stale".into(), Relaxed);
+    /// broadcast.publish("fresh".into(), Relaxed);
+    ///
+    /// assert_eq!(reader.take(Relaxed).as_deref(), Some("fresh"))
+    /// ```
+    pub fn publish(&self, value: T, order: Semantics)
+    where
+        T: Clone,
+    {
+        let mut registered = self
+            .mailboxes
+            .iter()
+            .zip(&self.registered)
+            .filter(|(_, registered)| registered.load(Ordering::Acquire) == REGISTERED)
+            .map(|(mailbox, _)| mailbox)
+            .peekable();
+
+        while let Some(mailbox) = registered.next() {
+            if registered.peek().is_some() {
+                mailbox.set(Some(value.clone()), order);
+            } else {
+                mailbox.set(Some(value), order);
+                break;
+            }
+        }
+    }
+
+    /// Returns the number of registered readers
+    ///
+    /// The result may be outdated if readers are registering or unregistering at the same time
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::broadcast::Broadcast;
+    ///
+    /// let broadcast: Broadcast<u8, 4> = Broadcast::new();
+    /// let _reader = broadcast.subscribe();
+    ///
+    /// assert_eq!(broadcast.readers(), 1)
+    /// ```
+    pub fn readers(&self) -> usize {
+        self.registered
+            .iter()
+            .filter(|registered| registered.load(Ordering::Relaxed) == REGISTERED)
+            .count()
+    }
+
+    /// Constructs a broadcast without readers
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::broadcast::Broadcast;
+    ///
+    /// static CONFIG: Broadcast<&str, 8> = Broadcast::new();
+    ///
+    /// assert_eq!(CONFIG.readers(), 0)
+    /// ```
+    #[must_use]
+    #[allow(clippy::borrow_interior_mutable_const)]
+    pub const fn new() -> Self {
+        Self {
+            mailboxes: [Self::EMPTY; N],
+            registered: [Self::UNREGISTERED; N],
+        }
+    }
+}
+
+impl<T, const N: usize> core::fmt::Debug for Broadcast<T, N> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("Broadcast")
+            .field("readers", &self.readers())
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+impl<T, const N: usize> Default for Broadcast<T, N> {
+    /// Constructs a broadcast without readers
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Registered reader of a [`Broadcast`]
+///
+/// Dropping it unregisters the reader and frees its mailbox for another one
+///
+/// Created by [`Broadcast::subscribe`]
+pub struct Reader<'a, T, const N: usize> {
+    /// The broadcast
+    broadcast: &'a Broadcast<T, N>,
+
+    /// Index of the reader's mailbox
+    index: usize,
+}
+
+impl<T, const N: usize> Reader<'_, T, N> {
+    /// Takes out the newest value published since the last take
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{broadcast::Broadcast, Semantics::Relaxed};
+    ///
+    /// let broadcast: Broadcast<u8, 1> = Broadcast::new();
+    /// let reader = broadcast.subscribe().expect("There should be room for a reader");
+    ///
+    /// broadcast.publish(1, Relaxed);
+    ///
+    /// assert_eq!(reader.take(Relaxed), Some(1));
+    /// assert_eq!(reader.take(Relaxed), None)
+    /// ```
+    #[inline]
+    pub fn take(&self, order: Semantics) -> Option<T> {
+        self.mailbox().take(order)
+    }
+
+    /// Determines whether there's no new value for the reader
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{broadcast::Broadcast, Semantics::Relaxed};
+    ///
+    /// let broadcast: Broadcast<u8, 1> = Broadcast::new();
+    /// let reader = broadcast.subscribe().expect("There should be room for a reader");
+    ///
+    /// assert!(reader.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_empty(&self, order: Semantics) -> bool {
+        self.mailbox().is_empty(order)
+    }
+
+    /// Returns the reader's mailbox
+    #[inline]
+    const fn mailbox(&self) -> &PtrCell<T> {
+        &self.broadcast.mailboxes[self.index]
+    }
+}
+
+impl<T, const N: usize> core::fmt::Debug for Reader<'_, T, N> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("Reader")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, const N: usize> Drop for Reader<'_, T, N> {
+    fn drop(&mut self) {
+        drop(self.mailbox().take(Semantics::Relaxed));
+
+        self.broadcast.registered[self.index].store(VACANT, Ordering::Release);
+    }
+}
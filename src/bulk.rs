@@ -0,0 +1,60 @@
+//! Operations over multiple cells at once
+
+use crate::{PtrCell, Semantics};
+
+impl<T> PtrCell<T> {
+    /// Returns an iterator that takes out the values of `cells` one by one
+    ///
+    /// Each take is atomic on its own, but the group isn't. Other threads may modify the cells that
+    /// haven't been reached yet. Cells are only taken from as the iterator advances
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cells: [PtrCell<u8>; 3] = [1.into(), PtrCell::default(), 3.into()];
+    ///
+    /// assert!(PtrCell::take_all(&cells, Relaxed).eq([Some(1), None, Some(3)]));
+    /// assert!(cells.iter().all(|cell| cell.is_empty(Relaxed)))
+    /// ```
+    pub fn take_all<'a>(
+        cells: impl IntoIterator<Item = &'a Self> + 'a,
+        order: Semantics,
+    ) -> impl Iterator<Item = Option<T>> + 'a
+    where
+        T: 'a,
+    {
+        cells.into_iter().map(move |cell| cell.take(order))
+    }
+
+    /// Inserts each of `slots` into the matching cell of `cells`
+    ///
+    /// The cells and slots are paired up in order. Extra items on either side are ignored
+    ///
+    /// Each insertion is atomic on its own, but the group isn't. Other threads may see some of the
+    /// cells updated before the rest
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cells: [PtrCell<u8>; 3] = Default::default();
+    ///
+    /// PtrCell::set_all(&cells, [Some(1), None, Some(3)], Relaxed);
+    ///
+    /// assert!(PtrCell::take_all(&cells, Relaxed).eq([Some(1), None, Some(3)]))
+    /// ```
+    pub fn set_all<'a>(
+        cells: impl IntoIterator<Item = &'a Self>,
+        slots: impl IntoIterator<Item = Option<T>>,
+        order: Semantics,
+    ) where
+        T: 'a,
+    {
+        for (cell, slot) in cells.into_iter().zip(slots) {
+            cell.set(slot, order);
+        }
+    }
+}
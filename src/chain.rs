@@ -0,0 +1,317 @@
+//! Traversal of chains built with [`map_owner`](PtrCell::map_owner) and similar linked cells
+
+use crate::{PtrCell, Semantics};
+use core::marker::PhantomData;
+
+/// Cursor over the nodes of an exclusively borrowed chain built with
+/// [`map_owner`](PtrCell::map_owner)
+///
+/// This isn't an [`Iterator`], as every node borrows the cursor until the next one is requested.
+/// Nodes may then be relinked or unlinked through the borrowed node without invalidating the
+/// cursor, which continues from whatever the node links to when advanced
+///
+/// Created by [`PtrCell::iter_chain_mut`]
+pub struct ChainIterMut<'a, T: AsMut<PtrCell<T>>> {
+    /// The cell that holds the chain. Taken when the first node is requested
+    head: Option<&'a mut PtrCell<T>>,
+
+    /// The node that was requested last, or null once the chain ends. Its link is only read when
+    /// advancing
+    node: *mut T,
+
+    /// Marks the nodes as borrowed exclusively
+    chain: PhantomData<&'a mut T>,
+}
+
+impl<T: AsMut<PtrCell<T>>> ChainIterMut<'_, T> {
+    /// Advances to the next node of the chain and borrows it
+    ///
+    /// Returns [`None`] once the chain ends
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, PtrCell, Semantics::Relaxed};
+    ///
+    /// let mut list = PtrCell::default();
+    ///
+    /// for value in [1, 2, 3] {
+    ///     Node::push(&list, value, Relaxed);
+    /// }
+    ///
+    /// let mut nodes = list.iter_chain_mut();
+    ///
+    /// while let Some(node) = nodes.next() {
+    ///     // Unlinking a node's successor skips it
+    ///     if node.value == 3 {
+    ///         let mut second = node.next.take(Relaxed).expect("The list has three nodes");
+    ///         node.next = std::mem::take(&mut second.next);
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(list.chain_len(), 2)
+    /// ```
+    #[allow(clippy::should_implement_trait)] // Nodes borrow the cursor, which `Iterator` can't do
+    pub fn next(&mut self) -> Option<&mut T> {
+        let link = match self.head.take() {
+            Some(head) => head,
+            None => unsafe { self.node.as_mut() }?.as_mut(),
+        };
+
+        self.node = *link.value.get_mut();
+
+        unsafe { self.node.as_mut() }
+    }
+}
+
+impl<T: AsMut<PtrCell<T>>> core::fmt::Debug for ChainIterMut<'_, T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("ChainIterMut")
+            .field("started", &self.head.is_none())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Iterator over clones of the nodes of a shared chain
+///
+/// Meant for chains of reference-counted nodes, like `Arc`s, where a clone is a new handle to the
+/// same node. Every yielded node stays alive for as long as its handle does, even if it's unlinked
+/// from the chain in the meantime
+///
+/// Created by [`PtrCell::iter_shared`]
+pub struct ChainIterShared<'a, T: Clone + Sync + AsRef<PtrCell<T>>> {
+    /// The cell that holds the chain. Taken when the first node is requested
+    head: Option<&'a PtrCell<T>>,
+
+    /// Handle to the node that was yielded last, which keeps its link alive
+    node: Option<T>,
+
+    /// Semantics of the loads of the links
+    order: Semantics,
+}
+
+impl<T: Clone + Sync + AsRef<PtrCell<T>>> Iterator for ChainIterShared<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let link = match self.head.take() {
+            Some(head) => head,
+            None => self.node.as_ref()?.as_ref(),
+        };
+
+        // The caller of `iter_shared` guarantees that the links aren't written to
+        let Some(next) = (unsafe { link.get_ptr(self.order).as_ref() }).cloned() else {
+            self.node = None;
+            return None;
+        };
+
+        self.node = Some(next.clone());
+
+        Some(next)
+    }
+}
+
+impl<T: Clone + Sync + AsRef<PtrCell<T>>> core::iter::FusedIterator for ChainIterShared<'_, T> {}
+
+impl<T: Clone + Sync + AsRef<PtrCell<T>>> core::fmt::Debug for ChainIterShared<'_, T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("ChainIterShared")
+            .field("started", &self.head.is_none())
+            .field("order", &self.order)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Lets chains link `Arc`s of their nodes directly, as in `PtrCell<Arc<Node>>`
+#[cfg(target_has_atomic = "ptr")]
+impl<N: AsRef<PtrCell<alloc::sync::Arc<N>>>> AsRef<PtrCell<Self>> for alloc::sync::Arc<N> {
+    #[inline]
+    fn as_ref(&self) -> &PtrCell<Self> {
+        (**self).as_ref()
+    }
+}
+
+impl<T: Clone + Sync + AsRef<PtrCell<T>>> PtrCell<T> {
+    /// Returns an iterator over clones of the nodes of the chain, from the cell onwards
+    ///
+    /// Unlike [`iter_chain_mut`](Self::iter_chain_mut) or draining, this only needs a shared
+    /// reference, so any number of threads can walk the same chain at once. Each link is loaded
+    /// with `order`, so pass semantics that acquire when the chain was published by another
+    /// thread. The chain itself is left untouched
+    ///
+    /// Several threads may clone the same value at once, which is why `T` has to be [`Sync`]
+    ///
+    /// # Safety
+    ///
+    /// While the iterator exists, no link of the chain may be written to, including this cell.
+    /// Values are cloned straight out of the links, so a value that's taken or replaced elsewhere
+    /// in the meantime may be freed while it's cloned. The yielded nodes aren't affected, so they
+    /// can be kept after the iterator is dropped
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    /// use std::sync::Arc;
+    ///
+    /// struct Node {
+    ///     value: u32,
+    ///     next: PtrCell<Arc<Self>>,
+    /// }
+    ///
+    /// impl AsRef<PtrCell<Arc<Self>>> for Node {
+    ///     fn as_ref(&self) -> &PtrCell<Arc<Self>> {
+    ///         &self.next
+    ///     }
+    /// }
+    ///
+    /// let mut head = None;
+    ///
+    /// for value in 1..=3 {
+    ///     let next = PtrCell::from(head);
+    ///     head = Some(Arc::new(Node { value, next }));
+    /// }
+    ///
+    /// let list: PtrCell<Arc<Node>> = PtrCell::from(head);
+    ///
+    /// std::thread::scope(|scope| {
+    ///     for _ in 0..4 {
+    ///         scope.spawn(|| {
+    ///             // The list isn't changed until all of the readers are done
+    ///             let nodes = unsafe { list.iter_shared(Coupled) };
+    ///
+    ///             assert!(nodes.map(|node| node.value).eq([3, 2, 1]))
+    ///         });
+    ///     }
+    /// });
+    ///
+    /// let first = unsafe { list.iter_shared(Coupled) }.next();
+    /// list.take(Coupled);
+    ///
+    /// // The first node outlives the list
+    /// assert_eq!(first.map(|node| node.value), Some(3))
+    /// ```
+    #[inline]
+    pub const unsafe fn iter_shared(&self, order: Semantics) -> ChainIterShared<'_, T> {
+        ChainIterShared {
+            head: Some(self),
+            node: None,
+            order,
+        }
+    }
+}
+
+impl<T: AsMut<PtrCell<T>>> PtrCell<T> {
+    /// Counts the nodes of the chain built with [`map_owner`](Self::map_owner)
+    ///
+    /// No atomic operations are performed and the chain stays in place, as the cell is borrowed
+    /// exclusively
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, PtrCell, Semantics::Relaxed};
+    ///
+    /// let mut list = PtrCell::default();
+    /// assert_eq!(list.chain_len(), 0);
+    ///
+    /// for value in 1..=3 {
+    ///     Node::push(&list, value, Relaxed);
+    /// }
+    ///
+    /// assert_eq!(list.chain_len(), 3)
+    /// ```
+    pub fn chain_len(&mut self) -> usize {
+        let mut count = 0;
+        let mut link = self;
+
+        while let Some(node) = link.get_mut() {
+            count += 1;
+            link = node.as_mut();
+        }
+
+        count
+    }
+
+    /// Returns a cursor that borrows the nodes of the chain built with
+    /// [`map_owner`](Self::map_owner) one by one
+    ///
+    /// The nodes are visited from the most recently inserted one. No atomic operations are
+    /// performed and the chain stays in place, as the cell is borrowed exclusively
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, PtrCell, Semantics::Relaxed};
+    ///
+    /// let mut list = PtrCell::default();
+    ///
+    /// for value in 1..=3 {
+    ///     Node::push(&list, value, Relaxed);
+    /// }
+    ///
+    /// let mut nodes = list.iter_chain_mut();
+    /// while let Some(node) = nodes.next() {
+    ///     node.value *= 10;
+    /// }
+    ///
+    /// let head = list.take(Relaxed).expect("The list shouldn't be empty");
+    /// assert!(head.into_iter().eq([30, 20, 10]))
+    /// ```
+    #[inline]
+    pub const fn iter_chain_mut(&mut self) -> ChainIterMut<'_, T> {
+        ChainIterMut {
+            head: Some(self),
+            node: core::ptr::null_mut(),
+            chain: PhantomData,
+        }
+    }
+
+    /// Drops the chain built with [`map_owner`](Self::map_owner) one node at a time, leaving the
+    /// cell empty
+    ///
+    /// Dropping a chain normally recurses into every node's link, which overflows the stack once
+    /// the chain is long enough. Here, each node is unlinked from the rest of the chain before it's
+    /// dropped. Call this from the [`Drop`] implementation of your node type to make the whole
+    /// chain safe to drop, like [`Node`](crate::node::Node) does
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// struct Link {
+    ///     next: PtrCell<Self>,
+    /// }
+    ///
+    /// impl AsMut<PtrCell<Self>> for Link {
+    ///     fn as_mut(&mut self) -> &mut PtrCell<Self> {
+    ///         &mut self.next
+    ///     }
+    /// }
+    ///
+    /// impl Drop for Link {
+    ///     fn drop(&mut self) {
+    ///         self.next.drop_chain_iteratively()
+    ///     }
+    /// }
+    ///
+    /// let mut chain = PtrCell::default();
+    ///
+    /// for _ in 0..100_000 {
+    ///     chain.map_owner(|next| Link { next }, Relaxed);
+    /// }
+    ///
+    /// chain.drop_chain_iteratively();
+    /// assert!(chain.is_empty(Relaxed))
+    /// ```
+    pub fn drop_chain_iteratively(&mut self) {
+        let mut link = core::mem::replace(self.value.get_mut(), core::ptr::null_mut());
+
+        while let Some(mut node) = unsafe { Self::heap_reclaim(link) } {
+            link = core::mem::replace(node.as_mut().value.get_mut(), core::ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,468 @@
+//! Ready-made channels built on cells
+//!
+//! Each constructor returns a pair of handles backed by the structure that suits its pattern:
+//!
+//! - [`channel`]: Any number of [`Sender`]s and one [`Receiver`], like `std::sync::mpsc`. Values
+//!   are pushed onto a lock-free [stack of nodes](crate::node) and received in the order they were
+//!   sent by each sender
+//!
+//! - [`oneshot`]: A single value, sent once. Backed by a single [`PtrCell`]
+//!
+//! - `watch` (`std` only): The latest of a series of values, for any number of subscribers. See
+//!   the [`watch`](crate::watch) module
+//!
+//! Only the `recv` methods wait, and sending allocates at most one node per value. Dropping every
+//! handle on one side closes the channel for the other. The channels perform their operations
+//! with fixed memory orderings, as if with [`Coupled`](Semantics::Coupled) semantics
+//!
+//! # Usage
+//!
+#![doc = doctest!()]
+//! use ptr_cell::channel;
+//!
+//! let (sender, mut receiver) = channel::channel();
+//!
+//! std::thread::scope(|scope| {
+//!     for worker in 0..4 {
+//!         let sender = sender.clone();
+//!         scope.spawn(move || sender.send(worker));
+//!     }
+//! });
+//!
+//! drop(sender);
+//!
+//! let mut results: Vec<_> = receiver.try_iter().collect();
+//! results.sort();
+//!
+//! assert_eq!(results, [0, 1, 2, 3])
+//! ```
+
+use crate::{node::Node, AtomicU8, AtomicUsize, PtrCell, Semantics, TakeError};
+use alloc::sync::Arc;
+use core::sync::atomic::Ordering;
+
+#[cfg(feature = "std")]
+pub use crate::watch::channel as watch;
+
+/// Constructs a channel for any number of senders and a single receiver
+///
+/// More senders are created by cloning the one returned
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::channel;
+///
+/// let (sender, mut receiver) = channel::channel();
+///
+/// sender.send("first").expect("The receiver is alive");
+/// sender.send("second").expect("The receiver is alive");
+///
+/// assert_eq!(receiver.try_recv(), Ok("first"));
+/// assert_eq!(receiver.try_recv(), Ok("second"))
+/// ```
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Queue {
+        head: PtrCell::empty(),
+        senders: AtomicUsize::new(1),
+        receiver: AtomicU8::new(ALIVE),
+    });
+
+    let sender = Sender {
+        shared: Arc::clone(&shared),
+    };
+
+    let receiver = Receiver {
+        shared,
+        pending: None,
+    };
+
+    (sender, receiver)
+}
+
+/// Constructs a channel for a single value
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{channel, TakeError};
+///
+/// let (sender, mut receiver) = channel::oneshot();
+/// assert_eq!(receiver.try_recv(), Err(TakeError::Empty));
+///
+/// std::thread::spawn(move || sender.send(1776))
+///     .join()
+///     .expect("The sender shouldn't panic")
+///     .expect("The receiver is alive");
+///
+/// assert_eq!(receiver.try_recv(), Ok(1776));
+/// assert_eq!(receiver.try_recv(), Err(TakeError::Closed))
+/// ```
+pub fn oneshot<T>() -> (OneshotSender<T>, OneshotReceiver<T>) {
+    let shared = Arc::new(Slot {
+        value: PtrCell::empty(),
+        sender: AtomicU8::new(ALIVE),
+        receiver: AtomicU8::new(ALIVE),
+    });
+
+    let sender = OneshotSender {
+        shared: Arc::clone(&shared),
+    };
+
+    (sender, OneshotReceiver { shared })
+}
+
+/// State of a handle that hasn't been dropped yet
+const ALIVE: u8 = 0;
+
+/// State of a handle that has been dropped
+const DROPPED: u8 = 1;
+
+/// State shared by the handles of a [`channel`]
+struct Queue<T> {
+    /// Values that haven't been received yet, the newest first
+    head: PtrCell<Node<T>>,
+
+    /// Number of live senders
+    senders: AtomicUsize,
+
+    /// Either [`ALIVE`] or [`DROPPED`]
+    receiver: AtomicU8,
+}
+
+/// Sending end of a [`channel`]
+///
+/// Cloning it creates another sender for the same channel
+pub struct Sender<T> {
+    /// State shared with the other handles
+    shared: Arc<Queue<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Sends a value to the receiver
+    ///
+    /// # Errors
+    ///
+    /// Hands `value` back if the receiver has been dropped
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::channel;
+    ///
+    /// let (sender, receiver) = channel::channel();
+    /// assert_eq!(sender.send(1), Ok(()));
+    ///
+    /// drop(receiver);
+    /// assert_eq!(sender.send(2), Err(2))
+    /// ```
+    pub fn send(&self, value: T) -> Result<(), T> {
+        if self.shared.receiver.load(Ordering::Acquire) == DROPPED {
+            return Err(value);
+        }
+
+        Node::push(&self.shared.head, value, Semantics::Coupled);
+
+        Ok(())
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for Sender<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        // Publishes the values sent by this sender to a receiver that sees the count drop
+        self.shared.senders.fetch_sub(1, Ordering::Release);
+    }
+}
+
+/// Receiving end of a [`channel`]
+pub struct Receiver<T> {
+    /// State shared with the senders
+    shared: Arc<Queue<T>>,
+
+    /// Values taken out of the queue but not received yet, the oldest first
+    pending: Option<crate::node::IntoIter<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Receives a value if one has been sent
+    ///
+    /// Values sent by the same sender are received in the same order. Values sent by different
+    /// senders at the same time may be received in any order
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TakeError::Empty`] if there's nothing to receive yet, and [`TakeError::Closed`] if
+    /// there never will be, as every sender has been dropped
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{channel, TakeError};
+    ///
+    /// let (sender, mut receiver) = channel::channel::<u8>();
+    /// assert_eq!(receiver.try_recv(), Err(TakeError::Empty));
+    ///
+    /// drop(sender);
+    /// assert_eq!(receiver.try_recv(), Err(TakeError::Closed))
+    /// ```
+    pub fn try_recv(&mut self) -> Result<T, TakeError> {
+        if let Some(value) = self.pending.as_mut().and_then(Iterator::next) {
+            return Ok(value);
+        }
+
+        // Checked before the queue, so that no value sent before the last sender was dropped is
+        // missed
+        let closed = self.shared.senders.load(Ordering::Acquire) == 0;
+
+        let Some(newest) = self.shared.head.take(Semantics::Coupled) else {
+            return Err(if closed {
+                TakeError::Closed
+            } else {
+                TakeError::Empty
+            });
+        };
+
+        let mut pending = newest.reverse().into_iter();
+        let oldest = pending.next();
+        self.pending = Some(pending);
+
+        oldest.ok_or(TakeError::Empty)
+    }
+
+    /// Returns an iterator that receives values until there are none left for now
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{channel, TakeError};
+    ///
+    /// let (sender, mut receiver) = channel::channel();
+    ///
+    /// for value in 0..3 {
+    ///     let _ = sender.send(value);
+    /// }
+    ///
+    /// assert!(receiver.try_iter().eq(0..3));
+    /// assert_eq!(receiver.try_recv(), Err(TakeError::Empty))
+    /// ```
+    pub fn try_iter(&mut self) -> impl Iterator<Item = T> + '_ {
+        core::iter::from_fn(|| self.try_recv().ok())
+    }
+
+    /// Receives a value, waiting for one to be sent
+    ///
+    /// Sending doesn't notify anyone, so the thread polls the channel, yielding to the OS between
+    /// attempts
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClosedError`](crate::ClosedError) if every sender has been dropped and nothing is
+    /// left to receive
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::channel;
+    ///
+    /// let (sender, mut receiver) = channel::channel();
+    ///
+    /// std::thread::spawn(move || {
+    ///     for line in ["GET / HTTP/1.1", "Host: example.com"] {
+    ///         let _ = sender.send(line);
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(receiver.recv(), Ok("GET / HTTP/1.1"));
+    /// assert_eq!(receiver.recv(), Ok("Host: example.com"));
+    /// assert!(receiver.recv().is_err())
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn recv(&mut self) -> Result<T, crate::ClosedError> {
+        use crate::backoff::{Backoff, SpinThenYield};
+
+        let mut backoff = SpinThenYield::new();
+
+        loop {
+            match self.try_recv() {
+                Ok(value) => return Ok(value),
+                Err(TakeError::Closed) => return Err(crate::ClosedError),
+                Err(TakeError::Empty) => backoff.backoff(),
+            }
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for Receiver<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.receiver.store(DROPPED, Ordering::Release);
+    }
+}
+
+/// State shared by the handles of a [`oneshot`] channel
+struct Slot<T> {
+    /// The sent value
+    value: PtrCell<T>,
+
+    /// Either [`ALIVE`] or [`DROPPED`]
+    sender: AtomicU8,
+
+    /// Either [`ALIVE`] or [`DROPPED`]
+    receiver: AtomicU8,
+}
+
+/// Sending end of a [`oneshot`] channel
+pub struct OneshotSender<T> {
+    /// State shared with the receiver
+    shared: Arc<Slot<T>>,
+}
+
+impl<T> OneshotSender<T> {
+    /// Sends the value to the receiver, consuming the sender
+    ///
+    /// # Errors
+    ///
+    /// Hands `value` back if the receiver has been dropped
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::channel;
+    ///
+    /// let (sender, receiver) = channel::oneshot();
+    /// drop(receiver);
+    ///
+    /// assert_eq!(sender.send("too late"), Err("too late"))
+    /// ```
+    pub fn send(self, value: T) -> Result<(), T> {
+        if self.shared.receiver.load(Ordering::Acquire) == DROPPED {
+            return Err(value);
+        }
+
+        self.shared.value.set(Some(value), Semantics::Coupled);
+
+        Ok(())
+    }
+}
+
+impl<T> core::fmt::Debug for OneshotSender<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("OneshotSender")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for OneshotSender<T> {
+    fn drop(&mut self) {
+        // Publishes the sent value to a receiver that sees the sender gone
+        self.shared.sender.store(DROPPED, Ordering::Release);
+    }
+}
+
+/// Receiving end of a [`oneshot`] channel
+pub struct OneshotReceiver<T> {
+    /// State shared with the sender
+    shared: Arc<Slot<T>>,
+}
+
+impl<T> OneshotReceiver<T> {
+    /// Receives the value if it has been sent
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TakeError::Empty`] if the value hasn't been sent yet, and [`TakeError::Closed`] if
+    /// it never will be, or has already been received
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{channel, TakeError};
+    ///
+    /// let (sender, mut receiver) = channel::oneshot::<u8>();
+    /// drop(sender);
+    ///
+    /// assert_eq!(receiver.try_recv(), Err(TakeError::Closed))
+    /// ```
+    pub fn try_recv(&mut self) -> Result<T, TakeError> {
+        // Checked before the value, so that a value sent before the sender was dropped isn't missed
+        let closed = self.shared.sender.load(Ordering::Acquire) == DROPPED;
+
+        match self.shared.value.take(Semantics::Coupled) {
+            Some(value) => Ok(value),
+            None if closed => Err(TakeError::Closed),
+            None => Err(TakeError::Empty),
+        }
+    }
+
+    /// Receives the value, waiting for it to be sent
+    ///
+    /// Sending doesn't notify anyone, so the thread polls the channel, yielding to the OS between
+    /// attempts
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClosedError`](crate::ClosedError) if the sender has been dropped without sending
+    /// the value, or if it has already been received
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::channel;
+    ///
+    /// let (sender, mut receiver) = channel::oneshot();
+    ///
+    /// std::thread::spawn(move || sender.send(String::from("done")));
+    ///
+    /// assert_eq!(receiver.recv().as_deref(), Ok("done"))
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn recv(&mut self) -> Result<T, crate::ClosedError> {
+        use crate::backoff::{Backoff, SpinThenYield};
+
+        let mut backoff = SpinThenYield::new();
+
+        loop {
+            match self.try_recv() {
+                Ok(value) => return Ok(value),
+                Err(TakeError::Closed) => return Err(crate::ClosedError),
+                Err(TakeError::Empty) => backoff.backoff(),
+            }
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for OneshotReceiver<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("OneshotReceiver")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for OneshotReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.receiver.store(DROPPED, Ordering::Release);
+    }
+}
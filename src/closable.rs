@@ -0,0 +1,332 @@
+//! Thread-safe cell that can be closed for good
+
+use crate::{ClosedError, InsertError, PtrCell, Semantics, TakeError};
+
+/// Byte whose address marks a closed cell. No value can ever be allocated there
+static CLOSED: u8 = 0;
+
+/// Returns the pointer that marks a closed cell
+#[inline]
+fn closed<T>() -> *mut T {
+    core::ptr::addr_of!(CLOSED).cast_mut().cast()
+}
+
+/// Reclaims a value that was leaked from [`Some`] but never made it into the cell
+///
+/// # Safety
+///
+/// `ptr` must have been returned by [`PtrCell::heap_leak`] for a [`Some`] and not reclaimed since
+#[inline]
+unsafe fn unleak<T>(ptr: *mut T) -> T {
+    // The pointer was leaked from `Some`, so it's non-null
+    unsafe { PtrCell::heap_reclaim(ptr).unwrap_unchecked() }
+}
+
+/// Thread-safe cell with a terminal "closed" state
+///
+/// Lets consumers tell "empty for now" apart from "no more values ever" without reserving a magic
+/// value. Once the cell is [closed](Self::close), values can no longer be set, and takes report
+/// [`ClosedError`]. The state is a sentinel pointer stored in place of the value, so checking it
+/// costs nothing extra
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{ClosablePtrCell, Semantics::Coupled};
+///
+/// let jobs = ClosablePtrCell::new();
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         for job in 1..=3 {
+///             while let Err(_) = jobs.set(job, Coupled) {}
+///         }
+///
+///         while !jobs.is_empty(Coupled) {
+///             std::thread::yield_now();
+///         }
+///
+///         let _ = jobs.close(Coupled);
+///     });
+///
+///     let mut done = Vec::new();
+///
+///     while let Ok(job) = jobs.take(Coupled) {
+///         done.extend(job);
+///     }
+///
+///     assert!(done.len() <= 3)
+/// })
+/// ```
+pub struct ClosablePtrCell<T> {
+    /// The cell. Holds the pointer returned by `closed` once closed
+    cell: PtrCell<T>,
+}
+
+impl<T> ClosablePtrCell<T> {
+    /// Inserts a value into the cell, dropping the previous one
+    ///
+    /// # Errors
+    ///
+    /// Hands `value` back if the cell is closed
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{ClosablePtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = ClosablePtrCell::new();
+    /// assert_eq!(cell.set(1, Relaxed), Ok(()));
+    ///
+    /// let _ = cell.close(Relaxed);
+    /// assert_eq!(cell.set(2, Relaxed), Err(2))
+    /// ```
+    pub fn set(&self, value: T, order: Semantics) -> Result<(), T> {
+        let new_ptr = PtrCell::heap_leak(Some(value));
+        let mut current = self.cell.value.load(order.read());
+
+        loop {
+            if current == closed() {
+                return Err(unsafe { unleak(new_ptr) });
+            }
+
+            let setting = self.cell.value.compare_exchange_weak(
+                current,
+                new_ptr,
+                order.read_write(),
+                order.read(),
+            );
+
+            match setting {
+                Ok(old_ptr) => {
+                    drop(unsafe { PtrCell::heap_reclaim(old_ptr) });
+                    return Ok(());
+                }
+                Err(modified) => current = modified,
+            }
+        }
+    }
+
+    /// Takes out the cell's value
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClosedError`] if the cell is closed
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{ClosablePtrCell, ClosedError, Semantics::Relaxed};
+    ///
+    /// let cell = ClosablePtrCell::new();
+    /// assert_eq!(cell.take(Relaxed), Ok(None));
+    ///
+    /// let _ = cell.set('a', Relaxed);
+    /// assert_eq!(cell.take(Relaxed), Ok(Some('a')));
+    ///
+    /// let _ = cell.close(Relaxed);
+    /// assert_eq!(cell.take(Relaxed), Err(ClosedError))
+    /// ```
+    pub fn take(&self, order: Semantics) -> Result<Option<T>, ClosedError> {
+        let mut current = self.cell.value.load(order.read());
+
+        loop {
+            if current == closed() {
+                return Err(ClosedError);
+            }
+
+            if current.is_null() {
+                return Ok(None);
+            }
+
+            let taking = self.cell.value.compare_exchange_weak(
+                current,
+                core::ptr::null_mut(),
+                order.read_write(),
+                order.read(),
+            );
+
+            match taking {
+                Ok(old_ptr) => return Ok(unsafe { PtrCell::heap_reclaim(old_ptr) }),
+                Err(modified) => current = modified,
+            }
+        }
+    }
+
+    /// Inserts a value into the cell only if it's open and empty
+    ///
+    /// # Errors
+    ///
+    /// Hands `value` back, along with the reason, if the cell is occupied or closed
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{ClosablePtrCell, InsertError, Semantics::Coupled};
+    ///
+    /// let slot = ClosablePtrCell::new();
+    ///
+    /// assert_eq!(slot.try_insert("first", Coupled), Ok(()));
+    /// assert_eq!(slot.try_insert("second", Coupled), Err(InsertError::Occupied("second")));
+    /// assert_eq!(slot.try_take(Coupled), Ok("first"))
+    /// ```
+    pub fn try_insert(&self, value: T, order: Semantics) -> Result<(), InsertError<T>> {
+        let new_ptr = PtrCell::heap_leak(Some(value));
+
+        let inserting = self.cell.value.compare_exchange(
+            core::ptr::null_mut(),
+            new_ptr,
+            order.read_write(),
+            order.read(),
+        );
+
+        match inserting {
+            Ok(_) => Ok(()),
+            Err(current) => {
+                let value = unsafe { unleak(new_ptr) };
+
+                if current == closed() {
+                    Err(InsertError::Closed(value))
+                } else {
+                    Err(InsertError::Occupied(value))
+                }
+            }
+        }
+    }
+
+    /// Takes out the cell's value, reporting why there is none
+    ///
+    /// Unlike [`take`](Self::take), this tells apart the two reasons for not getting a value
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TakeError::Empty`] if the cell is empty for now, or [`TakeError::Closed`] if it
+    /// has been closed
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{ClosablePtrCell, Semantics::Relaxed, TakeError};
+    ///
+    /// let cell = ClosablePtrCell::new();
+    /// assert_eq!(cell.try_take(Relaxed), Err(TakeError::Empty));
+    ///
+    /// let _ = cell.set(8, Relaxed);
+    /// assert_eq!(cell.try_take(Relaxed), Ok(8))
+    /// ```
+    #[inline]
+    pub fn try_take(&self, order: Semantics) -> Result<T, TakeError> {
+        self.take(order)?.ok_or(TakeError::Empty)
+    }
+
+    /// Closes the cell for good, returning the value that was left in it
+    ///
+    /// Closing a closed cell does nothing and returns [`None`]
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{ClosablePtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = ClosablePtrCell::new();
+    /// let _ = cell.set(45, Relaxed);
+    ///
+    /// assert_eq!(cell.close(Relaxed), Some(45));
+    /// assert_eq!(cell.close(Relaxed), None)
+    /// ```
+    #[must_use = "the value left in the cell is returned"]
+    pub fn close(&self, order: Semantics) -> Option<T> {
+        let old_ptr = self.cell.value.swap(closed(), order.read_write());
+
+        if old_ptr == closed() {
+            return None;
+        }
+
+        unsafe { PtrCell::heap_reclaim(old_ptr) }
+    }
+
+    /// Determines whether the cell has been closed
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{ClosablePtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = ClosablePtrCell::<u8>::new();
+    /// assert!(!cell.is_closed(Relaxed));
+    ///
+    /// let _ = cell.close(Relaxed);
+    /// assert!(cell.is_closed(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_closed(&self, order: Semantics) -> bool {
+        self.cell.value.load(order.read()) == closed()
+    }
+
+    /// Determines whether the cell is open and empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{ClosablePtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = ClosablePtrCell::<u8>::new();
+    /// assert!(cell.is_empty(Relaxed));
+    ///
+    /// let _ = cell.close(Relaxed);
+    /// assert!(!cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_empty(&self, order: Semantics) -> bool {
+        self.cell.is_empty(order)
+    }
+
+    /// Constructs an open, empty cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{ClosablePtrCell, Semantics::Relaxed};
+    ///
+    /// static SHUTDOWN: ClosablePtrCell<&str> = ClosablePtrCell::new();
+    ///
+    /// assert!(!SHUTDOWN.is_closed(Relaxed))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            cell: PtrCell::empty(),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for ClosablePtrCell<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("ClosablePtrCell")
+            .field("cell", &self.cell)
+            .field("closed", &self.is_closed(Semantics::Relaxed))
+            .finish()
+    }
+}
+
+impl<T> Default for ClosablePtrCell<T> {
+    /// Constructs an open, empty cell
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for ClosablePtrCell<T> {
+    #[inline]
+    fn drop(&mut self) {
+        let ptr = self.cell.value.get_mut();
+
+        // The sentinel must never be reclaimed by the inner cell
+        if *ptr == closed() {
+            *ptr = core::ptr::null_mut();
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Backend for targets without usable atomics
+//!
+//! With the `critical-section` feature, every operation runs inside a critical section. On
+//! `wasm32` without the `atomics` target feature, there's only ever one thread, so operations run
+//! directly on the value instead
+
+// Without `alloc`, only the integer atomics of the inline cells are used
+#![cfg_attr(not(feature = "alloc"), allow(dead_code))]
+
+use crate::Backend;
+use core::{cell::UnsafeCell, sync::atomic::Ordering};
+
+/// Stand-in for [`AtomicPtr`](core::sync::atomic::AtomicPtr)
+pub type AtomicPtr<T> = Atomic<*mut T>;
+
+/// Stand-in for [`AtomicU8`](core::sync::atomic::AtomicU8)
+pub type AtomicU8 = Atomic<u8>;
+
+/// Stand-in for [`AtomicU64`](core::sync::atomic::AtomicU64)
+pub type AtomicU64 = Atomic<u64>;
+
+/// Stand-in for [`AtomicUsize`](core::sync::atomic::AtomicUsize)
+pub type AtomicUsize = Atomic<usize>;
+
+/// Backend of the current build
+#[cfg(feature = "critical-section")]
+pub const BACKEND: Backend = Backend::CriticalSection;
+
+/// Backend of the current build
+#[cfg(not(feature = "critical-section"))]
+pub const BACKEND: Backend = Backend::SingleThreaded;
+
+/// Stand-in for [`fence`](core::sync::atomic::fence)
+///
+/// Entering a critical section already synchronizes with every other one, so the ordering is
+/// ignored
+#[inline]
+pub fn fence(_: Ordering) {
+    exclusive(|| ());
+}
+
+/// Runs `f` without any other operation on an atomic happening in the meantime
+#[inline]
+#[cfg(feature = "critical-section")]
+fn exclusive<R>(f: impl FnOnce() -> R) -> R {
+    critical_section::with(|_| f())
+}
+
+/// Runs `f` without any other operation on an atomic happening in the meantime
+///
+/// Single-threaded WebAssembly has nothing to exclude
+#[inline]
+#[cfg(not(feature = "critical-section"))]
+fn exclusive<R>(f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+/// Stand-in for the atomic types of `core` that performs every operation inside
+/// `critical_section::with`, or directly on single-threaded WebAssembly
+///
+/// Orderings are accepted only to mirror the API of real atomics. A critical section already
+/// provides the strongest possible synchronization, and a single thread needs none, so they're
+/// ignored
+#[repr(transparent)]
+pub struct Atomic<V> {
+    /// The stored value. Only ever accessed through `exclusive` or `&mut self`
+    value: UnsafeCell<V>,
+}
+
+// Same guarantees as `core`'s atomics: all shared access goes through a critical section, or
+// happens on the only thread there is
+unsafe impl<V> Send for Atomic<V> {}
+unsafe impl<V> Sync for Atomic<V> {}
+
+impl<V: Copy + PartialEq> Atomic<V> {
+    /// Constructs a new atomic value
+    #[inline]
+    pub const fn new(value: V) -> Self {
+        let value = UnsafeCell::new(value);
+
+        Self { value }
+    }
+
+    /// Returns a mutable reference to the value
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V {
+        self.value.get_mut()
+    }
+
+    /// Loads the value
+    #[inline]
+    pub fn load(&self, _: Ordering) -> V {
+        exclusive(|| unsafe { *self.value.get() })
+    }
+
+    /// Stores `value`
+    #[inline]
+    pub fn store(&self, value: V, _: Ordering) {
+        exclusive(|| unsafe { *self.value.get() = value });
+    }
+
+    /// Stores `value` and returns the previous one
+    #[inline]
+    pub fn swap(&self, value: V, _: Ordering) -> V {
+        exclusive(|| unsafe { core::ptr::replace(self.value.get(), value) })
+    }
+
+    /// Stores `new` if the current value is `current`
+    ///
+    /// Never fails spuriously
+    #[inline]
+    pub fn compare_exchange_weak(
+        &self,
+        current: V,
+        new: V,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<V, V> {
+        self.compare_exchange(current, new, success, failure)
+    }
+
+    /// Stores `new` if the current value is `current`
+    #[inline]
+    pub fn compare_exchange(&self, current: V, new: V, _: Ordering, _: Ordering) -> Result<V, V> {
+        exclusive(|| {
+            let slot = unsafe { &mut *self.value.get() };
+
+            if *slot == current {
+                Ok(core::mem::replace(slot, new))
+            } else {
+                Err(*slot)
+            }
+        })
+    }
+}
+
+impl Atomic<usize> {
+    /// Adds `value` to the current value, wrapping around on overflow. Returns the previous value
+    #[inline]
+    pub fn fetch_add(&self, value: usize, _: Ordering) -> usize {
+        exclusive(|| {
+            let slot = unsafe { &mut *self.value.get() };
+
+            core::mem::replace(slot, slot.wrapping_add(value))
+        })
+    }
+
+    /// Subtracts `value` from the current value, wrapping around on overflow. Returns the previous
+    /// value
+    #[inline]
+    pub fn fetch_sub(&self, value: usize, _: Ordering) -> usize {
+        exclusive(|| {
+            let slot = unsafe { &mut *self.value.get() };
+
+            core::mem::replace(slot, slot.wrapping_sub(value))
+        })
+    }
+}
+
+impl Atomic<u64> {
+    /// Adds `value` to the current value, wrapping around on overflow. Returns the previous value
+    #[inline]
+    pub fn fetch_add(&self, value: u64, _: Ordering) -> u64 {
+        exclusive(|| {
+            let slot = unsafe { &mut *self.value.get() };
+
+            core::mem::replace(slot, slot.wrapping_add(value))
+        })
+    }
+}
+
+impl<V: Copy + PartialEq + core::fmt::Debug> core::fmt::Debug for Atomic<V> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&self.load(Ordering::Relaxed), formatter)
+    }
+}
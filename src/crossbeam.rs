@@ -0,0 +1,123 @@
+//! Interop with [`AtomicCell`] from `crossbeam-utils`, enabled by the `crossbeam` feature
+//!
+//! A `PtrCell<T>` is equivalent to an `AtomicCell<Option<Box<T>>>`. Both store a single pointer
+//! to a heap-allocated value, and both represent [`None`] with a null pointer. The operations map
+//! as follows:
+//!
+//! | [`PtrCell`]                                | [`AtomicCell`]                   |
+//! | ------------------------------------------ | -------------------------------- |
+//! | [`set(Some(value), order)`](PtrCell::set)  | `store(Some(Box::new(value)))`   |
+//! | [`replace(slot, order)`](PtrCell::replace) | `swap(slot.map(Box::new))`       |
+//! | [`take(order)`](PtrCell::take)             | `take()`                         |
+//! | [`into_inner()`](PtrCell::into_inner)      | `into_inner()`                   |
+//!
+//! The main difference is the memory ordering. [`AtomicCell`] always uses sequentially consistent
+//! operations, which is what [`Ordered`](Semantics::Ordered) semantics give
+//!
+//! Values are moved between the two without reallocating them, with the [`From`] conversions and
+//! with [`PtrCell::move_to_atomic_cell`] and [`PtrCell::move_from_atomic_cell`]
+
+use crate::{PtrCell, Semantics};
+use alloc::boxed::Box;
+use crossbeam_utils::atomic::AtomicCell;
+
+impl<T> PtrCell<T> {
+    /// Moves the cell's value into `target`, returning the value that `target` held
+    ///
+    /// The cell is left empty. The value keeps its allocation
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use crossbeam_utils::atomic::AtomicCell;
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    ///
+    /// let cell = PtrCell::from(1);
+    /// let target = AtomicCell::new(Some(Box::new(2)));
+    ///
+    /// assert_eq!(cell.move_to_atomic_cell(&target, Coupled), Some(Box::new(2)));
+    /// assert_eq!(target.take(), Some(Box::new(1)));
+    /// assert!(cell.is_empty(Coupled))
+    /// ```
+    pub fn move_to_atomic_cell(
+        &self,
+        target: &AtomicCell<Option<Box<T>>>,
+        order: Semantics,
+    ) -> Option<Box<T>> {
+        let ptr = self.replace_ptr(core::ptr::null_mut(), order);
+
+        target.swap(unsafe { crate::into_box(ptr) })
+    }
+
+    /// Moves the value of `source` into the cell, returning the cell's previous value
+    ///
+    /// `source` is left empty. The value keeps its allocation
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use crossbeam_utils::atomic::AtomicCell;
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    ///
+    /// let cell = PtrCell::from('a');
+    /// let source = AtomicCell::new(Some(Box::new('b')));
+    ///
+    /// assert_eq!(cell.move_from_atomic_cell(&source, Coupled), Some(Box::new('a')));
+    /// assert_eq!(cell.take(Coupled), Some('b'));
+    /// assert!(source.take().is_none())
+    /// ```
+    pub fn move_from_atomic_cell(
+        &self,
+        source: &AtomicCell<Option<Box<T>>>,
+        order: Semantics,
+    ) -> Option<Box<T>> {
+        let ptr = source
+            .take()
+            .map_or(core::ptr::null_mut(), |boxed| Self::from(boxed).leak());
+
+        let old_ptr = self.replace_ptr(ptr, order);
+
+        unsafe { crate::into_box(old_ptr) }
+    }
+}
+
+impl<T> From<PtrCell<T>> for AtomicCell<Option<Box<T>>> {
+    /// Moves the cell's value into an [`AtomicCell`] without reallocating it
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use crossbeam_utils::atomic::AtomicCell;
+    /// use ptr_cell::PtrCell;
+    ///
+    /// let cell: AtomicCell<Option<Box<i32>>> = PtrCell::from(34).into();
+    ///
+    /// assert_eq!(cell.into_inner(), Some(Box::new(34)))
+    /// ```
+    #[inline]
+    fn from(cell: PtrCell<T>) -> Self {
+        Self::new(unsafe { crate::into_box(cell.leak()) })
+    }
+}
+
+impl<T> From<AtomicCell<Option<Box<T>>>> for PtrCell<T> {
+    /// Moves the value of an [`AtomicCell`] into a cell without reallocating it
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use crossbeam_utils::atomic::AtomicCell;
+    /// use ptr_cell::PtrCell;
+    ///
+    /// let cell = PtrCell::from(AtomicCell::new(Some(Box::new(34))));
+    ///
+    /// assert_eq!(cell.into_inner(), Some(34))
+    /// ```
+    #[inline]
+    fn from(cell: AtomicCell<Option<Box<T>>>) -> Self {
+        match cell.into_inner() {
+            Some(boxed) => boxed.into(),
+            None => Self::empty(),
+        }
+    }
+}
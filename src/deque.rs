@@ -0,0 +1,459 @@
+//! Bounded work-stealing deque
+//!
+//! A [`Deque`] is split into a single [`Worker`], which pushes and pops values at one end, and any
+//! number of [`Stealer`]s, which take values from the other end. The owner of the worker treats
+//! the deque as a stack of its own work, while idle threads steal the oldest values from it. This
+//! is the Chase-Lev algorithm over a fixed-size ring of leaked values, so nothing is allocated
+//! besides the values themselves
+//!
+//! The deque performs its operations with fixed memory orderings, as the algorithm doesn't work
+//! with weaker ones
+//!
+//! # Usage
+//!
+#![doc = doctest!()]
+//! use ptr_cell::deque::Deque;
+//!
+//! let mut deque: Deque<u32, 64> = Deque::new();
+//! let (worker, stealer) = deque.split();
+//!
+//! for job in 0..64 {
+//!     worker.push(job).expect("The deque shouldn't be full");
+//! }
+//!
+//! std::thread::scope(|scope| {
+//!     let thief = scope.spawn(|| {
+//!         let mut stolen = 0;
+//!
+//!         while let Some(job) = stealer.steal() {
+//!             stolen += job;
+//!         }
+//!
+//!         stolen
+//!     });
+//!
+//!     let mut done = 0;
+//!
+//!     while let Some(job) = worker.pop() {
+//!         done += job;
+//!     }
+//!
+//!     let stolen = thief.join().expect("The thief shouldn't panic");
+//!
+//!     assert_eq!(done + stolen, (0..64).sum())
+//! })
+//! ```
+
+use crate::{AtomicPtr, AtomicUsize, PtrCell, Semantics};
+use core::{cell::Cell, marker::PhantomData, sync::atomic::Ordering};
+
+/// Fixed-capacity work-stealing deque of up to `N` values, where `N` is a power of two
+///
+/// See the [module documentation](self) for an overview
+pub struct Deque<T, const N: usize> {
+    /// Ring of values leaked with [`PtrCell::heap_leak`]
+    ///
+    /// #### Invariants
+    ///
+    /// - The slots from `top` up to `bottom`, wrapped around `N`, hold the values of the deque.
+    ///   The other slots may hold stale pointers, which must never be reclaimed
+    /// - `N` is a power of two, so consecutive indices stay on consecutive slots when the indices
+    ///   wrap around `usize::MAX`
+    slots: [AtomicPtr<T>; N],
+
+    /// Index of the oldest value, which is the next one to be stolen. Only ever increases, so
+    /// that a stealer can't claim a value with an outdated index
+    top: AtomicUsize,
+
+    /// Index one past the newest value. Only modified by the worker
+    bottom: AtomicUsize,
+
+    /// Marks the deque as the owner of its values
+    owns: PhantomData<T>,
+}
+
+// Values only enter and leave the deque by value, so no `&T` is ever shared
+unsafe impl<T: Send, const N: usize> Sync for Deque<T, N> {}
+
+impl<T, const N: usize> Deque<T, N> {
+    /// Slot that holds no value
+    #[allow(clippy::declare_interior_mutable_const)]
+    const VACANT: AtomicPtr<T> = AtomicPtr::new(core::ptr::null_mut());
+
+    /// Splits the deque into its worker and a stealer
+    ///
+    /// More stealers can be made by cloning the returned one or with [`Worker::stealer`]
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::deque::Deque;
+    ///
+    /// let mut deque: Deque<char, 4> = Deque::new();
+    /// let (worker, stealer) = deque.split();
+    ///
+    /// let _ = worker.push('a');
+    ///
+    /// assert_eq!(stealer.steal(), Some('a'))
+    /// ```
+    #[inline]
+    pub const fn split(&mut self) -> (Worker<'_, T, N>, Stealer<'_, T, N>) {
+        let deque = &*self;
+
+        let worker = Worker {
+            deque,
+            unshared: PhantomData,
+        };
+
+        (worker, Stealer { deque })
+    }
+
+    /// Returns the number of values in the deque
+    ///
+    /// The result may be outdated if the deque is being modified at the same time
+    fn len(&self) -> usize {
+        let bottom = self.bottom.load(Ordering::Acquire);
+        let top = self.top.load(Ordering::Acquire);
+
+        // The worker may have claimed a value that's still counted by `top`
+        usize::try_from(Self::distance(top, bottom)).unwrap_or(0)
+    }
+
+    /// Returns how far `to` is ahead of `from`, which is negative if it's behind
+    #[inline]
+    #[allow(clippy::cast_possible_wrap)] // Wrapping is what makes a lagging index negative
+    const fn distance(from: usize, to: usize) -> isize {
+        to.wrapping_sub(from) as isize
+    }
+
+    /// Returns the slot at `index`, wrapped around the capacity
+    #[inline]
+    const fn slot(&self, index: usize) -> &AtomicPtr<T> {
+        &self.slots[index % N]
+    }
+
+    /// Constructs an empty deque
+    ///
+    /// `N` must be a power of two, which is checked at compile time:
+    ///
+    /// ```rust,compile_fail
+    /// use ptr_cell::deque::Deque;
+    ///
+    /// let _: Deque<u8, 12> = Deque::new();
+    /// ```
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::deque::Deque;
+    ///
+    /// let mut deque: Deque<u8, 16> = Deque::new();
+    /// let (worker, _) = deque.split();
+    ///
+    /// assert!(worker.is_empty())
+    /// ```
+    #[must_use]
+    #[allow(clippy::borrow_interior_mutable_const)]
+    pub const fn new() -> Self {
+        const { assert!(N.is_power_of_two(), "deque capacity must be a power of two") };
+
+        Self {
+            slots: [Self::VACANT; N],
+            top: AtomicUsize::new(0),
+            bottom: AtomicUsize::new(0),
+            owns: PhantomData,
+        }
+    }
+}
+
+impl<T, const N: usize> core::fmt::Debug for Deque<T, N> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("Deque")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+impl<T, const N: usize> Default for Deque<T, N> {
+    /// Constructs an empty deque
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for Deque<T, N> {
+    fn drop(&mut self) {
+        let top = *self.top.get_mut();
+        let bottom = *self.bottom.get_mut();
+
+        let mut index = top;
+
+        while index != bottom {
+            let ptr = *self.slots[index % N].get_mut();
+            drop(unsafe { PtrCell::heap_reclaim(ptr) });
+
+            index = index.wrapping_add(1);
+        }
+    }
+}
+
+/// The owner's end of a [`Deque`]
+///
+/// Values are pushed and popped in last-in, first-out order. There is only one worker per deque,
+/// and it can't be shared between threads, though it can be moved to another one
+///
+/// Created by [`Deque::split`]
+pub struct Worker<'a, T, const N: usize> {
+    /// The deque
+    deque: &'a Deque<T, N>,
+
+    /// Keeps the worker from being shared, as only one thread may push and pop
+    unshared: PhantomData<Cell<()>>,
+}
+
+impl<'a, T, const N: usize> Worker<'a, T, N> {
+    /// Pushes a value to the worker's end of the deque
+    ///
+    /// # Errors
+    ///
+    /// Hands `value` back if the deque is full
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::deque::Deque;
+    ///
+    /// let mut deque: Deque<u8, 1> = Deque::new();
+    /// let (worker, _) = deque.split();
+    ///
+    /// assert_eq!(worker.push(1), Ok(()));
+    /// assert_eq!(worker.push(2), Err(2))
+    /// ```
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let deque = self.deque;
+
+        let bottom = deque.bottom.load(Ordering::Relaxed);
+        let top = deque.top.load(Ordering::Acquire);
+
+        if bottom.wrapping_sub(top) >= N {
+            return Err(value);
+        }
+
+        let ptr = PtrCell::heap_leak(Some(value));
+        deque.slot(bottom).store(ptr, Ordering::Relaxed);
+
+        Semantics::ReleaseOnly.fence();
+        deque
+            .bottom
+            .store(bottom.wrapping_add(1), Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Pops the most recently pushed value off the worker's end of the deque
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::deque::Deque;
+    ///
+    /// let mut deque: Deque<u8, 4> = Deque::new();
+    /// let (worker, _) = deque.split();
+    ///
+    /// let _ = worker.push(1);
+    /// let _ = worker.push(2);
+    ///
+    /// assert_eq!(worker.pop(), Some(2));
+    /// assert_eq!(worker.pop(), Some(1));
+    /// assert_eq!(worker.pop(), None)
+    /// ```
+    pub fn pop(&self) -> Option<T> {
+        let deque = self.deque;
+
+        let bottom = deque.bottom.load(Ordering::Relaxed).wrapping_sub(1);
+        deque.bottom.store(bottom, Ordering::Relaxed);
+
+        Semantics::Ordered.fence();
+        let top = deque.top.load(Ordering::Relaxed);
+
+        let remaining = Deque::<T, N>::distance(top, bottom);
+
+        if remaining < 0 {
+            deque
+                .bottom
+                .store(bottom.wrapping_add(1), Ordering::Relaxed);
+            return None;
+        }
+
+        let ptr = deque.slot(bottom).load(Ordering::Relaxed);
+
+        if remaining > 0 {
+            return unsafe { PtrCell::heap_reclaim(ptr) };
+        }
+
+        // This is the last value, so a stealer may be after it as well
+        let claiming = deque.top.compare_exchange(
+            top,
+            top.wrapping_add(1),
+            Ordering::SeqCst,
+            Ordering::Relaxed,
+        );
+
+        deque
+            .bottom
+            .store(bottom.wrapping_add(1), Ordering::Relaxed);
+
+        match claiming {
+            Ok(_) => unsafe { PtrCell::heap_reclaim(ptr) },
+            Err(_) => None,
+        }
+    }
+
+    /// Determines whether the deque is empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::deque::Deque;
+    ///
+    /// let mut deque: Deque<u8, 4> = Deque::new();
+    /// let (worker, _) = deque.split();
+    ///
+    /// assert!(worker.is_empty());
+    ///
+    /// let _ = worker.push(1);
+    /// assert!(!worker.is_empty())
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.deque.len() == 0
+    }
+
+    /// Returns a new stealer of the deque
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::deque::Deque;
+    ///
+    /// let mut deque: Deque<u8, 4> = Deque::new();
+    /// let (worker, _) = deque.split();
+    ///
+    /// let _ = worker.push(1);
+    ///
+    /// assert_eq!(worker.stealer().steal(), Some(1))
+    /// ```
+    #[inline]
+    pub const fn stealer(&self) -> Stealer<'a, T, N> {
+        Stealer { deque: self.deque }
+    }
+}
+
+impl<T, const N: usize> core::fmt::Debug for Worker<'_, T, N> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("Worker")
+            .field("deque", self.deque)
+            .finish()
+    }
+}
+
+/// The stealing end of a [`Deque`]
+///
+/// Values are stolen in first-in, first-out order. Stealers can be cloned and shared freely
+///
+/// Created by [`Deque::split`] or [`Worker::stealer`]
+pub struct Stealer<'a, T, const N: usize> {
+    /// The deque
+    deque: &'a Deque<T, N>,
+}
+
+impl<T, const N: usize> Stealer<'_, T, N> {
+    /// Steals the least recently pushed value from the deque
+    ///
+    /// Retries if another thread takes the value first, until the deque is empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::deque::Deque;
+    ///
+    /// let mut deque: Deque<u8, 4> = Deque::new();
+    /// let (worker, stealer) = deque.split();
+    ///
+    /// let _ = worker.push(1);
+    /// let _ = worker.push(2);
+    ///
+    /// assert_eq!(stealer.steal(), Some(1));
+    /// assert_eq!(worker.pop(), Some(2));
+    /// assert_eq!(stealer.steal(), None)
+    /// ```
+    pub fn steal(&self) -> Option<T> {
+        let deque = self.deque;
+
+        loop {
+            let top = deque.top.load(Ordering::Acquire);
+
+            Semantics::Ordered.fence();
+            let bottom = deque.bottom.load(Ordering::Acquire);
+
+            if Deque::<T, N>::distance(top, bottom) <= 0 {
+                return None;
+            }
+
+            // The slot can't be reused by the worker until `top` moves past it
+            let ptr = deque.slot(top).load(Ordering::Relaxed);
+
+            let claiming = deque.top.compare_exchange(
+                top,
+                top.wrapping_add(1),
+                Ordering::SeqCst,
+                Ordering::Relaxed,
+            );
+
+            if claiming.is_ok() {
+                return unsafe { PtrCell::heap_reclaim(ptr) };
+            }
+
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Determines whether the deque is empty
+    ///
+    /// The result may be outdated if the deque is being modified at the same time
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::deque::Deque;
+    ///
+    /// let mut deque: Deque<u8, 4> = Deque::new();
+    /// let (_, stealer) = deque.split();
+    ///
+    /// assert!(stealer.is_empty())
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.deque.len() == 0
+    }
+}
+
+impl<T, const N: usize> Clone for Stealer<'_, T, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { deque: self.deque }
+    }
+}
+
+impl<T, const N: usize> core::fmt::Debug for Stealer<'_, T, N> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("Stealer")
+            .field("deque", self.deque)
+            .finish()
+    }
+}
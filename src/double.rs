@@ -0,0 +1,194 @@
+//! Double buffering on top of two cells
+
+use crate::{AtomicU8, AtomicUsize, PtrCell, Semantics};
+use core::sync::atomic::Ordering;
+
+/// Two cells that let readers look at a consistent snapshot while a writer prepares the next one
+///
+/// A writer [publishes](Self::publish) into the inactive buffer and then flips the buffers in a
+/// single step. Readers never wait for writers and never see a half-written value. Writers wait
+/// for each other, and for the readers that are still looking at the snapshot before the previous
+/// one
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::DoubleBuffer;
+///
+/// let state = DoubleBuffer::new([0_u32; 3]);
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         for frame in 1..=100 {
+///             state.publish([frame; 3]);
+///         }
+///     });
+///
+///     for _ in 0..100 {
+///         let [x, y, z] = state.read();
+///         assert!(x == y && y == z)
+///     }
+/// })
+/// ```
+pub struct DoubleBuffer<T> {
+    /// The two buffers
+    ///
+    /// #### Invariants
+    ///
+    /// - The buffer at index `active` is never empty
+    /// - The other buffer is only modified by the writer that holds `writing`, and only while no
+    ///   readers are counted for it
+    buffers: [PtrCell<T>; 2],
+
+    /// Index of the buffer that readers look at. Either 0 or 1
+    active: AtomicUsize,
+
+    /// Number of readers that may be looking at each buffer
+    readers: [AtomicUsize; 2],
+
+    /// Whether a writer is publishing. Either 0 or 1
+    writing: AtomicU8,
+}
+
+// Readers share references to the value between threads, while writers drop values that may have
+// been published elsewhere
+unsafe impl<T: Send> Send for DoubleBuffer<T> {}
+unsafe impl<T: Send + Sync> Sync for DoubleBuffer<T> {}
+
+impl<T> DoubleBuffer<T> {
+    /// Returns a clone of the current snapshot
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::DoubleBuffer;
+    ///
+    /// let buffer = DoubleBuffer::new(String::from("first"));
+    /// buffer.publish("second".into());
+    ///
+    /// assert_eq!(buffer.read(), "second")
+    /// ```
+    #[inline]
+    pub fn read(&self) -> T
+    where
+        T: Clone,
+    {
+        self.read_with(T::clone)
+    }
+
+    /// Calls `f` with a reference to the current snapshot
+    ///
+    /// The snapshot stays valid until `f` returns, even if new values are published in the
+    /// meantime. Keep `f` short, as a writer may have to wait for it
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::DoubleBuffer;
+    ///
+    /// let buffer = DoubleBuffer::new(vec![1, 2, 3]);
+    ///
+    /// assert_eq!(buffer.read_with(|values| values.iter().sum::<i32>()), 6)
+    /// ```
+    pub fn read_with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        /// Stops counting a reader when dropped, even if `f` panics
+        struct Reading<'a>(&'a AtomicUsize);
+
+        impl Drop for Reading<'_> {
+            fn drop(&mut self) {
+                self.0.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        loop {
+            let index = self.active.load(Ordering::SeqCst);
+
+            self.readers[index].fetch_add(1, Ordering::SeqCst);
+            let _reading = Reading(&self.readers[index]);
+
+            // The buffers may have been flipped before this reader was counted. Then the buffer
+            // may be rewritten at any moment
+            if self.active.load(Ordering::SeqCst) != index {
+                continue;
+            }
+
+            let ptr = self.buffers[index].get_ptr(Semantics::Relaxed);
+
+            break f(unsafe { &*ptr });
+        }
+    }
+
+    /// Publishes a value as the new snapshot
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::DoubleBuffer;
+    ///
+    /// let buffer = DoubleBuffer::new('a');
+    ///
+    /// buffer.publish('b');
+    /// buffer.publish('c');
+    ///
+    /// assert_eq!(buffer.read(), 'c')
+    /// ```
+    pub fn publish(&self, value: T) {
+        while self.writing.swap(1, Ordering::Acquire) == 1 {
+            core::hint::spin_loop();
+        }
+
+        let inactive = 1 - self.active.load(Ordering::Relaxed);
+
+        while self.readers[inactive].load(Ordering::SeqCst) != 0 {
+            core::hint::spin_loop();
+        }
+
+        let stale = self.buffers[inactive].replace(Some(value), Semantics::Relaxed);
+        self.active.store(inactive, Ordering::SeqCst);
+
+        self.writing.store(0, Ordering::Release);
+
+        drop(stale);
+    }
+
+    /// Constructs a double buffer with an initial snapshot
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::DoubleBuffer;
+    ///
+    /// let buffer = DoubleBuffer::new(0xFAA);
+    ///
+    /// assert_eq!(buffer.read(), 0xFAA)
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(value: T) -> Self {
+        Self {
+            buffers: [PtrCell::from(value), PtrCell::default()],
+            active: AtomicUsize::new(0),
+            readers: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            writing: AtomicU8::new(0),
+        }
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for DoubleBuffer<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        self.read_with(|value| {
+            formatter
+                .debug_struct("DoubleBuffer")
+                .field("value", value)
+                .finish()
+        })
+    }
+}
+
+impl<T: Default> Default for DoubleBuffer<T> {
+    /// Constructs a double buffer with the default value as its snapshot
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
@@ -0,0 +1,196 @@
+//! Iterators that move values out of cells
+
+use crate::{PtrCell, Semantics};
+
+/// Iterator that repeatedly takes the value out of a cell until it stays empty
+///
+/// Created by [`PtrCell::drain`]
+pub struct Drain<'a, T> {
+    /// The drained cell
+    cell: &'a PtrCell<T>,
+
+    /// Semantics of every take
+    order: Semantics,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.cell.take(self.order)
+    }
+}
+
+impl<T> core::fmt::Debug for Drain<'_, T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("Drain")
+            .field("cell", self.cell)
+            .field("order", &self.order)
+            .finish()
+    }
+}
+
+/// Iterator over the nodes of a chain built with [`map_owner`](PtrCell::map_owner)
+///
+/// Every yielded node is detached from the rest of the chain, so its own cell is empty
+///
+/// Created by [`PtrCell::drain_chain`]
+pub struct DrainChain<T: AsMut<PtrCell<T>>> {
+    /// The nodes that haven't been yielded yet
+    rest: PtrCell<T>,
+}
+
+impl<T: AsMut<PtrCell<T>>> Iterator for DrainChain<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let rest = self.rest.value.get_mut();
+
+        let head_ptr = core::mem::replace(rest, core::ptr::null_mut());
+        let mut head = unsafe { PtrCell::heap_reclaim(head_ptr) }?;
+
+        *rest = core::mem::replace(head.as_mut().value.get_mut(), core::ptr::null_mut());
+
+        Some(head)
+    }
+}
+
+impl<T: AsMut<PtrCell<T>>> core::fmt::Debug for DrainChain<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("DrainChain")
+            .field("rest", &self.rest)
+            .finish()
+    }
+}
+
+impl<T> PtrCell<T> {
+    /// Returns an iterator that takes out the cell's value until the cell stays empty
+    ///
+    /// Values inserted by other threads while iterating are yielded as well
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::from(45);
+    /// let mut drain = cell.drain(Relaxed);
+    ///
+    /// assert_eq!(drain.next(), Some(45));
+    /// assert_eq!(drain.next(), None);
+    ///
+    /// cell.set(Some(54), Relaxed);
+    /// assert_eq!(drain.next(), Some(54))
+    /// ```
+    #[inline]
+    pub const fn drain(&self, order: Semantics) -> Drain<'_, T> {
+        Drain { cell: self, order }
+    }
+
+    /// Detaches the chain of nodes built with [`map_owner`](Self::map_owner) and returns an
+    /// iterator over them
+    ///
+    /// The nodes are yielded from the most recently inserted one
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// struct Node<T> {
+    ///     pub value: T,
+    ///     pub next: PtrCell<Self>,
+    /// }
+    ///
+    /// impl<T> AsMut<PtrCell<Self>> for Node<T> {
+    ///     fn as_mut(&mut self) -> &mut PtrCell<Self> {
+    ///         &mut self.next
+    ///     }
+    /// }
+    ///
+    /// let cell = PtrCell::default();
+    ///
+    /// for value in 1..=3 {
+    ///     cell.map_owner(|next| Node { value, next }, Relaxed);
+    /// }
+    ///
+    /// let values = cell.drain_chain(Relaxed).map(|node| node.value);
+    ///
+    /// assert!(values.eq([3, 2, 1]));
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn drain_chain(&self, order: Semantics) -> DrainChain<T>
+    where
+        T: AsMut<Self>,
+    {
+        let head = self.take_ptr(order);
+        let rest = unsafe { Self::from_ptr(head) };
+
+        DrainChain { rest }
+    }
+
+    /// Like [`drain_chain`](Self::drain_chain), but yields the nodes from the earliest inserted one
+    ///
+    /// The detached chain is reversed in place by relinking its nodes, so consumers get them in
+    /// queue order without collecting them first
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, PtrCell, Semantics::Relaxed};
+    ///
+    /// let queue = PtrCell::default();
+    ///
+    /// for job in ["first", "second", "third"] {
+    ///     Node::push(&queue, job, Relaxed);
+    /// }
+    ///
+    /// let jobs = queue.drain_chain_fifo(Relaxed).map(|node| node.value);
+    ///
+    /// assert!(jobs.eq(["first", "second", "third"]))
+    /// ```
+    pub fn drain_chain_fifo(&self, order: Semantics) -> DrainChain<T>
+    where
+        T: AsMut<Self>,
+    {
+        let mut previous = core::ptr::null_mut();
+        let mut current = self.take_ptr(order);
+
+        while !current.is_null() {
+            let node = unsafe { &mut *current };
+            let next = core::mem::replace(node.as_mut().value.get_mut(), previous);
+
+            previous = current;
+            current = next;
+        }
+
+        let rest = unsafe { Self::from_ptr(previous) };
+
+        DrainChain { rest }
+    }
+}
+
+impl<T> IntoIterator for PtrCell<T> {
+    type Item = T;
+    type IntoIter = core::option::IntoIter<T>;
+
+    /// Consumes the cell, returning an iterator over its value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::PtrCell;
+    ///
+    /// let cell = PtrCell::from('a');
+    ///
+    /// assert!(cell.into_iter().eq(['a']))
+    /// ```
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_inner().into_iter()
+    }
+}
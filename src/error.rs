@@ -0,0 +1,168 @@
+//! Error types
+
+/// Error returned when memory for a value couldn't be allocated
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::AllocError;
+///
+/// assert_eq!(AllocError.to_string(), "memory allocation failed")
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+pub struct AllocError;
+
+impl core::fmt::Display for AllocError {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("memory allocation failed")
+    }
+}
+
+impl core::error::Error for AllocError {}
+
+/// Error returned when a [`ClosablePtrCell`](crate::ClosablePtrCell) has been closed
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::ClosedError;
+///
+/// assert_eq!(ClosedError.to_string(), "the cell has been closed")
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+pub struct ClosedError;
+
+impl core::fmt::Display for ClosedError {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("the cell has been closed")
+    }
+}
+
+impl core::error::Error for ClosedError {}
+
+/// Error returned when a value couldn't be inserted into a cell. Hands the value back
+///
+/// # Usage
+///
+#[doc = alloc_doctest!()]
+/// use ptr_cell::{ClosablePtrCell, InsertError, Semantics::Relaxed};
+///
+/// let cell = ClosablePtrCell::new();
+/// assert_eq!(cell.try_insert(1, Relaxed), Ok(()));
+/// assert_eq!(cell.try_insert(2, Relaxed), Err(InsertError::Occupied(2)));
+///
+/// let _ = cell.close(Relaxed);
+/// assert_eq!(cell.try_insert(3, Relaxed), Err(InsertError::Closed(3)))
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum InsertError<T> {
+    /// The cell already holds a value
+    Occupied(T),
+
+    /// The cell has been closed
+    Closed(T),
+}
+
+impl<T> InsertError<T> {
+    /// Returns the value that couldn't be inserted
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::InsertError;
+    ///
+    /// assert_eq!(InsertError::Closed('x').into_inner(), 'x')
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Occupied(value) | Self::Closed(value) => value,
+        }
+    }
+}
+
+impl<T> core::fmt::Display for InsertError<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Occupied(_) => formatter.write_str("the cell is occupied"),
+            Self::Closed(_) => formatter.write_str("the cell has been closed"),
+        }
+    }
+}
+
+impl<T: core::fmt::Debug> core::error::Error for InsertError<T> {}
+
+/// Error returned when a value couldn't be taken out of a cell
+///
+/// # Usage
+///
+#[doc = alloc_doctest!()]
+/// use ptr_cell::{ClosablePtrCell, Semantics::Relaxed, TakeError};
+///
+/// let cell = ClosablePtrCell::<u8>::new();
+/// assert_eq!(cell.try_take(Relaxed), Err(TakeError::Empty));
+///
+/// let _ = cell.close(Relaxed);
+/// assert_eq!(cell.try_take(Relaxed), Err(TakeError::Closed))
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum TakeError {
+    /// The cell is empty for now
+    Empty,
+
+    /// The cell has been closed and won't hold a value ever again
+    Closed,
+}
+
+impl core::fmt::Display for TakeError {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Empty => formatter.write_str("the cell is empty"),
+            Self::Closed => formatter.write_str("the cell has been closed"),
+        }
+    }
+}
+
+impl core::error::Error for TakeError {}
+
+impl From<ClosedError> for TakeError {
+    #[inline]
+    fn from(_: ClosedError) -> Self {
+        Self::Closed
+    }
+}
+
+/// Error returned when an [`Ordering`](core::sync::atomic::Ordering) has no matching
+/// [`Semantics`](crate::Semantics)
+///
+/// Only possible with orderings that are added to the language in the future
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+pub struct OrderingError;
+
+impl core::fmt::Display for OrderingError {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("unknown memory ordering")
+    }
+}
+
+impl core::error::Error for OrderingError {}
+
+/// Error returned when a string doesn't name any [`Semantics`](crate::Semantics)
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::{ParseSemanticsError, Semantics};
+///
+/// assert_eq!("sequential".parse::<Semantics>(), Err(ParseSemanticsError))
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+pub struct ParseSemanticsError;
+
+impl core::fmt::Display for ParseSemanticsError {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("unknown semantics")
+    }
+}
+
+impl core::error::Error for ParseSemanticsError {}
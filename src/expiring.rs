@@ -0,0 +1,291 @@
+//! Thread-safe cell whose values expire
+
+use crate::{acquiring, non_null, PtrCell, Semantics};
+use core::time::Duration;
+
+/// Source of the current time for an [`ExpiringCell`]
+///
+/// The time is measured from an arbitrary fixed point, like the start of the program, and must
+/// never go backwards. Closures returning a [`Duration`] are clocks too, which suits tick counters
+/// on targets without `std`
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::Clock;
+/// use std::time::Duration;
+///
+/// struct Ticks;
+///
+/// impl Clock for Ticks {
+///     fn now(&self) -> Duration {
+///         Duration::from_millis(1200)
+///     }
+/// }
+///
+/// assert_eq!(Ticks.now().as_secs(), 1)
+/// ```
+pub trait Clock {
+    /// Returns the time elapsed since the clock's fixed point
+    fn now(&self) -> Duration;
+}
+
+impl<F: Fn() -> Duration> Clock for F {
+    #[inline]
+    fn now(&self) -> Duration {
+        self()
+    }
+}
+
+/// Monotonic [`Clock`] backed by [`Instant`](std::time::Instant)
+///
+/// Measures time from its first use in the program
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::{Clock, StdClock};
+///
+/// let earlier = StdClock.now();
+///
+/// assert!(StdClock.now() >= earlier)
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StdClock;
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    fn now(&self) -> Duration {
+        static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+
+        EPOCH.get_or_init(std::time::Instant::now).elapsed()
+    }
+}
+
+/// Value stored along with the time it was stored at
+struct Stamped<T> {
+    /// The value
+    value: T,
+
+    /// Time of the clock when the value was stored
+    stored: Duration,
+}
+
+/// Thread-safe cell that treats values older than a time-to-live as absent
+///
+/// Every value is stamped with the time of a [`Clock`] when it's inserted. Once the value has
+/// been in the cell for longer than the TTL, it's never handed out again. Expired values are
+/// dropped lazily, when an operation comes across them
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{ExpiringCell, Semantics::Coupled};
+/// use std::time::{Duration, Instant};
+///
+/// let start = Instant::now();
+/// let frame = ExpiringCell::new(Duration::from_millis(50), || start.elapsed());
+///
+/// frame.set(Some([0_u8; 16]), Coupled);
+/// assert_eq!(frame.get_cloned(Coupled), Some([0; 16]));
+///
+/// std::thread::sleep(Duration::from_millis(60));
+/// assert_eq!(frame.get_cloned(Coupled), None)
+/// ```
+pub struct ExpiringCell<T, C> {
+    /// The stamped value
+    cell: PtrCell<Stamped<T>>,
+
+    /// How long values stay fresh
+    ttl: Duration,
+
+    /// Source of the stamps
+    clock: C,
+}
+
+impl<T, C: Clock> ExpiringCell<T, C> {
+    /// Inserts a value into the cell, stamped with the current time
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{ExpiringCell, Semantics::Relaxed};
+    /// use std::time::Duration;
+    ///
+    /// let cell = ExpiringCell::new(Duration::from_secs(1), || Duration::ZERO);
+    /// cell.set(Some(1776), Relaxed);
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(1776))
+    /// ```
+    pub fn set(&self, slot: Option<T>, order: Semantics) {
+        let stored = self.clock.now();
+        let slot = slot.map(|value| Stamped { value, stored });
+
+        self.cell.set(slot, order);
+    }
+
+    /// Takes out the cell's value, unless it has expired
+    ///
+    /// An expired value is dropped
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{ExpiringCell, Semantics::Relaxed};
+    /// use std::{cell::Cell, time::Duration};
+    ///
+    /// let time = Cell::new(Duration::ZERO);
+    /// let cell = ExpiringCell::new(Duration::from_secs(5), || time.get());
+    ///
+    /// cell.set(Some("fresh"), Relaxed);
+    /// assert_eq!(cell.take(Relaxed), Some("fresh"));
+    ///
+    /// cell.set(Some("stale"), Relaxed);
+    /// time.set(Duration::from_secs(6));
+    /// assert_eq!(cell.take(Relaxed), None)
+    /// ```
+    pub fn take(&self, order: Semantics) -> Option<T> {
+        let stamped = self.cell.take(order)?;
+
+        if self.is_expired(&stamped) {
+            return None;
+        }
+
+        Some(stamped.value)
+    }
+
+    /// Returns a clone of the cell's value, unless it has expired
+    ///
+    /// As values can't be borrowed from a shared cell, the value is taken out for cloning and put
+    /// back afterwards, like in [`contains_value`](PtrCell::contains_value). An expired value is
+    /// dropped instead of being put back. The value is always acquired, as cloning reads it
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{ExpiringCell, Semantics::Coupled};
+    /// use std::time::Duration;
+    ///
+    /// let cell = ExpiringCell::new(Duration::from_secs(1), || Duration::ZERO);
+    /// cell.set(Some(String::from("frame")), Coupled);
+    ///
+    /// assert_eq!(cell.get_cloned(Coupled).as_deref(), Some("frame"));
+    /// assert_eq!(cell.take(Coupled).as_deref(), Some("frame"))
+    /// ```
+    pub fn get_cloned(&self, order: Semantics) -> Option<T>
+    where
+        T: Clone,
+    {
+        let taking = self
+            .cell
+            .value
+            .swap(core::ptr::null_mut(), acquiring(order.read_write()));
+
+        let ptr = non_null(taking)?;
+
+        let restore = Restore {
+            cell: &self.cell,
+            ptr,
+            order,
+        };
+
+        let stamped = unsafe { &*ptr };
+
+        if self.is_expired(stamped) {
+            core::mem::forget(restore);
+            drop(unsafe { PtrCell::heap_reclaim(ptr) });
+
+            return None;
+        }
+
+        Some(stamped.value.clone())
+    }
+
+    /// Returns how long values stay fresh
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::ExpiringCell;
+    /// use std::time::Duration;
+    ///
+    /// let cell = ExpiringCell::<u8, _>::new(Duration::from_secs(3), || Duration::ZERO);
+    ///
+    /// assert_eq!(cell.ttl(), Duration::from_secs(3))
+    /// ```
+    #[inline]
+    pub const fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Constructs an empty cell whose values expire after `ttl` by the time of `clock`
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{ExpiringCell, Semantics::Relaxed};
+    /// use std::time::Duration;
+    ///
+    /// fn ticks() -> Duration {
+    ///     Duration::from_millis(40)
+    /// }
+    ///
+    /// static SESSION: ExpiringCell<u64, fn() -> Duration> =
+    ///     ExpiringCell::new(Duration::from_secs(900), ticks);
+    ///
+    /// assert_eq!(SESSION.take(Relaxed), None)
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(ttl: Duration, clock: C) -> Self {
+        Self {
+            cell: PtrCell::empty(),
+            ttl,
+            clock,
+        }
+    }
+
+    /// Determines whether `stamped` has been in the cell for longer than the TTL
+    fn is_expired(&self, stamped: &Stamped<T>) -> bool {
+        self.clock.now().saturating_sub(stamped.stored) > self.ttl
+    }
+}
+
+impl<T, C> core::fmt::Debug for ExpiringCell<T, C> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("ExpiringCell")
+            .field("cell", &self.cell)
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Puts a value back into the cell, even if cloning it panics
+struct Restore<'a, T> {
+    /// The cell that the value was taken out of
+    cell: &'a PtrCell<T>,
+
+    /// The value
+    ptr: *mut T,
+
+    /// Semantics of the restoration
+    order: Semantics,
+}
+
+impl<T> Drop for Restore<'_, T> {
+    fn drop(&mut self) {
+        let restoring = self.cell.value.compare_exchange(
+            core::ptr::null_mut(),
+            self.ptr,
+            self.order.read_write(),
+            self.order.read(),
+        );
+
+        // Another value was inserted in the meantime, which replaces this one
+        if restoring.is_err() {
+            drop(unsafe { PtrCell::heap_reclaim(self.ptr) });
+        }
+    }
+}
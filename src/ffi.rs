@@ -0,0 +1,200 @@
+//! C interface for cells, enabled by the `ffi` feature
+//!
+//! ## Layout
+//!
+//! A `PtrCell<T>` has the same size, alignment, and ABI as a `*mut T`, and an empty cell is a
+//! null pointer. C code can thus embed cells in its own structs as `void *` fields, and a
+//! zero-initialized field is a valid empty cell:
+//!
+//! ```c
+//! struct session {
+//!     int id;
+//!     void *pending; /* PtrCell<Job>, zeroed = empty */
+//! };
+//! ```
+//!
+//! Only the functions generated by [`export_ffi!`](crate::export_ffi) may touch such a field, and
+//! the field may only be moved while no thread operates on it. This guarantee doesn't hold with
+//! the `allocator_api` or `shuttle` features, so the module and the macro are left out while
+//! either is enabled
+//!
+//! ## Functions
+//!
+//! [`export_ffi!`](crate::export_ffi) generates `extern "C"` functions for one value type. The
+//! values are passed by value, so the type must be FFI-safe, like a `#[repr(C)]` struct or a raw
+//! pointer. Every operation has [`Coupled`](crate::Semantics::Coupled) semantics, which is what
+//! C11's `memory_order_acq_rel` gives
+//!
+//! A panic can't unwind into C. If dropping a value panics, the process is aborted
+
+use crate::PtrCell;
+use core::mem::{align_of, size_of};
+
+// The layout guarantee
+const _: () = {
+    assert!(size_of::<PtrCell<u8>>() == size_of::<*mut u8>());
+    assert!(align_of::<PtrCell<u8>>() == align_of::<*mut u8>());
+};
+
+/// Generates `extern "C"` functions that operate on cells of one value type
+///
+/// With just a type, the functions are named `ptr_cell_new`, `ptr_cell_free`, `ptr_cell_set`,
+/// `ptr_cell_take`, and `ptr_cell_is_empty`. Each name can also be chosen, which is necessary to
+/// export several types from one library. The functions correspond to these C declarations:
+///
+/// ```c
+/// void *ptr_cell_new(void);               /* Allocates an empty cell */
+/// void ptr_cell_free(void *cell);         /* Drops a cell from `ptr_cell_new` and its value */
+/// void ptr_cell_set(const void *cell, T value);
+/// bool ptr_cell_take(const void *cell, T *out); /* Writes to `out` only if it returns true */
+/// bool ptr_cell_is_empty(const void *cell);
+/// ```
+///
+/// Apart from `ptr_cell_new` and `ptr_cell_free`, the functions work on any cell, including ones
+/// embedded in C structs and cells owned by Rust code. See the [module docs](crate::ffi) for the
+/// layout guarantees
+///
+/// # Safety
+///
+/// The generated functions are `unsafe`. Every cell pointer must point to a live cell of the
+/// exported type, and `out` must be valid for writes. Cells may only be passed to
+/// `ptr_cell_free` once, and only if they were allocated by `ptr_cell_new`
+///
+/// # Usage
+///
+/// ```rust
+/// #[repr(C)]
+/// #[derive(Debug, PartialEq)]
+/// pub struct Job {
+///     id: u32,
+///     priority: u8,
+/// }
+///
+/// ptr_cell::export_ffi! {
+///     Job {
+///         new: job_cell_new,
+///         free: job_cell_free,
+///         set: job_cell_set,
+///         take: job_cell_take,
+///         is_empty: job_cell_is_empty,
+///     }
+/// }
+///
+/// // As C would call them
+/// unsafe {
+///     let cell = job_cell_new();
+///     job_cell_set(cell, Job { id: 7, priority: 1 });
+///
+///     let mut job = std::mem::MaybeUninit::uninit();
+///     assert!(job_cell_take(cell, job.as_mut_ptr()));
+///     assert_eq!(job.assume_init(), Job { id: 7, priority: 1 });
+///
+///     assert!(job_cell_is_empty(cell));
+///     job_cell_free(cell)
+/// }
+/// ```
+#[macro_export]
+macro_rules! export_ffi {
+    ($ty:ty) => {
+        $crate::export_ffi! {
+            $ty {
+                new: ptr_cell_new,
+                free: ptr_cell_free,
+                set: ptr_cell_set,
+                take: ptr_cell_take,
+                is_empty: ptr_cell_is_empty,
+            }
+        }
+    };
+
+    ($ty:ty {
+        new: $new:ident,
+        free: $free:ident,
+        set: $set:ident,
+        take: $take:ident,
+        is_empty: $is_empty:ident $(,)?
+    }) => {
+        /// Allocates an empty cell
+        #[no_mangle]
+        pub extern "C" fn $new() -> *mut $crate::PtrCell<$ty> {
+            $crate::ffi::__new()
+        }
+
+        /// Drops a cell allocated by the matching `new` function, along with its value
+        ///
+        /// # Safety
+        ///
+        /// `cell` must be null or come from the matching `new` function, and must not be used
+        /// afterwards
+        #[no_mangle]
+        pub unsafe extern "C" fn $free(cell: *mut $crate::PtrCell<$ty>) {
+            unsafe { $crate::ffi::__free(cell) }
+        }
+
+        /// Inserts a value into the cell, dropping the previous one
+        ///
+        /// # Safety
+        ///
+        /// `cell` must point to a live cell
+        #[no_mangle]
+        pub unsafe extern "C" fn $set(cell: *const $crate::PtrCell<$ty>, value: $ty) {
+            unsafe { $crate::ffi::__set(cell, value) }
+        }
+
+        /// Takes out the cell's value and writes it to `out`. Returns whether there was a value
+        ///
+        /// # Safety
+        ///
+        /// `cell` must point to a live cell, and `out` must be valid for writes
+        #[no_mangle]
+        pub unsafe extern "C" fn $take(cell: *const $crate::PtrCell<$ty>, out: *mut $ty) -> bool {
+            unsafe { $crate::ffi::__take(cell, out) }
+        }
+
+        /// Determines whether the cell is empty
+        ///
+        /// # Safety
+        ///
+        /// `cell` must point to a live cell
+        #[no_mangle]
+        pub unsafe extern "C" fn $is_empty(cell: *const $crate::PtrCell<$ty>) -> bool {
+            unsafe { $crate::ffi::__is_empty(cell) }
+        }
+    };
+}
+
+/// Semantics of every operation made through the C interface
+const ORDER: crate::Semantics = crate::Semantics::Coupled;
+
+#[doc(hidden)]
+pub fn __new<T>() -> *mut PtrCell<T> {
+    alloc::boxed::Box::into_raw(alloc::boxed::Box::new(PtrCell::empty()))
+}
+
+#[doc(hidden)]
+pub unsafe fn __free<T>(cell: *mut PtrCell<T>) {
+    if let Some(cell) = crate::non_null(cell) {
+        drop(unsafe { alloc::boxed::Box::from_raw(cell) });
+    }
+}
+
+#[doc(hidden)]
+pub unsafe fn __set<T>(cell: *const PtrCell<T>, value: T) {
+    unsafe { &*cell }.set(Some(value), ORDER);
+}
+
+#[doc(hidden)]
+pub unsafe fn __take<T>(cell: *const PtrCell<T>, out: *mut T) -> bool {
+    match unsafe { &*cell }.take(ORDER) {
+        Some(value) => {
+            unsafe { out.write(value) };
+            true
+        }
+        None => false,
+    }
+}
+
+#[doc(hidden)]
+pub unsafe fn __is_empty<T>(cell: *const PtrCell<T>) -> bool {
+    unsafe { &*cell }.is_empty(ORDER)
+}
@@ -0,0 +1,266 @@
+//! Thread-safe cell that stores its value inline
+
+use crate::{AtomicU8, Semantics};
+use core::{cell::UnsafeCell, mem::MaybeUninit, sync::atomic::Ordering};
+
+/// The cell contains no value
+const EMPTY: u8 = 0;
+
+/// A thread is currently moving values in or out of the cell
+const BUSY: u8 = 1;
+
+/// The cell contains a value
+const FULL: u8 = 2;
+
+/// Thread-safe cell that stores its value inline, without allocating
+///
+/// This is a sibling of [`PtrCell`](crate::PtrCell) for environments where heap allocation is
+/// unavailable or too expensive. The value is kept inside the cell itself and guarded by an atomic
+/// state word. While a thread moves a value in or out, others briefly spin until it's done
+///
+/// Because the value is accessed directly, the cell always synchronizes at least as strongly as
+/// [`Coupled`](Semantics::Coupled) semantics. [`Ordered`](Semantics::Ordered) semantics are
+/// respected, while [`Custom`](Semantics::Custom) ones are treated as `Coupled`
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{InlineCell, Semantics::Relaxed};
+///
+/// let cell: InlineCell<u16> = 0x81D.into();
+///
+/// assert_eq!(cell.replace(Some(2047), Relaxed), Some(0x81D));
+/// assert_eq!(cell.is_empty(Relaxed), false);
+/// assert_eq!(cell.take(Relaxed), Some(2047))
+/// ```
+pub struct InlineCell<T> {
+    /// Either [`EMPTY`], [`BUSY`], or [`FULL`]
+    state: AtomicU8,
+
+    /// Storage for the contained value
+    ///
+    /// #### Invariants
+    ///
+    /// - **If `state` is [`FULL`]**: Must be initialized
+    /// - **If `state` is [`BUSY`]**: May only be accessed by the thread that set the state
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// The value is only ever accessed by the thread that owns the `BUSY` state, so sharing the cell
+// amounts to sending the value between threads
+unsafe impl<T: Send> Send for InlineCell<T> {}
+unsafe impl<T: Send> Sync for InlineCell<T> {}
+
+impl<T> InlineCell<T> {
+    /// Takes out the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{InlineCell, Semantics::Relaxed};
+    ///
+    /// let cell: InlineCell<u8> = 45.into();
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(45));
+    /// assert_eq!(cell.take(Relaxed), None)
+    /// ```
+    #[inline]
+    pub fn take(&self, order: Semantics) -> Option<T> {
+        self.replace(None, order)
+    }
+
+    /// Inserts a value into the cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{InlineCell, Semantics::Relaxed};
+    ///
+    /// let cell = InlineCell::default();
+    /// cell.set(Some(1776), Relaxed);
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(1776))
+    /// ```
+    #[inline]
+    pub fn set(&self, slot: Option<T>, order: Semantics) {
+        let _ = self.replace(slot, order);
+    }
+
+    /// Replaces the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{InlineCell, Semantics::Relaxed};
+    ///
+    /// let cell = InlineCell::from('a');
+    ///
+    /// assert_eq!(cell.replace(Some('b'), Relaxed), Some('a'));
+    /// assert_eq!(cell.take(Relaxed), Some('b'))
+    /// ```
+    #[must_use = "use `.set()` if you don't need the old value"]
+    pub fn replace(&self, slot: Option<T>, order: Semantics) -> Option<T> {
+        let order = match order {
+            Semantics::Ordered => Semantics::Ordered,
+            _ => Semantics::Coupled,
+        };
+        let was_full = self.lock(order);
+
+        let value = unsafe { &mut *self.value.get() };
+        let old = was_full.then(|| unsafe { value.assume_init_read() });
+
+        let state = match slot {
+            Some(new) => {
+                value.write(new);
+                FULL
+            }
+            None => EMPTY,
+        };
+
+        self.state.store(state, order.write());
+
+        old
+    }
+
+    /// Mutably borrows the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{InlineCell, Semantics::Relaxed};
+    ///
+    /// let mut number = InlineCell::from(1);
+    /// *number.get_mut().expect("The cell should contain a value") += 1;
+    ///
+    /// assert_eq!(number.take(Relaxed), Some(2))
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        match *self.state.get_mut() {
+            FULL => Some(unsafe { self.value.get_mut().assume_init_mut() }),
+            _ => None,
+        }
+    }
+
+    /// Determines whether this cell is empty
+    ///
+    /// A cell whose value is being replaced by another thread isn't considered empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{InlineCell, Semantics::Relaxed};
+    ///
+    /// let cell = InlineCell::<[u8; 3]>::default();
+    ///
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_empty(&self, order: Semantics) -> bool {
+        self.state.load(order.read()) == EMPTY
+    }
+
+    /// Constructs a cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{InlineCell, Semantics::Relaxed};
+    ///
+    /// let cell = InlineCell::new(Some(0xFAA));
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(0xFAA));
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(slot: Option<T>) -> Self {
+        let Some(value) = slot else {
+            return Self::empty();
+        };
+
+        Self {
+            state: AtomicU8::new(FULL),
+            value: UnsafeCell::new(MaybeUninit::new(value)),
+        }
+    }
+
+    /// Constructs an empty cell
+    ///
+    /// Unlike [`new`](Self::new), this can be used to initialize statics
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{InlineCell, Semantics::Relaxed};
+    ///
+    /// static CELL: InlineCell<u16> = InlineCell::empty();
+    ///
+    /// CELL.set(Some(0xFAA), Relaxed);
+    /// assert_eq!(CELL.take(Relaxed), Some(0xFAA))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self {
+            state: AtomicU8::new(EMPTY),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Spins until the cell is acquired in the [`BUSY`] state. Returns whether the cell was full
+    fn lock(&self, order: Semantics) -> bool {
+        let mut state = self.state.load(Ordering::Relaxed);
+
+        loop {
+            if state == BUSY {
+                state = self.state.load(Ordering::Relaxed);
+            } else {
+                let (success, failure) = (order.read(), Ordering::Relaxed);
+                let locking = self
+                    .state
+                    .compare_exchange_weak(state, BUSY, success, failure);
+
+                match locking {
+                    Ok(_) => return state == FULL,
+                    Err(current) => state = current,
+                }
+            }
+
+            core::hint::spin_loop();
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for InlineCell<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("InlineCell")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Default for InlineCell<T> {
+    /// Constructs an empty cell
+    #[inline]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T> Drop for InlineCell<T> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(value) = self.get_mut() {
+            unsafe { core::ptr::drop_in_place(value) };
+        }
+    }
+}
+
+impl<T> From<T> for InlineCell<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(Some(value))
+    }
+}
@@ -0,0 +1,239 @@
+//! Cells for passing values in and out of interrupt handlers
+//!
+//! Interrupt handlers on embedded targets usually must not touch the heap: the allocator may be
+//! locked by the very code that was interrupted. An [`IsrCell`] has two kinds of methods.
+//! [`set_from_isr`](IsrCell::set_from_isr) and [`take_in_isr`](IsrCell::take_in_isr) only ever
+//! move pre-allocated boxes in and out with a single atomic operation, so they never allocate or
+//! free memory and may be called from a handler. The others move plain values and are meant for
+//! thread or main-loop context, where allocating is fine
+//!
+//! A box that they hand back must not be dropped inside the handler either, as that frees its
+//! memory. Pass it on to thread context through another cell instead
+//!
+//! The guarantee relies on the atomics backend being lock-free, like for the `signal_safe` subset.
+//! On targets without atomic pointers, the `critical-section` backend works too, as long as its
+//! critical sections disable interrupts. The `debug-track` feature records ownership under a
+//! spinlock, so it must not be enabled in firmware that uses this module
+//!
+//! # Usage
+//!
+#![doc = doctest!()]
+//! use ptr_cell::{isr::IsrCell, Semantics::Coupled};
+//!
+//! static FREE: IsrCell<[u8; 64]> = IsrCell::empty();
+//! static READY: IsrCell<[u8; 64]> = IsrCell::empty();
+//!
+//! fn handler() {
+//!     let Some(mut frame) = FREE.take_in_isr(Coupled) else {
+//!         return;
+//!     };
+//!
+//!     frame.fill(0xFF);
+//!
+//!     if let Some(unread) = READY.set_from_isr(frame, Coupled) {
+//!         // Nothing can refill `FREE` while the handler runs, so it's still empty
+//!         let empty = FREE.set_from_isr(unread, Coupled);
+//!         debug_assert!(empty.is_none());
+//!     }
+//! }
+//!
+//! FREE.set(Some([0; 64]), Coupled);
+//! handler();
+//!
+//! assert_eq!(READY.take(Coupled), Some([0xFF; 64]))
+//! ```
+//!
+//! On Cortex-M, the handler is an interrupt service routine, and the main loop unmasks its
+//! interrupt in the NVIC once the first buffer is in place:
+//!
+//! ```rust,ignore
+//! use cortex_m::peripheral::NVIC;
+//! use ptr_cell::{isr::IsrCell, Semantics::Coupled};
+//! use stm32f4xx_hal::pac::{interrupt, Interrupt};
+//!
+//! static FREE: IsrCell<[u8; 64]> = IsrCell::empty();
+//! static READY: IsrCell<[u8; 64]> = IsrCell::empty();
+//!
+//! #[interrupt]
+//! fn USART1() {
+//!     let Some(mut frame) = FREE.take_in_isr(Coupled) else {
+//!         return;
+//!     };
+//!
+//!     read_frame(&mut frame);
+//!
+//!     if let Some(unread) = READY.set_from_isr(frame, Coupled) {
+//!         let empty = FREE.set_from_isr(unread, Coupled);
+//!         debug_assert!(empty.is_none());
+//!     }
+//! }
+//!
+//! #[cortex_m_rt::entry]
+//! fn main() -> ! {
+//!     FREE.set(Some([0; 64]), Coupled);
+//!     unsafe { NVIC::unmask(Interrupt::USART1) };
+//!
+//!     loop {
+//!         if let Some(frame) = READY.take(Coupled) {
+//!             handle_frame(&frame);
+//!             FREE.set(Some(frame), Coupled);
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::{PtrCell, Semantics};
+use alloc::boxed::Box;
+
+/// Thread-safe cell with allocation-free methods for interrupt handlers
+///
+/// See the [module documentation](self) for an overview
+///
+/// This is a thin wrapper around a [`PtrCell`], so it's the same size as a pointer
+pub struct IsrCell<T> {
+    /// The cell
+    cell: PtrCell<T>,
+}
+
+impl<T> IsrCell<T> {
+    /// Inserts a boxed value into the cell without allocating, returning the previous one
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{isr::IsrCell, Semantics::Relaxed};
+    ///
+    /// let cell = IsrCell::empty();
+    ///
+    /// assert_eq!(cell.set_from_isr(Box::new(1), Relaxed), None);
+    /// assert_eq!(cell.set_from_isr(Box::new(2), Relaxed), Some(Box::new(1)))
+    /// ```
+    #[inline]
+    #[must_use = "dropping the previous value frees its memory"]
+    pub fn set_from_isr(&self, boxed: Box<T>, order: Semantics) -> Option<Box<T>> {
+        let new_ptr = PtrCell::from(boxed).leak();
+        let old_ptr = self.cell.replace_ptr(new_ptr, order);
+
+        unsafe { crate::into_box(old_ptr) }
+    }
+
+    /// Takes out the cell's value without freeing its allocation
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{isr::IsrCell, Semantics::Relaxed};
+    ///
+    /// let cell = IsrCell::from(Box::new('a'));
+    ///
+    /// assert_eq!(cell.take_in_isr(Relaxed), Some(Box::new('a')));
+    /// assert_eq!(cell.take_in_isr(Relaxed), None)
+    /// ```
+    #[inline]
+    #[must_use = "dropping the value frees its memory"]
+    pub fn take_in_isr(&self, order: Semantics) -> Option<Box<T>> {
+        let ptr = self.cell.take_ptr(order);
+
+        unsafe { crate::into_box(ptr) }
+    }
+
+    /// Determines whether the cell is empty
+    ///
+    /// Safe to call from an interrupt handler
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{isr::IsrCell, Semantics::Relaxed};
+    ///
+    /// let cell = IsrCell::<u8>::empty();
+    ///
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_empty(&self, order: Semantics) -> bool {
+        self.cell.is_empty(order)
+    }
+
+    /// Inserts a value into the cell
+    ///
+    /// **Allocates, so it must not be called from an interrupt handler**
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{isr::IsrCell, Semantics::Relaxed};
+    ///
+    /// let cell = IsrCell::empty();
+    /// cell.set(Some(1776), Relaxed);
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(1776))
+    /// ```
+    #[inline]
+    pub fn set(&self, slot: Option<T>, order: Semantics) {
+        self.cell.set(slot, order);
+    }
+
+    /// Takes out the cell's value
+    ///
+    /// **Frees memory, so it must not be called from an interrupt handler**
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{isr::IsrCell, Semantics::Relaxed};
+    ///
+    /// let cell = IsrCell::from(Box::new(45));
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(45));
+    /// assert_eq!(cell.take(Relaxed), None)
+    /// ```
+    #[inline]
+    pub fn take(&self, order: Semantics) -> Option<T> {
+        self.cell.take(order)
+    }
+
+    /// Constructs an empty cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{isr::IsrCell, Semantics::Relaxed};
+    ///
+    /// static COMMAND: IsrCell<[u8; 16]> = IsrCell::empty();
+    ///
+    /// assert!(COMMAND.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self {
+            cell: PtrCell::empty(),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for IsrCell<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("IsrCell")
+            .field("cell", &self.cell)
+            .finish()
+    }
+}
+
+impl<T> Default for IsrCell<T> {
+    /// Constructs an empty cell
+    #[inline]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T> From<Box<T>> for IsrCell<T> {
+    /// Constructs a cell that takes over the allocation of `boxed` without moving its value
+    #[inline]
+    fn from(boxed: Box<T>) -> Self {
+        Self { cell: boxed.into() }
+    }
+}
@@ -0,0 +1,336 @@
+//! Request-response slot built on two cells
+
+use crate::{AtomicU8, PtrCell, Semantics, WakerCell};
+use core::sync::atomic::Ordering;
+
+/// No job has been submitted, or its result has been taken
+const IDLE: u8 = 0;
+
+/// A job has been submitted and its result hasn't been taken yet
+const SUBMITTED: u8 = 1;
+
+/// Slot for one job at a time and its result
+///
+/// A requester [submits](Self::submit) a job, a worker [takes](Self::take_job) it and
+/// [completes](Self::complete) it with a result, and the requester
+/// [takes the result](Self::take_result). The job and the result each live in their own
+/// [`PtrCell`]. Only one job can be in flight, so a result always belongs to the latest job
+///
+/// With the `std` feature, [`submit_and_wait`](Self::submit_and_wait) blocks until the result is
+/// ready. With the `async` feature, [`submit_and_await`](Self::submit_and_await) returns a future
+/// of it instead
+///
+/// All operations use [`Coupled`](Semantics::Coupled) semantics
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::JobSlot;
+///
+/// let slot = JobSlot::new();
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| while !slot.serve(|text: &str| text.len()) {});
+///
+///     assert_eq!(slot.submit("request"), Ok(()));
+///
+///     let length = loop {
+///         if let Some(length) = slot.take_result() {
+///             break length;
+///         }
+///     };
+///
+///     assert_eq!(length, 7)
+/// })
+/// ```
+pub struct JobSlot<T, R> {
+    /// The submitted job, until a worker takes it
+    job: PtrCell<T>,
+
+    /// The result of the job, until the requester takes it
+    result: PtrCell<R>,
+
+    /// Either [`IDLE`] or [`SUBMITTED`]
+    state: AtomicU8,
+
+    /// Waker of the task that awaits the result
+    waker: WakerCell,
+}
+
+impl<T, R> JobSlot<T, R> {
+    /// Submits a job
+    ///
+    /// # Errors
+    ///
+    /// Hands `job` back if the result of the previous job hasn't been taken yet
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::JobSlot;
+    ///
+    /// let slot = JobSlot::<u8, u8>::new();
+    ///
+    /// assert_eq!(slot.submit(1), Ok(()));
+    /// assert_eq!(slot.submit(2), Err(2))
+    /// ```
+    pub fn submit(&self, job: T) -> Result<(), T> {
+        let submitting =
+            self.state
+                .compare_exchange(IDLE, SUBMITTED, Ordering::Acquire, Ordering::Relaxed);
+
+        if submitting.is_err() {
+            return Err(job);
+        }
+
+        self.job.set(Some(job), Semantics::Coupled);
+
+        Ok(())
+    }
+
+    /// Takes out the submitted job, if there is one
+    ///
+    /// The job must then be [completed](Self::complete), or no other job can be submitted
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::JobSlot;
+    ///
+    /// let slot = JobSlot::<&str, ()>::new();
+    /// assert_eq!(slot.take_job(), None);
+    ///
+    /// let _ = slot.submit("job");
+    /// assert_eq!(slot.take_job(), Some("job"))
+    /// ```
+    #[inline]
+    pub fn take_job(&self) -> Option<T> {
+        self.job.take(Semantics::Coupled)
+    }
+
+    /// Hands the result of the taken job to the requester
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::JobSlot;
+    ///
+    /// let slot = JobSlot::new();
+    /// let _ = slot.submit(20);
+    ///
+    /// if let Some(job) = slot.take_job() {
+    ///     slot.complete(job + 1);
+    /// }
+    ///
+    /// assert_eq!(slot.take_result(), Some(21))
+    /// ```
+    pub fn complete(&self, result: R) {
+        self.result.set(Some(result), Semantics::Coupled);
+        self.waker.wake();
+    }
+
+    /// Takes out the submitted job, if there is one, and completes it with the result of `f`
+    ///
+    /// Returns whether a job was served
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::JobSlot;
+    ///
+    /// let slot = JobSlot::new();
+    /// assert!(!slot.serve(|number: u8| number * 2));
+    ///
+    /// let _ = slot.submit(4);
+    ///
+    /// assert!(slot.serve(|number| number * 2));
+    /// assert_eq!(slot.take_result(), Some(8))
+    /// ```
+    pub fn serve<F>(&self, f: F) -> bool
+    where
+        F: FnOnce(T) -> R,
+    {
+        let Some(job) = self.take_job() else {
+            return false;
+        };
+
+        self.complete(f(job));
+
+        true
+    }
+
+    /// Takes out the result of the submitted job, if it's ready
+    ///
+    /// Another job can be submitted afterwards
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::JobSlot;
+    ///
+    /// let slot = JobSlot::new();
+    /// let _ = slot.submit('a');
+    /// assert_eq!(slot.take_result(), None);
+    ///
+    /// slot.serve(|letter| letter.to_ascii_uppercase());
+    ///
+    /// assert_eq!(slot.take_result(), Some('A'));
+    /// assert_eq!(slot.submit('b'), Ok(()))
+    /// ```
+    pub fn take_result(&self) -> Option<R> {
+        let result = self.result.take(Semantics::Coupled)?;
+        self.state.store(IDLE, Ordering::Release);
+
+        Some(result)
+    }
+
+    /// Submits a job and blocks until its result is ready
+    ///
+    /// The result is polled for like in [`take_timeout`](PtrCell::take_timeout)
+    ///
+    /// # Errors
+    ///
+    /// Hands `job` back without waiting if the result of the previous job hasn't been taken yet
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::JobSlot;
+    ///
+    /// let slot = JobSlot::new();
+    ///
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| while !slot.serve(|number: u32| number.pow(2)) {});
+    ///
+    ///     assert_eq!(slot.submit_and_wait(12), Ok(144))
+    /// })
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn submit_and_wait(&self, job: T) -> Result<R, T> {
+        self.submit(job)?;
+
+        loop {
+            // Timeouts too long to represent never expire
+            let result = self
+                .result
+                .take_timeout(std::time::Duration::MAX, Semantics::Coupled);
+
+            if let Some(result) = result {
+                self.state.store(IDLE, Ordering::Release);
+                break Ok(result);
+            }
+        }
+    }
+
+    /// Submits a job and returns a future of its result
+    ///
+    /// The future is woken by [`complete`](Self::complete), so it waits without polling
+    ///
+    /// # Errors
+    ///
+    /// Hands `job` back if the result of the previous job hasn't been taken yet
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::JobSlot;
+    ///
+    /// let slot = JobSlot::new();
+    ///
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| while !slot.serve(|name: &str| format!("Hello, {name}")) {});
+    ///
+    ///     let greeting = slot.submit_and_await("world").expect("The slot should be idle");
+    ///
+    ///     assert_eq!(pollster::block_on(greeting), "Hello, world")
+    /// })
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn submit_and_await(&self, job: T) -> Result<Completion<'_, T, R>, T> {
+        self.submit(job)?;
+
+        Ok(Completion { slot: self })
+    }
+
+    /// Constructs an idle slot
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::JobSlot;
+    ///
+    /// static RESIZE: JobSlot<(u32, u32), bool> = JobSlot::new();
+    ///
+    /// assert_eq!(RESIZE.take_job(), None)
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            job: PtrCell::empty(),
+            result: PtrCell::empty(),
+            state: AtomicU8::new(IDLE),
+            waker: WakerCell::new(),
+        }
+    }
+}
+
+impl<T, R> core::fmt::Debug for JobSlot<T, R> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("JobSlot")
+            .field("job", &self.job)
+            .field("result", &self.result)
+            .field(
+                "submitted",
+                &(self.state.load(Ordering::Relaxed) == SUBMITTED),
+            )
+            .field("waker", &self.waker)
+            .finish()
+    }
+}
+
+impl<T, R> Default for JobSlot<T, R> {
+    /// Constructs an idle slot
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Future of the result of a job submitted to a [`JobSlot`]
+///
+/// Created by [`JobSlot::submit_and_await`]
+#[cfg(feature = "async")]
+pub struct Completion<'a, T, R> {
+    /// The slot that the job was submitted to
+    slot: &'a JobSlot<T, R>,
+}
+
+#[cfg(feature = "async")]
+impl<T, R> core::future::Future for Completion<'_, T, R> {
+    type Output = R;
+
+    fn poll(
+        self: core::pin::Pin<&mut Self>,
+        context: &mut core::task::Context,
+    ) -> core::task::Poll<R> {
+        // Register first, then check
+        self.slot.waker.register(context.waker());
+
+        match self.slot.take_result() {
+            Some(result) => core::task::Poll::Ready(result),
+            None => core::task::Poll::Pending,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T, R> core::fmt::Debug for Completion<'_, T, R> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("Completion")
+            .field("slot", self.slot)
+            .finish()
+    }
+}
@@ -0,0 +1,364 @@
+//! Values and cells that are initialized on first access, and the
+//! [`static_cell`](crate::static_cell) macro
+
+use crate::{AtomicU8, PtrCell, Semantics};
+use core::sync::atomic::Ordering;
+
+/// The cell hasn't been filled yet
+const UNINIT: u8 = 0;
+
+/// A thread is running the initializer
+const RUNNING: u8 = 1;
+
+/// The cell has been filled. Its value may have been taken out since
+const READY: u8 = 2;
+
+/// [`PtrCell`] that is filled with the result of a function on first access
+///
+/// Dereferences to the underlying cell. The initializer runs at most once, even if the value is
+/// later taken out of the cell. Concurrent first accesses wait for it to finish
+///
+/// Meant for statics that can't be filled in a `const` context, as heap allocation isn't `const`.
+/// Usually declared with [`static_cell`](crate::static_cell)
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{LazyPtrCell, Semantics::Relaxed};
+///
+/// static GREETING: LazyPtrCell<String> = LazyPtrCell::new(|| "Hello".into());
+///
+/// assert_eq!(GREETING.replace(Some("Bye".into()), Relaxed).as_deref(), Some("Hello"));
+/// assert_eq!(GREETING.take(Relaxed).as_deref(), Some("Bye"))
+/// ```
+pub struct LazyPtrCell<T> {
+    /// The cell. Empty until `state` is `READY`
+    cell: PtrCell<T>,
+
+    /// Either `UNINIT`, `RUNNING`, or `READY`
+    state: AtomicU8,
+
+    /// Produces the initial value
+    init: fn() -> T,
+}
+
+impl<T> LazyPtrCell<T> {
+    /// Constructs a cell that will be filled with the result of `init`
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{LazyPtrCell, Semantics::Relaxed};
+    ///
+    /// static PRIMES: LazyPtrCell<Vec<u8>> = LazyPtrCell::new(|| vec![2, 3, 5, 7]);
+    ///
+    /// assert_eq!(PRIMES.take(Relaxed), Some(vec![2, 3, 5, 7]));
+    /// assert!(PRIMES.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(init: fn() -> T) -> Self {
+        Self {
+            cell: PtrCell::empty(),
+            state: AtomicU8::new(UNINIT),
+            init,
+        }
+    }
+
+    /// Runs the initializer if it hasn't run yet and returns the underlying cell
+    ///
+    /// This is what dereferencing does
+    ///
+    /// # Panics
+    ///
+    /// If the initializer panics. The next access will run it again
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{LazyPtrCell, Semantics::Relaxed};
+    ///
+    /// let lazy = LazyPtrCell::new(|| 0xFAA);
+    /// let cell = LazyPtrCell::force(&lazy);
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(0xFAA))
+    /// ```
+    pub fn force(this: &Self) -> &PtrCell<T> {
+        /// Lets another thread retry the initializer if it panics
+        struct Reset<'a>(&'a AtomicU8);
+
+        impl Drop for Reset<'_> {
+            fn drop(&mut self) {
+                self.0.store(UNINIT, Ordering::Release);
+            }
+        }
+
+        loop {
+            let acquiring = this.state.compare_exchange_weak(
+                UNINIT,
+                RUNNING,
+                Ordering::Acquire,
+                Ordering::Acquire,
+            );
+
+            match acquiring {
+                Ok(_) => {
+                    let reset = Reset(&this.state);
+                    this.cell.set(Some((this.init)()), Semantics::Relaxed);
+                    core::mem::forget(reset);
+
+                    this.state.store(READY, Ordering::Release);
+                    break;
+                }
+                Err(READY) => break,
+                Err(_) => core::hint::spin_loop(),
+            }
+        }
+
+        &this.cell
+    }
+
+    /// Unwraps the underlying cell, running the initializer if it hasn't run yet
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{LazyPtrCell, Semantics::Relaxed};
+    ///
+    /// let lazy = LazyPtrCell::new(|| 'a');
+    ///
+    /// assert_eq!(lazy.into_cell().take(Relaxed), Some('a'))
+    /// ```
+    #[inline]
+    pub fn into_cell(self) -> PtrCell<T> {
+        Self::force(&self);
+
+        self.cell
+    }
+}
+
+impl<T> core::ops::Deref for LazyPtrCell<T> {
+    type Target = PtrCell<T>;
+
+    #[inline]
+    fn deref(&self) -> &PtrCell<T> {
+        Self::force(self)
+    }
+}
+
+impl<T> core::fmt::Debug for LazyPtrCell<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("LazyPtrCell")
+            .field("cell", &self.cell)
+            .field("ready", &(self.state.load(Ordering::Relaxed) == READY))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Value that is initialized on first access
+///
+/// A `no_std` alternative to `once_cell::sync::Lazy`. Dereferencing runs the initializer and
+/// places its result into an internal [`PtrCell`], unless it's already there. Threads never wait
+/// for each other. If several of them access the value for the first time at once, each runs the
+/// initializer, the first result to be inserted wins, and the others are dropped. This is why the
+/// initializer is an [`Fn`] rather than an [`FnOnce`]
+///
+/// Unlike [`LazyPtrCell`], this type dereferences to the value itself, which is never replaced
+/// once initialized
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::Lazy;
+///
+/// static PRIMES: Lazy<Vec<u32>> = Lazy::new(|| vec![2, 3, 5, 7]);
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| assert_eq!(PRIMES.iter().sum::<u32>(), 17));
+///     }
+/// })
+/// ```
+pub struct Lazy<T, F = fn() -> T> {
+    /// The value. Never emptied once it has been filled
+    cell: PtrCell<T>,
+
+    /// Produces the value
+    init: F,
+}
+
+// References to the value are shared between threads, and the value may be created by one thread
+// and dropped by another. The initializer may run on any thread
+unsafe impl<T: Send, F: Send> Send for Lazy<T, F> {}
+unsafe impl<T: Send + Sync, F: Sync> Sync for Lazy<T, F> {}
+
+impl<T, F> Lazy<T, F> {
+    /// Constructs a value that will be initialized by `init`
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::Lazy;
+    ///
+    /// let lazy = Lazy::new(|| 0xFAA);
+    ///
+    /// assert_eq!(*lazy, 0xFAA)
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(init: F) -> Self {
+        Self {
+            cell: PtrCell::empty(),
+            init,
+        }
+    }
+
+    /// Returns a reference to the value, or [`None`] if it hasn't been initialized yet
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::Lazy;
+    ///
+    /// let lazy = Lazy::new(|| 'a');
+    /// assert_eq!(Lazy::get(&lazy), None);
+    ///
+    /// let _ = *lazy;
+    /// assert_eq!(Lazy::get(&lazy), Some(&'a'))
+    /// ```
+    #[inline]
+    pub fn get(this: &Self) -> Option<&T> {
+        let ptr = this.cell.get_ptr(Semantics::Coupled);
+
+        // The value is never taken out of the cell while it's borrowed
+        crate::non_null(ptr).map(|ptr| unsafe { &*ptr })
+    }
+
+    /// Unwraps the value, or returns [`None`] if it hasn't been initialized yet
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::Lazy;
+    ///
+    /// let lazy = Lazy::new(|| String::from("value"));
+    /// let _ = lazy.len();
+    ///
+    /// assert_eq!(Lazy::into_value(lazy).as_deref(), Some("value"))
+    /// ```
+    #[inline]
+    pub fn into_value(this: Self) -> Option<T> {
+        this.cell.into_inner()
+    }
+}
+
+impl<T, F: Fn() -> T> Lazy<T, F> {
+    /// Initializes the value if needed and returns a reference to it
+    ///
+    /// This is what dereferencing does
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::Lazy;
+    ///
+    /// let lazy = Lazy::new(|| [1, 2, 3]);
+    ///
+    /// assert_eq!(Lazy::force(&lazy), &[1, 2, 3])
+    /// ```
+    pub fn force(this: &Self) -> &T {
+        if let Some(value) = Self::get(this) {
+            return value;
+        }
+
+        let new_ptr = PtrCell::heap_leak(Some((this.init)()));
+
+        let inserting = this.cell.value.compare_exchange(
+            core::ptr::null_mut(),
+            new_ptr,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+
+        let ptr = match inserting {
+            Ok(_) => new_ptr,
+            Err(current) => {
+                // Another thread got there first
+                drop(unsafe { PtrCell::heap_reclaim(new_ptr) });
+                current
+            }
+        };
+
+        unsafe { &*ptr }
+    }
+}
+
+impl<T, F: Fn() -> T> core::ops::Deref for Lazy<T, F> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        Self::force(self)
+    }
+}
+
+impl<T: core::fmt::Debug, F> core::fmt::Debug for Lazy<T, F> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("Lazy")
+            .field("value", &Self::get(self))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Default> Default for Lazy<T> {
+    /// Constructs a value that will be initialized with [`T::default`](Default::default)
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default)
+    }
+}
+
+/// Declares static cells without `unsafe` boilerplate
+///
+/// A declaration without an initializer expands to an empty [`PtrCell`] static. One with an
+/// initializer expands to a [`LazyPtrCell`] static that is filled with the expression on first
+/// access. Attributes, visibility, and several declarations in one invocation are supported
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{static_cell, Semantics::Relaxed};
+///
+/// static_cell! {
+///     /// Last reading of the sensor
+///     static READING: u16;
+///
+///     pub static CALIBRATION: [i8; 4] = [1, -2, 0, 3];
+/// }
+///
+/// assert!(READING.is_empty(Relaxed));
+/// READING.set(Some(512), Relaxed);
+///
+/// assert_eq!(READING.take(Relaxed), Some(512));
+/// assert_eq!(CALIBRATION.take(Relaxed), Some([1, -2, 0, 3]))
+/// ```
+#[macro_export]
+macro_rules! static_cell {
+    () => {};
+
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty; $($rest:tt)*) => {
+        $(#[$attr])*
+        $vis static $name: $crate::PtrCell<$ty> = $crate::PtrCell::empty();
+
+        $crate::static_cell!($($rest)*);
+    };
+
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $init:expr; $($rest:tt)*) => {
+        $(#[$attr])*
+        $vis static $name: $crate::LazyPtrCell<$ty> = $crate::LazyPtrCell::new(|| $init);
+
+        $crate::static_cell!($($rest)*);
+    };
+}
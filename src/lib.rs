@@ -13,19 +13,25 @@
 //! - **Familiarity**: `PtrCell`'s API was modelled after `std`'s [Cell](core::cell::Cell)
 //!
 //! - **Easy Concurrency**: No more `Arc<Mutex<T>>`, `Arc::clone()`, and `Mutex::lock().expect()`!
-//! Leave the data static and then point to it when you need to. It's a _single instruction_ on most
-//! modern platforms
+//!   Leave the data static and then point to it when you need to. It's a _single instruction_ on
+//!   most modern platforms
 //!
 //! #### Limitations:
 //!
 //! - **Heap Allocation**: Every value you insert into `PtrCell` must first be allocated using
-//! [`Box`]. Allocating on the heap is, computationally, a moderately expensive operation. To
-//! address this, the cell exposes a pointer API that can be used to avoid allocating the same
-//! values multiple times. Future releases will primarily rely on the stack
+//!   [`Box`]. Allocating on the heap is, computationally, a moderately expensive operation. To
+//!   address this, the cell exposes a pointer API that can be used to avoid allocating the same
+//!   values multiple times. When allocation isn't an option at all, use [`InlineCell`], which
+//!   stores its value on the stack
+//!
+//! - **Move-Based Access**: Values are moved in and out of the cell rather than read in place. For
+//!   [`Copy`] values that are read far more often than written, use [`SeqCell`] instead
 //!
 //! ## Usage
 //!
-//! ```rust
+#![cfg_attr(all(feature = "alloc", not(feature = "shuttle")), doc = "```rust")]
+#![cfg_attr(all(feature = "alloc", feature = "shuttle"), doc = "```rust,no_run")]
+#![cfg_attr(not(feature = "alloc"), doc = "```rust,ignore")]
 //! use ptr_cell::{PtrCell, Semantics::Relaxed};
 //!
 //! let cell: PtrCell<u16> = 0x81D.into();
@@ -44,21 +50,29 @@
 //! | Variant | Overhead | Synchronization |
 //! |---|---|---|
 //! | [`Relaxed`](Semantics::Relaxed) | Negligible | None |
+//! | [`AcquireOnly`](Semantics::AcquireOnly) | Low | Reads only |
+//! | [`ReleaseOnly`](Semantics::ReleaseOnly) | Low | Writes only |
 //! | [`Coupled`](Semantics::Coupled) | Acceptable | Intuitive |
 //! | [`Ordered`](Semantics::Ordered) | Noticeable | Strict |
 //!
 //! `Coupled` is what you'd typically use. However, other orderings have their use cases too. For
 //! example, the `Relaxed` semantics could be useful when the operations are already synchronized
-//! through other means, like [fences](core::sync::atomic::fence). As always, the documentation for
+//! through other means, like [fences](PtrCell::fence_for). The one-sided semantics suit
+//! handoffs where one thread only writes and another only reads. As always, the documentation for
 //! each item contains more details
 //!
+//! If none of the presets fit, [`Custom`](Semantics::Custom) semantics let you pick the ordering of
+//! each type of operations yourself
+//!
 //! ## Examples
 //!
 //! The code below finds the maximum value of a sequence by concurrently processing its halves.
 //! Notice how the code doesn't read the shared value. Instead, it uses moves and corrects previous
 //! operations as new data comes in
 //!
-//! ```rust
+#![cfg_attr(all(feature = "alloc", not(feature = "shuttle")), doc = "```rust")]
+#![cfg_attr(all(feature = "alloc", feature = "shuttle"), doc = "```rust,no_run")]
+#![cfg_attr(not(feature = "alloc"), doc = "```rust,ignore")]
 //! use ptr_cell::{PtrCell, Semantics};
 //! use std::sync::Arc;
 //!
@@ -104,21 +118,532 @@
 //! }
 //! ```
 //!
+//! ## Pointer Provenance
+//!
+//! The crate follows the [strict provenance][8] rules. It never converts pointers into integers or
+//! back, so no provenance is ever exposed or guessed. Empty cells hold null pointers, and zero-sized
+//! values are placed at [dangling](core::ptr::NonNull::dangling) ones. This keeps it sound under
+//! Miri's `-Zmiri-strict-provenance` mode and on targets like CHERI, where pointers carry hardware
+//! capabilities
+//!
+//! ## Features
+//!
+//! - **`alloc`** (default): Enables [`PtrCell`] and everything else that allocates. Without it, the
+//!   crate builds on targets with no global allocator at all, and only the types that store their
+//!   values inline are available: [`InlineCell`], [`SeqCell`], [`SmallPtrCell`], `StampCell`, the
+//!   [`spsc`] queue, and [`Semantics`]. The pointer API isn't available either, as the cell's
+//!   pointers must always come from [`Box`]
+//!
+//! - **`std`**: Enables everything that depends on the standard library, like the
+//!   [`SpinThenYield`](backoff::SpinThenYield) backoff strategy, the [`select`] function, the
+//!   [`watch`] channel, and [`take_timeout`](PtrCell::take_timeout)
+//!
+//! - **`portable-atomic`**: Backs the cell with [`portable_atomic::AtomicPtr`][5] instead of the
+//!   one from `core`. Enable this on targets without native atomic pointers, like `thumbv6m` and
+//!   AVR. Depending on the target, `portable-atomic` may need additional configuration
+//!
+//! - **`critical-section`**: Performs every operation on the cell inside
+//!   [`critical_section::with`][6] instead of using atomics, so code can be shared between chips
+//!   with and without atomic instructions. The public API stays the same, except for what relies
+//!   on real atomics: `PtrCell::{as_atomic_ptr, from_atomic, from_atomic_ref}`, which convert from
+//!   and to `core`'s atomic pointer, and the `signal_safe` module, as a critical section may be
+//!   guarded by a lock. An implementation of the critical section must be provided by the
+//!   application. Takes precedence over `portable-atomic`
+//!
+//! - **`shuttle`**: Backs every atomic in the crate with the ones from [`shuttle`][16], so that
+//!   code built on cells can be tested for concurrency bugs under Shuttle's randomized scheduler.
+//!   Meant for test builds only, by enabling it in `dev-dependencies`. Operations on cells then
+//!   only work inside a Shuttle test, like one run by `shuttle::check_random`. Takes precedence
+//!   over `portable-atomic` and `critical-section`. The `ffi` and `bytemuck` features have no
+//!   effect while it's enabled, as Shuttle's atomics have neither the C layout nor a valid
+//!   all-zero state
+//!
+//! - **`async`**: Adds [`PtrCell::into_stream`] and [`PtrCell::into_sink`], which turn a shared
+//!   cell into a [`Stream`][10] of the values set in it and a [`Sink`][11] that sets them
+//!
+//! - **`arbitrary`** and **`proptest`**: Implement the `Arbitrary` traits of [`arbitrary`][14] and
+//!   [`proptest`][15] for the cell and for [`Semantics`]. Cells are generated empty or with an
+//!   arbitrary value, and semantics are generated as presets or custom semantics with valid
+//!   orderings
+//!
+//! - **`test-util`**: Adds the [`model`] module, a reference implementation of the cell built on a
+//!   `Mutex` along with a harness that replays logs of operations against it and a cell under
+//!   test. Wrappers around the cell can be checked for the same behavior this way
+//!
+//! - **`bytemuck`**: Implements [`Zeroable`][13] for the cell, as an all-zero cell is empty.
+//!   Arrays of cells can then be allocated with `bytemuck::zeroed_box` without constructing each
+//!   cell
+//!
+//! - **`crossbeam`**: Adds conversions between the cell and `AtomicCell<Option<Box<T>>>` from
+//!   [`crossbeam-utils`][12] that move values without reallocating them, along with
+//!   [`move_to_atomic_cell`](PtrCell::move_to_atomic_cell) and
+//!   [`move_from_atomic_cell`](PtrCell::move_from_atomic_cell)
+//!
+//! - **`ffi`**: Adds the [`ffi`](crate::ffi) module, which documents the cell's C layout, and the
+//!   [`export_ffi!`] macro, which generates `extern "C"` functions for cells of a chosen type.
+//!   Has no effect together with `allocator_api` or `shuttle`, which change the cell's layout
+//!
+//! - **`debug-track`**: Records every pointer leaked by the crate in a global table and panics when
+//!   one is reclaimed twice, or when a pointer that wasn't leaked by the crate is reclaimed. Meant
+//!   for debugging code that uses the pointer API, as it slows down every allocation. Pointers
+//!   from other sources, like [`Box::into_raw`], must not be passed to the crate while it's enabled
+//!
+//! - **`stats`**: Counts replaces, takes, lost races in the [`map_owner`](PtrCell::map_owner)
+//!   family, and live heap allocations across all cells. A snapshot is returned by
+//!   `PtrCell::stats`. Every counted operation pays for an extra atomic increment
+//!
+//! - **`registry`**: Adds the [`registry`] module, where static cells can be registered under
+//!   string keys and looked up from anywhere in the program
+//!
+//! - **`tracing`**: Emits a trace-level [`tracing`][9] event from [`replace`](PtrCell::replace),
+//!   [`take`](PtrCell::take), [`set`](PtrCell::set), and the [`map_owner`](PtrCell::map_owner)
+//!   family. Each event records the cell's address, the operation, the semantics, and whether the
+//!   cell was empty before the operation
+//!
+//! - **`may_dangle`** (nightly): Implements [`Drop`] for the cell with the unstable dropck
+//!   eyepatch. Borrows inside the cell's value may then expire before the cell is dropped, as long
+//!   as the value's own drop code doesn't use them. Without it, a struct that holds something like
+//!   `PtrCell<&'a mut Buffer>` must be dropped strictly before the buffer
+//!
+//! - **`allocator_api`** (nightly): Adds an [allocator][7] parameter to the cell, as in
+//!   `PtrCell<T, A = Global>`. Cells with custom allocators are built with `PtrCell::new_in` and
+//!   operated on through the `_in` family of methods. Because the
+//!   allocator is stored in the cell, the in-memory representation is no longer guaranteed to match
+//!   that of a `*mut T`. Cells with a custom allocator only get the `_in` methods, while the rest
+//!   of the API, like [`map_owner`](PtrCell::map_owner) and [`rcu`](PtrCell::rcu), stays limited
+//!   to the [`Global`](alloc::alloc::Global) allocator
+//!
+//! ## WebAssembly
+//!
+//! On `wasm32` without the `atomics` target feature, like a default build for
+//! `wasm32-unknown-unknown`, a module can't share memory between threads. The cell then operates
+//! on its pointer with plain loads and stores instead of atomic instructions, behind the same API.
+//! Builds with the `atomics` target feature, as used for threads on the web, keep the atomic
+//! instructions. Enabling `portable-atomic` or `critical-section` overrides this choice
+//!
+//! Whichever backend a build ends up with is reported by [`Backend::CURRENT`], along with whether
+//! its operations are lock-free
+//!
 //! [1]: https://doc.rust-lang.org/std/index.html
 //! [2]: https://groups.google.com/g/comp.std.c/c/ycpVKxTZkgw/m/S2hHdTbv4d8J?hl=en
 //! [3]: https://doc.rust-lang.org/std/sync/struct.Mutex.html
 //! [4]: https://doc.rust-lang.org/std/sync/struct.RwLock.html
+//! [5]: https://docs.rs/portable-atomic/latest/portable_atomic/struct.AtomicPtr.html
+//! [6]: https://docs.rs/critical-section/latest/critical_section/fn.with.html
+//! [7]: https://doc.rust-lang.org/std/alloc/trait.Allocator.html
+//! [8]: https://doc.rust-lang.org/std/ptr/index.html#strict-provenance
+//! [9]: https://docs.rs/tracing/latest/tracing/
+//! [10]: https://docs.rs/futures-core/latest/futures_core/stream/trait.Stream.html
+//! [11]: https://docs.rs/futures-sink/latest/futures_sink/trait.Sink.html
+//! [12]: https://docs.rs/crossbeam-utils/latest/crossbeam_utils/atomic/struct.AtomicCell.html
+//! [13]: https://docs.rs/bytemuck/latest/bytemuck/trait.Zeroable.html
+//! [14]: https://docs.rs/arbitrary/latest/arbitrary/trait.Arbitrary.html
+//! [15]: https://docs.rs/proptest/latest/proptest/arbitrary/trait.Arbitrary.html
+//! [16]: https://docs.rs/shuttle/latest/shuttle/
 
 #![no_std]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+#![cfg_attr(feature = "may_dangle", feature(dropck_eyepatch))]
 #![warn(missing_docs, clippy::all, clippy::pedantic, clippy::cargo)]
 #![allow(clippy::must_use_candidate)]
 #![forbid(unsafe_op_in_unsafe_fn)]
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
-use alloc::boxed::Box;
+#[cfg(feature = "std")]
+extern crate std;
+
 use core::sync::atomic::Ordering;
 
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+#[cfg(feature = "alloc")]
+use backoff::{Backoff, Spin};
+
+#[cfg(feature = "alloc")]
+use core::{convert::Infallible, marker::PhantomData};
+
+#[cfg(feature = "allocator_api")]
+use alloc::alloc::{Allocator, Global};
+
+/// Opens an example that can run, which under `shuttle` is only compiled, as Shuttle's atomics panic
+/// outside a Shuttle test
+#[cfg(not(feature = "shuttle"))]
+macro_rules! doctest {
+    () => {
+        "```rust"
+    };
+}
+
+/// Opens an example that can run, which under `shuttle` is only compiled, as Shuttle's atomics panic
+/// outside a Shuttle test
+#[cfg(feature = "shuttle")]
+macro_rules! doctest {
+    () => {
+        "```rust,no_run"
+    };
+}
+
+/// Opens an example that needs the `alloc` feature, which is ignored without it
+#[cfg(feature = "alloc")]
+macro_rules! alloc_doctest {
+    () => {
+        doctest!()
+    };
+}
+
+/// Opens an example that needs the `alloc` feature, which is ignored without it
+#[cfg(not(feature = "alloc"))]
+macro_rules! alloc_doctest {
+    () => {
+        "```rust,ignore"
+    };
+}
+
+#[cfg(feature = "allocator_api")]
+mod allocator;
+
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+mod arbitrary;
+
+#[cfg(feature = "alloc")]
+mod array;
+
+#[cfg(feature = "alloc")]
+pub mod atomic_option;
+
+mod backend;
+
+pub mod backoff;
+
+#[cfg(feature = "std")]
+mod blocking;
+
+#[cfg(feature = "alloc")]
+pub mod broadcast;
+
+#[cfg(feature = "alloc")]
+mod bulk;
+
+#[cfg(feature = "alloc")]
+mod chain;
+
+// `Arc` needs atomic pointers
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub mod channel;
+
+#[cfg(feature = "alloc")]
+mod closable;
+
+#[cfg(all(
+    not(feature = "shuttle"),
+    any(
+        feature = "critical-section",
+        all(
+            target_arch = "wasm32",
+            not(target_feature = "atomics"),
+            not(feature = "portable-atomic")
+        )
+    )
+))]
+mod critical;
+
+#[cfg(feature = "alloc")]
+pub mod deque;
+
+#[cfg(feature = "crossbeam")]
+mod crossbeam;
+
+#[cfg(feature = "alloc")]
+mod double;
+
+#[cfg(feature = "alloc")]
+mod drain;
+
+mod error;
+
+#[cfg(feature = "alloc")]
+mod expiring;
+
+// The C layout doesn't hold with an allocator in the cell or with Shuttle's atomics
+#[cfg(all(
+    feature = "ffi",
+    not(any(feature = "allocator_api", feature = "shuttle"))
+))]
+pub mod ffi;
+
+mod inline;
+
+#[cfg(feature = "alloc")]
+pub mod isr;
+
+#[cfg(feature = "alloc")]
+mod job;
+
+#[cfg(feature = "alloc")]
+mod lazy;
+
+#[cfg(feature = "alloc")]
+mod merge;
+
+#[cfg(feature = "test-util")]
+pub mod model;
+#[cfg(feature = "alloc")]
+mod multi;
+
+#[cfg(feature = "alloc")]
+pub mod node;
+
+#[cfg(feature = "alloc")]
+mod node_alloc;
+
+#[cfg(feature = "alloc")]
+mod nonnull;
+
+mod padded;
+
+#[cfg(feature = "alloc")]
+mod pinned;
+
+#[cfg(feature = "alloc")]
+mod pool;
+
+#[cfg(feature = "alloc")]
+mod priority;
+
+#[cfg(feature = "registry")]
+pub mod registry;
+
+#[cfg(feature = "std")]
+mod select;
+
+#[cfg(feature = "alloc")]
+mod semantic;
+
+mod seq;
+
+#[cfg(all(feature = "alloc", not(feature = "critical-section")))]
+pub mod signal_safe;
+
+#[cfg(feature = "async")]
+mod sink;
+
+#[cfg(feature = "alloc")]
+mod slice;
+
+mod small;
+
+#[cfg(feature = "alloc")]
+mod snapshot;
+
+pub mod spsc;
+
+#[cfg(any(
+    target_has_atomic = "64",
+    feature = "portable-atomic",
+    feature = "critical-section",
+    feature = "shuttle"
+))]
+mod stamp;
+
+#[cfg(feature = "alloc")]
+mod state;
+
+#[cfg(feature = "stats")]
+mod stats;
+
+#[cfg(feature = "async")]
+mod stream;
+
+#[cfg(all(feature = "alloc", feature = "tracing"))]
+mod trace;
+
+#[cfg(feature = "alloc")]
+mod traced;
+
+#[cfg(feature = "debug-track")]
+mod track;
+
+#[cfg(all(
+    feature = "alloc",
+    any(
+        target_has_atomic = "64",
+        feature = "portable-atomic",
+        feature = "critical-section",
+        feature = "shuttle"
+    )
+))]
+mod versioned;
+
+#[cfg(feature = "alloc")]
+mod view;
+
+#[cfg(feature = "alloc")]
+mod waker;
+
+// Shuttle's atomics aren't valid when zeroed
+#[cfg(all(feature = "alloc", feature = "bytemuck", not(feature = "shuttle")))]
+mod zeroable;
+
+#[cfg(feature = "std")]
+pub mod watch;
+
+pub use backend::Backend;
+pub use error::{
+    AllocError, ClosedError, InsertError, OrderingError, ParseSemanticsError, TakeError,
+};
+pub use inline::InlineCell;
+pub use padded::CachePadded;
+pub use seq::SeqCell;
+pub use small::{Small, SmallPtrCell};
+
+#[cfg(feature = "alloc")]
+pub use {
+    array::PtrCellArray,
+    chain::{ChainIterMut, ChainIterShared},
+    closable::ClosablePtrCell,
+    double::DoubleBuffer,
+    drain::{Drain, DrainChain},
+    expiring::{Clock, ExpiringCell},
+    job::JobSlot,
+    lazy::{Lazy, LazyPtrCell},
+    merge::MergeCell,
+    multi::MultiCell,
+    node_alloc::NodeAlloc,
+    padded::PaddedPtrCell,
+    pinned::PinnedPtrCell,
+    pool::Pool,
+    priority::PriorityCell,
+    semantic::SemanticPtrCell,
+    slice::{Slice, SlicePtrCell, ThinBox},
+    snapshot::snapshot2,
+    state::{Sentinel, State, StateCell},
+    traced::ReplaceInfo,
+    view::OptionViewMut,
+    waker::WakerCell,
+};
+
+#[cfg(any(
+    target_has_atomic = "64",
+    feature = "portable-atomic",
+    feature = "critical-section",
+    feature = "shuttle"
+))]
+pub use stamp::StampCell;
+
+#[cfg(all(
+    feature = "alloc",
+    any(
+        target_has_atomic = "64",
+        feature = "portable-atomic",
+        feature = "critical-section",
+        feature = "shuttle"
+    )
+))]
+pub use versioned::VersionedPtrCell;
+
+#[cfg(feature = "std")]
+pub use expiring::StdClock;
+
+#[cfg(feature = "std")]
+pub use select::select;
+
+#[cfg(feature = "stats")]
+pub use stats::Stats;
+
+#[cfg(feature = "async")]
+pub use sink::{PtrCellSink, SinkPolicy};
+
+#[cfg(feature = "async")]
+pub use stream::IntoStream;
+
+#[cfg(feature = "async")]
+pub use job::Completion;
+
+#[cfg(not(any(
+    target_has_atomic = "ptr",
+    feature = "portable-atomic",
+    feature = "critical-section",
+    feature = "shuttle"
+)))]
+compile_error!(
+    "ptr_cell: this target has no atomic pointers. Enable the `critical-section` feature and \
+     provide a critical section implementation for the target, or enable the `portable-atomic` \
+     feature and configure it for the target"
+);
+
+#[cfg(all(
+    target_has_atomic = "ptr",
+    not(any(
+        feature = "portable-atomic",
+        feature = "critical-section",
+        feature = "shuttle",
+        all(target_arch = "wasm32", not(target_feature = "atomics"))
+    ))
+))]
+#[cfg_attr(not(feature = "alloc"), allow(unused_imports))]
+use core::sync::atomic::{fence, AtomicPtr, AtomicU8, AtomicUsize};
+
+/// Backend of the current build
+///
+/// Not gated on atomic pointers, so that targets without them only get the error above
+#[cfg(not(any(
+    feature = "portable-atomic",
+    feature = "critical-section",
+    feature = "shuttle",
+    all(target_arch = "wasm32", not(target_feature = "atomics"))
+)))]
+const BACKEND: Backend = Backend::Native;
+
+#[cfg(all(
+    target_has_atomic = "64",
+    not(any(
+        feature = "portable-atomic",
+        feature = "critical-section",
+        feature = "shuttle",
+        all(target_arch = "wasm32", not(target_feature = "atomics"))
+    ))
+))]
+use core::sync::atomic::AtomicU64;
+
+#[cfg(all(
+    feature = "portable-atomic",
+    not(any(feature = "critical-section", feature = "shuttle"))
+))]
+#[cfg_attr(not(feature = "alloc"), allow(unused_imports))]
+use portable_atomic::{fence, AtomicPtr, AtomicU64, AtomicU8, AtomicUsize};
+
+/// Backend of the current build
+#[cfg(all(
+    feature = "portable-atomic",
+    not(any(feature = "critical-section", feature = "shuttle"))
+))]
+const BACKEND: Backend = Backend::PortableAtomic;
+
+#[cfg(all(
+    not(feature = "shuttle"),
+    any(
+        feature = "critical-section",
+        all(
+            target_arch = "wasm32",
+            not(target_feature = "atomics"),
+            not(feature = "portable-atomic")
+        )
+    )
+))]
+#[cfg_attr(not(feature = "alloc"), allow(unused_imports))]
+use critical::{fence, AtomicPtr, AtomicU64, AtomicU8, AtomicUsize, BACKEND};
+
+#[cfg(feature = "shuttle")]
+#[cfg_attr(not(feature = "alloc"), allow(unused_imports))]
+use shuttle::sync::atomic::{fence, AtomicPtr, AtomicU64, AtomicU8, AtomicUsize};
+
+/// Backend of the current build
+#[cfg(feature = "shuttle")]
+const BACKEND: Backend = Backend::Shuttle;
+
 // 3.0.0:
 // - Just fix `replace_ptr` already!!! \
 // - Make `Semantics` exhaustive       |
@@ -128,16 +653,14 @@ use core::sync::atomic::Ordering;
 // - Add "virtually" to "no locks" in the top-level docs (very important)
 // - Add `from_mut` like on std's Cell
 
-// It's possible to ditch heap allocation entirely if we pre-allocate a buffer of type T.
-// Pre-allocating an array of N buffers (const N: usize) could amortize performance losses during
-// periods of high contention
+// `take_ptr`, `set_ptr`, `replace_ptr`, `get_ptr`, and `is_empty` back the `signal_safe` module, so
+// they must stay single atomic operations: no allocation, locks, panics, or instrumentation
+
+// Pointers must never round-trip through integers. Anything that needs to manipulate addresses,
+// like tagging the low bits of the cell's pointer, has to go through `map_addr` and `with_addr`
 
-// Top-level:
-//
-// ## Features
-//
-// - **`std`**: Enables everything that may depend on the standard library. Currently, there are no
-// such items. Could optimize performace in future updates
+// It's possible to ditch heap allocation entirely if we pre-allocate a buffer of type T. See `Pool`
+// for the amortized version with N buffers (const N: usize)
 
 /// Thread-safe cell based on atomic pointers
 ///
@@ -146,7 +669,7 @@ use core::sync::atomic::Ordering;
 ///
 /// # Usage
 ///
-/// ```rust
+#[doc = doctest!()]
 /// use ptr_cell::{PtrCell, Semantics::Relaxed};
 ///
 /// let cell: PtrCell<u16> = 0x81D.into();
@@ -165,8 +688,105 @@ use core::sync::atomic::Ordering;
 ///
 /// This also applies to externally-sourced pointers, like the `ptr` parameter in
 /// [`from_ptr`](Self::from_ptr)
-#[repr(transparent)]
-pub struct PtrCell<T> {
+///
+/// # Zero-Sized Types
+///
+/// Values of zero-sized types, like `()`, are never allocated. This makes `PtrCell<()>` a cheap
+/// cross-thread flag:
+///
+#[doc = doctest!()]
+/// use ptr_cell::{PtrCell, Semantics::Coupled};
+///
+/// let event = PtrCell::default();
+/// event.set(Some(()), Coupled);
+///
+/// assert_eq!(event.take(Coupled), Some(()));
+/// assert!(event.is_empty(Coupled))
+/// ```
+///
+/// # Ownership
+///
+/// The cell owns its value, even though it only holds a pointer to it. Values are moved between
+/// threads, and the only methods that share a `&T` between threads, like
+/// [`iter_shared`](PtrCell::iter_shared), require `T` to be [`Sync`]. The cell itself is thus
+/// [`Send`] and [`Sync`] exactly when `T` is [`Send`], like a [`Mutex`][1]. Cells of other values
+/// still work within a thread:
+///
+#[doc = doctest!()]
+/// use ptr_cell::PtrCell;
+/// use std::rc::Rc;
+///
+/// let shared = Rc::new(5);
+/// let cell = PtrCell::from(Rc::clone(&shared));
+///
+/// drop(cell);
+/// assert_eq!(Rc::strong_count(&shared), 1)
+/// ```
+///
+/// But they can't be shared with other threads:
+///
+/// ```rust,compile_fail
+/// use ptr_cell::{PtrCell, Semantics::Relaxed};
+/// use std::rc::Rc;
+///
+/// let cell = PtrCell::from(Rc::new(5));
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| cell.take(Relaxed));
+/// })
+/// ```
+///
+/// As the value can be replaced through a shared reference, the cell is invariant in `T`, like
+/// [`Cell`](core::cell::Cell). A cell of long-lived references can't be used as a cell of
+/// shorter-lived ones:
+///
+/// ```rust,compile_fail
+/// use ptr_cell::PtrCell;
+///
+/// fn shorten<'a>(cell: &PtrCell<&'static str>) -> &PtrCell<&'a str> {
+///     cell
+/// }
+/// ```
+///
+/// # Unwind Safety
+///
+/// Values are mostly moved in and out of the cell whole, so a panic can't leave a half-modified
+/// value behind in it. A few methods, like [`contains_value`](PtrCell::contains_value), do run
+/// user code on a `&T` and put the value back afterwards, even if that code panics. The cell is
+/// therefore [`RefUnwindSafe`](core::panic::RefUnwindSafe) exactly when `T` is, and references to
+/// it can then be captured by [`catch_unwind`][2] without
+/// [`AssertUnwindSafe`](core::panic::AssertUnwindSafe):
+///
+#[doc = doctest!()]
+/// use ptr_cell::{PtrCell, Semantics::Relaxed};
+///
+/// let cell = PtrCell::from(String::from("queued"));
+///
+/// let result = std::panic::catch_unwind(|| {
+///     cell.set(Some(String::from("running")), Relaxed);
+///     panic!("The job failed")
+/// });
+///
+/// assert!(result.is_err());
+/// assert_eq!(cell.take(Relaxed).as_deref(), Some("running"))
+/// ```
+///
+/// Cells of values with interior mutability need the wrapper:
+///
+/// ```rust,compile_fail
+/// use ptr_cell::{PtrCell, Semantics::Relaxed};
+/// use std::cell::Cell;
+///
+/// let cell = PtrCell::from(Cell::new(1));
+///
+/// let _ = std::panic::catch_unwind(|| cell.contains_value(&Cell::new(1), Relaxed));
+/// ```
+///
+/// [1]: https://doc.rust-lang.org/std/sync/struct.Mutex.html
+/// [2]: https://doc.rust-lang.org/std/panic/fn.catch_unwind.html
+#[cfg(feature = "alloc")]
+#[cfg_attr(not(feature = "allocator_api"), repr(transparent))]
+pub struct PtrCell<T, #[cfg(feature = "allocator_api")] A: Allocator = Global> {
     /// Pointer to the contained value
     ///
     /// #### Invariants
@@ -175,20 +795,127 @@ pub struct PtrCell<T> {
     ///   [`Box`]
     ///
     /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
-    value: core::sync::atomic::AtomicPtr<T>,
+    value: AtomicPtr<T>,
+
+    /// Allocator that owns the contained value
+    #[cfg(feature = "allocator_api")]
+    alloc: A,
+
+    /// Marks the cell as the owner of a `T`. The drop checker then knows that dropping the cell
+    /// may drop a `T`, and the cell is only [`Send`] if `T` is
+    owns: PhantomData<T>,
 }
 
+// See the ownership section of the cell's docs
+#[cfg(all(feature = "alloc", not(feature = "allocator_api")))]
+unsafe impl<T: Send> Sync for PtrCell<T> {}
+
+// Values are only ever moved in and out whole, so a panic can't leave one half-modified in the cell
+#[cfg(all(feature = "alloc", not(feature = "allocator_api")))]
+impl<T: core::panic::UnwindSafe> core::panic::UnwindSafe for PtrCell<T> {}
+
+// See the unwind safety section of the cell's docs
+#[cfg(all(feature = "alloc", not(feature = "allocator_api")))]
+impl<T: core::panic::RefUnwindSafe> core::panic::RefUnwindSafe for PtrCell<T> {}
+
+// The value is kept on the heap, so moving the cell never moves the value
+#[cfg(all(feature = "alloc", not(feature = "allocator_api")))]
+impl<T> Unpin for PtrCell<T> {}
+
+#[cfg(feature = "alloc")]
 impl<T> PtrCell<T> {
+    /// Whether every operation on the cell is lock-free
+    ///
+    /// This is `false` with the `critical-section` feature, and with the `portable-atomic` feature
+    /// on targets where it falls back to locks. Generic code can branch on it like on a `cfg`
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::PtrCell;
+    ///
+    /// // Spinning on a cell that may be locked by a preempted thread could take a while
+    /// const SPIN_LIMIT: u32 = if PtrCell::<u8>::IS_LOCK_FREE { 64 } else { 1 };
+    ///
+    /// assert!(SPIN_LIMIT > 0)
+    /// ```
+    pub const IS_LOCK_FREE: bool = BACKEND.is_lock_free();
+
+    /// Returns `true` if every operation on the cell is lock-free
+    ///
+    /// Same as [`IS_LOCK_FREE`](Self::IS_LOCK_FREE). The answer comes from the
+    /// [backend](Self::backend), so it's known at compile time
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::PtrCell;
+    ///
+    /// assert_eq!(PtrCell::<u8>::is_lock_free(), PtrCell::<u8>::backend().is_lock_free())
+    /// ```
+    #[must_use]
+    pub const fn is_lock_free() -> bool {
+        Self::IS_LOCK_FREE
+    }
+
+    /// Returns the implementation of atomics that the cell is built on
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::{Backend, PtrCell};
+    ///
+    /// if PtrCell::<u8>::backend() == Backend::Native {
+    ///     println!("Using the atomics of the standard library")
+    /// }
+    /// ```
+    #[must_use]
+    pub const fn backend() -> Backend {
+        Backend::CURRENT
+    }
+
     /// Inserts the value constructed from this cell by `new` into the cell itself
     ///
     /// Think of this like the `push` method of a linked list, where each node contains a `PtrCell`
     ///
+    /// `new` is given an empty cell. The current value of this cell is linked into it when the
+    /// owner is inserted, so `new` never takes part in ownership of that value. `new` must leave
+    /// the given cell empty, which debug builds assert. Release builds drop anything it puts there
+    ///
+    /// Before version 3.0.0, `new` was given a cell that already held the current value. Code that
+    /// inspected the value through that cell has to read it from this cell beforehand instead
+    ///
+    /// The insertion is retried until no other thread interferes with it. Pass an [`Exchange`] to
+    /// choose weaker semantics for the failed attempts
+    ///
+    /// # Panics
+    ///
+    /// If `new` panics, or in debug builds, if it leaves a value in the given cell. The cell is
+    /// left as it was and nothing is allocated
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, PtrCell, Semantics::Relaxed};
+    /// use std::panic::{catch_unwind, AssertUnwindSafe};
+    ///
+    /// let list = PtrCell::default();
+    /// Node::push(&list, 1, Relaxed);
+    ///
+    /// let pushing = catch_unwind(AssertUnwindSafe(|| {
+    ///     list.map_owner(|_| -> Node<i32> { panic!("Out of values") }, Relaxed)
+    /// }));
+    ///
+    /// assert!(pushing.is_err());
+    ///
+    /// let head = list.take(Relaxed).expect("The list shouldn't be empty");
+    /// assert!(head.into_iter().eq([1]))
+    /// ```
+    ///
     /// # Examples
     ///
     /// The code below turns a sentence into a naive linked list of words, which is then assembled
     /// back into a [`String`][1]
     ///
-    /// ```rust
+    #[doc = doctest!()]
     /// use ptr_cell::{PtrCell, Semantics};
     ///
     /// struct Node<T> {
@@ -222,286 +949,1413 @@ impl<T> PtrCell<T> {
     /// ```
     ///
     /// [1]: https://doc.rust-lang.org/std/string/struct.String.html
-    pub fn map_owner<F>(&self, new: F, order: Semantics)
+    pub fn map_owner<F>(&self, new: F, order: impl Into<Exchange>)
     where
         F: FnOnce(Self) -> T,
         T: AsMut<Self>,
     {
-        let value_ptr = self.get_ptr(order);
-        let value = unsafe { Self::from_ptr(value_ptr) };
-
-        let owner_slot = Some(new(value));
-        let owner_ptr = Self::heap_leak(owner_slot);
-
-        let owner = unsafe { &mut *owner_ptr };
-        let value_ptr = owner.as_mut().value.get_mut();
-
-        loop {
-            let value_ptr_result = self.value.compare_exchange_weak(
-                *value_ptr,
-                owner_ptr,
-                order.read_write(),
-                order.read(),
-            );
-
-            let Err(modified) = value_ptr_result else {
-                break;
-            };
-
-            *value_ptr = modified;
-            core::hint::spin_loop();
-        }
+        let _ = self.map_owner_ptr(new, order);
     }
 
-    /// Swaps the values of two cells
+    /// Like [`map_owner`](Self::map_owner), but `new` may fail
+    ///
+    /// On failure, `new` must hand back the cell it was given along with an error. The cell is then
+    /// left exactly as it was, and nothing is allocated
+    ///
+    /// # Errors
+    ///
+    /// Returns the error produced by `new`
     ///
     /// # Usage
     ///
-    /// ```rust
-    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, PtrCell, Semantics::Relaxed};
     ///
-    /// let one: PtrCell<u8> = 1.into();
-    /// let mut two: PtrCell<u8> = 2.into();
+    /// let list = PtrCell::default();
     ///
-    /// one.swap(&mut two, Relaxed);
+    /// let push_even = |value: u8| {
+    ///     list.try_map_owner(
+    ///         |next| match value % 2 {
+    ///             0 => Ok(Node { value, next }),
+    ///             _ => Err((next, value)),
+    ///         },
+    ///         Relaxed,
+    ///     )
+    /// };
     ///
-    /// assert_eq!(two.take(Relaxed), Some(1));
-    /// assert_eq!(one.take(Relaxed), Some(2))
+    /// assert_eq!(push_even(2), Ok(()));
+    /// assert_eq!(push_even(3), Err(3));
+    /// assert_eq!(push_even(4), Ok(()));
+    ///
+    /// let head = list.take(Relaxed).expect("The list shouldn't be empty");
+    ///
+    /// assert!(head.into_iter().eq([4, 2]))
     /// ```
-    #[inline]
-    pub fn swap(&self, other: &mut Self, order: Semantics) {
-        let other_ptr = other.get_ptr(Semantics::Relaxed);
-
-        unsafe {
-            let ptr = self.replace_ptr(other_ptr, order);
-            other.set_ptr(ptr, Semantics::Relaxed);
-        }
+    pub fn try_map_owner<F, E>(&self, new: F, order: impl Into<Exchange>) -> Result<(), E>
+    where
+        F: FnOnce(Self) -> Result<T, (Self, E)>,
+        T: AsMut<Self>,
+    {
+        self.insert_owner(new, order.into(), Spin, Self::heap_leak)
+            .map(drop)
     }
 
-    /// Takes out the cell's value
+    /// Like [`map_owner`](Self::map_owner), but returns a pointer to the inserted owner
+    ///
+    /// The pointer identifies the owner for as long as it stays in the chain. For example, it can
+    /// be compared against the pointers encountered while walking the chain
+    ///
+    /// # Safety
+    ///
+    /// Not inherently unsafe. See [Pointer Safety][1]
     ///
     /// # Usage
     ///
-    /// ```rust
-    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, PtrCell, Semantics::Relaxed};
     ///
-    /// let cell: PtrCell<u8> = 45.into();
+    /// let list = PtrCell::default();
+    ///
+    /// let first = list.map_owner_ptr(|next| Node { value: 1, next }, Relaxed);
+    /// let second = list.map_owner_ptr(|next| Node { value: 2, next }, Relaxed);
+    ///
+    /// assert_eq!(list.get_ptr(Relaxed), second);
+    /// assert_eq!(unsafe { &*second }.next.get_ptr(Relaxed), first)
+    /// ```
+    ///
+    /// [1]: https://docs.rs/ptr_cell/latest/ptr_cell/struct.PtrCell.html#pointer-safety
+    pub fn map_owner_ptr<F>(&self, new: F, order: impl Into<Exchange>) -> *mut T
+    where
+        F: FnOnce(Self) -> T,
+        T: AsMut<Self>,
+    {
+        let infallible = |value| Ok::<_, (Self, Infallible)>(new(value));
+        let Ok(owner_ptr) = self.insert_owner(infallible, order.into(), Spin, Self::heap_leak);
+
+        owner_ptr
+    }
+
+    /// Like [`map_owner`](Self::map_owner), but waits between contended attempts with `backoff`
+    ///
+    /// See the [`backoff`] module for the available strategies
+    ///
+    /// # Panics
+    ///
+    /// If `new` or `backoff` panics. The cell is left as it was, and an owner that was already
+    /// constructed is dropped without its linked value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, PtrCell, Semantics::Relaxed};
+    ///
+    /// let list = PtrCell::default();
+    ///
+    /// list.map_owner_with_backoff(|next| Node { value: 1, next }, Relaxed, std::thread::yield_now);
+    ///
+    /// assert!(!list.is_empty(Relaxed))
+    /// ```
+    pub fn map_owner_with_backoff<F, B>(&self, new: F, order: impl Into<Exchange>, backoff: B)
+    where
+        F: FnOnce(Self) -> T,
+        T: AsMut<Self>,
+        B: Backoff,
+    {
+        let infallible = |value| Ok::<_, (Self, Infallible)>(new(value));
+        let Ok(_) = self.insert_owner(infallible, order.into(), backoff, Self::heap_leak);
+    }
+
+    /// Shared implementation of the `map_owner` family. Returns a pointer to the inserted owner
+    ///
+    /// The owner is leaked with `leak`, which must uphold the contract of [`NodeAlloc`]
+    fn insert_owner<F, E, B, L>(
+        &self,
+        new: F,
+        order: Exchange,
+        mut backoff: B,
+        leak: L,
+    ) -> Result<*mut T, E>
+    where
+        F: FnOnce(Self) -> Result<T, (Self, E)>,
+        T: AsMut<Self>,
+        B: Backoff,
+        L: FnOnce(Option<T>) -> *mut T,
+    {
+        /// Frees an owner that hasn't been inserted yet if the backoff strategy panics
+        struct Abandon<T: AsMut<PtrCell<T>>>(*mut T);
+
+        impl<T: AsMut<PtrCell<T>>> Drop for Abandon<T> {
+            fn drop(&mut self) {
+                let owner = unsafe { &mut *self.0 };
+
+                // The linked value still belongs to the cell
+                *owner.as_mut().value.get_mut() = core::ptr::null_mut();
+
+                drop(unsafe { PtrCell::heap_reclaim(self.0) });
+            }
+        }
+
+        // `new` only ever owns an empty cell, so nothing that the cell points to is lost if it
+        // panics. The cell's value is linked into the owner below
+        let mut owner = match new(Self::empty()) {
+            Ok(owner) => owner,
+            Err((_, error)) => return Err(error),
+        };
+
+        debug_assert!(
+            owner.as_mut().is_empty(Semantics::Relaxed),
+            "`new` must leave the cell it's given empty"
+        );
+
+        let owner_slot = Some(owner);
+
+        let owner_ptr = leak(owner_slot);
+
+        let abandon = Abandon(owner_ptr);
+
+        let owner = unsafe { &mut *owner_ptr };
+        let value_ptr = owner.as_mut().value.get_mut();
+
+        // In release builds, anything that `new` put into the cell is dropped to make room for the
+        // cell's value
+        let stray_ptr = core::mem::replace(value_ptr, self.get_ptr(order.failure));
+        drop(unsafe { Self::heap_reclaim(stray_ptr) });
+
+        loop {
+            let value_ptr_result = self.value.compare_exchange_weak(
+                *value_ptr,
+                owner_ptr,
+                order.success(),
+                order.failure(),
+            );
+
+            let Err(modified) = value_ptr_result else {
+                break;
+            };
+
+            #[cfg(feature = "stats")]
+            stats::exchange_failed();
+
+            *value_ptr = modified;
+            backoff.backoff();
+        }
+
+        core::mem::forget(abandon);
+
+        #[cfg(feature = "tracing")]
+        trace::event(self, "map_owner", order, value_ptr.is_null());
+
+        Ok(owner_ptr)
+    }
+
+    /// Swaps the values of two cells
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let one: PtrCell<u8> = 1.into();
+    /// let mut two: PtrCell<u8> = 2.into();
+    ///
+    /// one.swap(&mut two, Relaxed);
+    ///
+    /// assert_eq!(two.take(Relaxed), Some(1));
+    /// assert_eq!(one.take(Relaxed), Some(2))
+    /// ```
+    #[inline]
+    pub fn swap(&self, other: &mut Self, order: Semantics) {
+        let other_ptr = other.get_ptr(Semantics::Relaxed);
+
+        unsafe {
+            let ptr = self.replace_ptr(other_ptr, order);
+            other.set_ptr(ptr, Semantics::Relaxed);
+        }
+    }
+
+    /// Swaps the values of two shared cells
+    ///
+    /// Unlike [`swap`](Self::swap), both cells may be used by other threads at the same time. The
+    /// exchange then isn't atomic, but made of two steps:
+    ///
+    /// 1. The value of this cell is moved into `other`, leaving this cell empty
+    /// 2. The previous value of `other` is moved into this cell
+    ///
+    /// Between the steps, other threads may see this cell empty. A value that another thread
+    /// writes to this cell in the meantime is overwritten by the second step and silently dropped.
+    /// No order of the operations explains that outcome: the write is lost, and this cell ends up
+    /// with the previous value of `other` anyway. If writes to this cell may race with the swap,
+    /// use [`transfer_to`](Self::transfer_to), which never drops a value
+    ///
+    /// Swapping a cell with itself does nothing
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    /// use std::sync::Arc;
+    ///
+    /// let one = Arc::new(PtrCell::from(1));
+    /// let two = Arc::new(PtrCell::from(2));
+    ///
+    /// let (one_clone, two_clone) = (Arc::clone(&one), Arc::clone(&two));
+    /// std::thread::spawn(move || one_clone.swap_shared(&two_clone, Coupled))
+    ///     .join()
+    ///     .expect("The thread shouldn't panic");
+    ///
+    /// assert_eq!(one.take(Coupled), Some(2));
+    /// assert_eq!(two.take(Coupled), Some(1))
+    /// ```
+    pub fn swap_shared(&self, other: &Self, order: Semantics) {
+        if core::ptr::eq(self, other) {
+            return;
+        }
+
+        let ptr = self.take_ptr(order);
+        let other_ptr = other.replace_ptr(ptr, order);
+        let overwritten_ptr = self.replace_ptr(other_ptr, order);
+
+        unsafe { Self::heap_reclaim(overwritten_ptr) };
+    }
+
+    /// Moves the cell's value into `dest` if `dest` is empty
+    ///
+    /// Returns whether the value was moved. If `dest` is occupied, the value is put back into this
+    /// cell instead. Both steps only move the value's pointer, so the value is never dropped or
+    /// lost along the way, even if the thread panics
+    ///
+    /// Between the steps, other threads may see both cells empty. If they fill both cells in the
+    /// meantime, the value has nowhere to go, so the call blocks, spinning until another thread
+    /// empties one of the cells. Use [`transfer_to_with_backoff`](Self::transfer_to_with_backoff)
+    /// to wait in another way
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    ///
+    /// let inbox = PtrCell::from("letter");
+    /// let outbox = PtrCell::default();
+    ///
+    /// assert!(inbox.transfer_to(&outbox, Coupled));
+    /// assert!(inbox.is_empty(Coupled));
+    ///
+    /// inbox.set(Some("parcel"), Coupled);
+    ///
+    /// assert!(!inbox.transfer_to(&outbox, Coupled));
+    /// assert_eq!(inbox.take(Coupled), Some("parcel"));
+    /// assert_eq!(outbox.take(Coupled), Some("letter"))
+    /// ```
+    #[inline]
+    pub fn transfer_to(&self, dest: &Self, order: impl Into<Exchange>) -> bool {
+        self.transfer_to_with_backoff(dest, order, Spin)
+    }
+
+    /// Like [`transfer_to`](Self::transfer_to), but waits with `backoff` while both cells are
+    /// occupied
+    ///
+    /// See the [`backoff`] module for the available strategies
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    ///
+    /// let inbox = PtrCell::from("letter");
+    /// let outbox = PtrCell::default();
+    ///
+    /// assert!(inbox.transfer_to_with_backoff(&outbox, Coupled, std::thread::yield_now));
+    /// assert_eq!(outbox.take(Coupled), Some("letter"))
+    /// ```
+    pub fn transfer_to_with_backoff<B>(
+        &self,
+        dest: &Self,
+        order: impl Into<Exchange>,
+        mut backoff: B,
+    ) -> bool
+    where
+        B: Backoff,
+    {
+        let order = order.into();
+
+        let ptr = self.take_ptr(order.success);
+        if ptr.is_null() {
+            return false;
+        }
+
+        loop {
+            let null = core::ptr::null_mut();
+
+            let installing =
+                dest.value
+                    .compare_exchange(null, ptr, order.success(), order.failure());
+
+            if installing.is_ok() {
+                break true;
+            }
+
+            let restoring =
+                self.value
+                    .compare_exchange(null, ptr, order.success(), order.failure());
+
+            if restoring.is_ok() {
+                break false;
+            }
+
+            backoff.backoff();
+        }
+    }
+
+    /// Replaces the cell's value with one computed from it by `f`, returning the value it was
+    /// computed from
+    ///
+    /// This is the read-copy-update pattern. As values can't be borrowed from a shared cell, the
+    /// current value is taken out while `f` runs, leaving the cell empty in the meantime. If
+    /// another thread inserts a value before the result is put back, the result is dropped and `f`
+    /// is called again with the inserted value. The values taken out by failed attempts are
+    /// dropped as well
+    ///
+    /// The value is always taken out with at least [`AcquireOnly`](Semantics::AcquireOnly)
+    /// semantics, even if `order` is weaker, as `f` reads it
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    ///
+    /// let config = PtrCell::from(vec!["verbose"]);
+    ///
+    /// let old = config.rcu(
+    ///     |flags| {
+    ///         let mut flags = flags.cloned().unwrap_or_default();
+    ///         flags.push("color");
+    ///         Some(flags)
+    ///     },
+    ///     Coupled,
+    /// );
+    ///
+    /// assert_eq!(old, Some(vec!["verbose"]));
+    /// assert_eq!(config.take(Coupled), Some(vec!["verbose", "color"]))
+    /// ```
+    pub fn rcu<F>(&self, mut f: F, order: impl Into<Exchange>) -> Option<T>
+    where
+        F: FnMut(Option<&T>) -> Option<T>,
+    {
+        let order = order.into();
+
+        loop {
+            let current_ptr = self
+                .value
+                .swap(core::ptr::null_mut(), acquiring(order.success()));
+
+            let current = unsafe { Self::heap_reclaim(current_ptr) };
+            let new_ptr = Self::heap_leak(f(current.as_ref()));
+
+            let installing = self.value.compare_exchange(
+                core::ptr::null_mut(),
+                new_ptr,
+                order.success(),
+                order.failure(),
+            );
+
+            if installing.is_ok() {
+                break current;
+            }
+
+            drop(unsafe { Self::heap_reclaim(new_ptr) });
+        }
+    }
+
+    /// Takes out the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell: PtrCell<u8> = 45.into();
     ///
     /// assert_eq!(cell.take(Relaxed), Some(45));
     /// assert_eq!(cell.take(Relaxed), None)
     /// ```
     #[inline]
     pub fn take(&self, order: Semantics) -> Option<T> {
-        self.replace(None, order)
+        #[cfg(feature = "stats")]
+        stats::taken();
+
+        let leak = self.take_ptr(order);
+
+        #[cfg(feature = "tracing")]
+        trace::event(self, "take", order, leak.is_null());
+
+        unsafe { Self::heap_reclaim(leak) }
     }
 
     /// Takes out the cell's pointer
     ///
     /// # Safety
     ///
-    /// Not inherently unsafe. See [Pointer Safety][1]
+    /// Not inherently unsafe. See [Pointer Safety][1]
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell: PtrCell<u8> = 45.into();
+    /// let ptr = cell.take_ptr(Relaxed);
+    ///
+    /// assert_eq!(unsafe { ptr_cell::PtrCell::heap_reclaim(ptr) }, Some(45));
+    /// assert_eq!(cell.take_ptr(Relaxed), std::ptr::null_mut())
+    /// ```
+    ///
+    /// [1]: https://docs.rs/ptr_cell/latest/ptr_cell/struct.PtrCell.html#pointer-safety
+    #[inline]
+    pub fn take_ptr(&self, order: Semantics) -> *mut T {
+        self.replace_ptr(core::ptr::null_mut(), order)
+    }
+
+    /// Inserts a value into the cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::default();
+    /// cell.set(Some(1776), Relaxed);
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(1776))
+    /// ```
+    #[inline]
+    pub fn set(&self, slot: Option<T>, order: Semantics) {
+        #[cfg(feature = "stats")]
+        stats::replaced();
+
+        let new_leak = Self::heap_leak(slot);
+        let old_leak = self.replace_ptr(new_leak, order);
+
+        #[cfg(feature = "tracing")]
+        trace::event(self, "set", order, old_leak.is_null());
+
+        drop(unsafe { Self::heap_reclaim(old_leak) });
+    }
+
+    /// Inserts a value into the cell, reusing the allocation of the previous value
+    ///
+    /// [`set`](Self::set) allocates memory for the new value and frees the memory of the old one.
+    /// This method moves the new value into the old one's memory instead, so a cell whose value is
+    /// replaced over and over only touches the allocator when it's found empty. Emptying a cell
+    /// never touches the allocator either way
+    ///
+    /// See [`replace_in_place`](Self::replace_in_place) for how it interacts with other threads
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    ///
+    /// let frame = PtrCell::from([0_u8; 4096]);
+    ///
+    /// for byte in 1..=255 {
+    ///     frame.set_reusing([byte; 4096], Coupled);
+    /// }
+    ///
+    /// assert_eq!(frame.take(Coupled), Some([255; 4096]))
+    /// ```
+    #[inline]
+    pub fn set_reusing(&self, value: T, order: Semantics) {
+        drop(self.replace_in_place(value, order));
+    }
+
+    /// Replaces the cell's value, writing the new value into the allocation of the previous one
+    ///
+    /// Where [`replace`](Self::replace) allocates memory for the new value and frees the memory of
+    /// the old one, this method only allocates when the cell is empty. For large values in a
+    /// steady flow, that halves the traffic to the allocator
+    ///
+    /// Unlike `replace`, this isn't a single atomic operation. The allocation is taken out of the
+    /// cell while the values are swapped and put back afterwards, and other threads can observe
+    /// that:
+    ///
+    /// - The cell is empty in the meantime, so a concurrent [`take`](Self::take) or
+    ///   [`is_empty`](Self::is_empty) may find it empty even though it holds a value before and
+    ///   after the call
+    ///
+    /// - A value inserted concurrently in the meantime stays in the cell, and `new` is dropped
+    ///   without being handed back. The previous value is still returned
+    ///
+    /// Use `replace` when the cell has other writers and neither is acceptable
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    ///
+    /// let cell = PtrCell::default();
+    ///
+    /// assert_eq!(cell.replace_in_place([1_u64; 512], Coupled), None);
+    /// let address = cell.get_ptr(Coupled);
+    ///
+    /// assert_eq!(cell.replace_in_place([2; 512], Coupled), Some([1; 512]));
+    /// assert_eq!(cell.get_ptr(Coupled), address)
+    /// ```
+    #[must_use = "use `.set_reusing()` if you don't need the old value"]
+    pub fn replace_in_place(&self, new: T, order: Semantics) -> Option<T> {
+        let ptr = self.take_ptr(order);
+
+        if ptr.is_null() {
+            return self.replace(Some(new), order);
+        }
+
+        #[cfg(feature = "stats")]
+        stats::replaced();
+
+        // Nobody else can reach the memory until it's back in the cell
+        let old = unsafe { ptr.replace(new) };
+
+        let restoring = self.value.compare_exchange(
+            core::ptr::null_mut(),
+            ptr,
+            order.read_write(),
+            order.read(),
+        );
+
+        #[cfg(feature = "tracing")]
+        trace::event(self, "replace_in_place", order, false);
+
+        if restoring.is_err() {
+            drop(unsafe { Self::heap_reclaim(ptr) });
+        }
+
+        Some(old)
+    }
+
+    /// Inserts a pointer into the cell
+    ///
+    /// # Safety
+    ///
+    /// The pointed-to memory must conform to the [memory layout][1] used by [`Box`]
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::default();
+    ///
+    /// let ptr = PtrCell::heap_leak(Some(1776));
+    /// unsafe { cell.set_ptr(ptr, Relaxed) };
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(1776))
+    /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    #[inline]
+    pub unsafe fn set_ptr(&self, ptr: *mut T, order: Semantics) {
+        self.value.store(ptr, order.write());
+    }
+
+    /// Inserts a value into the cell, failing instead of aborting if memory can't be allocated
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AllocError`] if the allocation fails. The cell is left unchanged
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::default();
+    ///
+    /// assert!(cell.try_set(Some(1776), Relaxed).is_ok());
+    /// assert_eq!(cell.take(Relaxed), Some(1776))
+    /// ```
+    #[inline]
+    pub fn try_set(&self, slot: Option<T>, order: Semantics) -> Result<(), AllocError> {
+        self.try_replace(slot, order).map(drop)
+    }
+
+    /// Replaces the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::from('a');
+    ///
+    /// assert_eq!(cell.replace(Some('b'), Relaxed), Some('a'));
+    /// assert_eq!(cell.take(Relaxed), Some('b'))
+    /// ```
+    #[inline]
+    #[must_use = "use `.set()` if you don't need the old value"]
+    pub fn replace(&self, slot: Option<T>, order: Semantics) -> Option<T> {
+        #[cfg(feature = "stats")]
+        stats::replaced();
+
+        let new_leak = Self::heap_leak(slot);
+        let old_leak = self.replace_ptr(new_leak, order);
+
+        #[cfg(feature = "tracing")]
+        trace::event(self, "replace", order, old_leak.is_null());
+
+        unsafe { Self::heap_reclaim(old_leak) }
+    }
+
+    /// Like [`replace`](Self::replace), but with separate memory orderings for the exchange that
+    /// replaces the value and for the reads that precede it
+    ///
+    /// The orderings follow the rules of [`fetch_update`][1], so a protocol written for it carries
+    /// over unchanged. `success` applies to the swap that installs the new value and reads the
+    /// previous one. A swap can't fail and doesn't need a load beforehand, so `failure_or_load`
+    /// doesn't affect the operation and is only checked to be a valid load ordering
+    ///
+    /// # Panics
+    ///
+    /// If `failure_or_load` is [`Release`](Ordering::Release) or [`AcqRel`](Ordering::AcqRel), as
+    /// a read can't release anything. Nothing is allocated in that case
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::PtrCell;
+    /// use std::sync::atomic::Ordering::{Acquire, Relaxed};
+    ///
+    /// let cell = PtrCell::from(vec![1, 2]);
+    ///
+    /// // Synchronizes with the writer of the previous value, without releasing the new one
+    /// let previous = cell.replace_with_orderings(Some(vec![3]), Acquire, Relaxed);
+    ///
+    /// assert_eq!(previous, Some(vec![1, 2]));
+    /// assert_eq!(cell.into_inner(), Some(vec![3]))
+    /// ```
+    ///
+    /// [1]: core::sync::atomic::AtomicPtr::fetch_update
+    #[must_use = "use `.set()` if you don't need the old value"]
+    pub fn replace_with_orderings(
+        &self,
+        slot: Option<T>,
+        success: Ordering,
+        failure_or_load: Ordering,
+    ) -> Option<T> {
+        let order = Semantics::custom(failure_or_load, Ordering::Relaxed, success);
+
+        #[cfg(feature = "stats")]
+        stats::replaced();
+
+        let new_leak = Self::heap_leak(slot);
+        let old_leak = self.value.swap(new_leak, order.read_write());
+
+        #[cfg(feature = "tracing")]
+        trace::event(self, "replace", order, old_leak.is_null());
+
+        unsafe { Self::heap_reclaim(old_leak) }
+    }
+
+    /// Replaces the cell's pointer
+    ///
+    /// **WARNING: THIS FUNCTION WAS ERRONEOUSLY LEFT SAFE. IT'S UNSAFE AND WILL BE MARKED AS SUCH
+    /// IN THE NEXT MAJOR RELEASE**
+    ///
+    /// # Safety
+    ///
+    /// The pointed-to memory must conform to the [memory layout][1] used by [`Box`]
+    ///
+    /// See also: [Pointer Safety][2]
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// unsafe {
+    ///     let a = PtrCell::heap_leak(Some('a'));
+    ///     let b = PtrCell::heap_leak(Some('b'));
+    ///
+    ///     let cell = PtrCell::from_ptr(a);
+    ///
+    ///     assert_eq!(cell.replace_ptr(b, Relaxed), a);
+    ///     assert_eq!(cell.take_ptr(Relaxed), b);
+    ///
+    ///     PtrCell::heap_reclaim(a);
+    ///     PtrCell::heap_reclaim(b);
+    /// }
+    /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    /// [2]: https://docs.rs/ptr_cell/latest/ptr_cell/struct.PtrCell.html#pointer-safety
+    #[inline]
+    #[must_use = "use `.set_ptr()` if you don't need the old pointer"]
+    pub fn replace_ptr(&self, ptr: *mut T, order: Semantics) -> *mut T {
+        self.value.swap(ptr, order.read_write())
+    }
+
+    /// Updates the cell's pointer with `f` in a compare-exchange loop, like
+    /// [`AtomicPtr::fetch_update`](core::sync::atomic::AtomicPtr::fetch_update)
+    ///
+    /// `f` is called with the current pointer and returns the new one, or [`None`] to stop. It may
+    /// be called several times if other threads modify the cell in the meantime
+    ///
+    /// The pointers are only read: ownership of what they point to is up to the caller's protocol
+    ///
+    /// # Errors
+    ///
+    /// Returns the last pointer seen if `f` stopped. On success, the replaced pointer is returned
+    ///
+    /// # Safety
+    ///
+    /// Every pointer that `f` returns and that ends up in the cell must conform to the
+    /// [memory layout][1] used by [`Box`]
+    ///
+    /// See also: [Pointer Safety][2]
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    ///
+    /// let cell = PtrCell::<&str>::default();
+    /// let fallback = PtrCell::heap_leak(Some("fallback"));
+    ///
+    /// // Inserts the fallback only if the cell is empty
+    /// let update = unsafe {
+    ///     cell.fetch_update_ptr(Coupled, |ptr| ptr.is_null().then_some(fallback))
+    /// };
+    ///
+    /// assert_eq!(update, Ok(std::ptr::null_mut()));
+    /// assert_eq!(cell.take(Coupled), Some("fallback"))
+    /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    /// [2]: https://docs.rs/ptr_cell/latest/ptr_cell/struct.PtrCell.html#pointer-safety
+    pub unsafe fn fetch_update_ptr<F>(
+        &self,
+        order: impl Into<Exchange>,
+        mut f: F,
+    ) -> Result<*mut T, *mut T>
+    where
+        F: FnMut(*mut T) -> Option<*mut T>,
+    {
+        let order = order.into();
+        let mut current = self.value.load(order.failure());
+
+        while let Some(new) = f(current) {
+            let updating =
+                self.value
+                    .compare_exchange_weak(current, new, order.success(), order.failure());
+
+            match updating {
+                Ok(previous) => return Ok(previous),
+                Err(modified) => current = modified,
+            }
+        }
+
+        Err(current)
+    }
+
+    /// Replaces the cell's value, failing instead of aborting if memory can't be allocated
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AllocError`] if the allocation fails. The cell is left unchanged
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::from('a');
+    ///
+    /// assert_eq!(cell.try_replace(Some('b'), Relaxed), Ok(Some('a')));
+    /// assert_eq!(cell.take(Relaxed), Some('b'))
+    /// ```
+    #[inline]
+    #[must_use = "use `.try_set()` if you don't need the old value"]
+    pub fn try_replace(&self, slot: Option<T>, order: Semantics) -> Result<Option<T>, AllocError> {
+        let new_leak = Self::try_heap_leak(slot)?;
+
+        unsafe {
+            let old_leak = self.replace_ptr(new_leak, order);
+            Ok(Self::heap_reclaim(old_leak))
+        }
+    }
+
+    /// Mutably borrows the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let mut text = PtrCell::from("Point".to_string());
+    /// text.get_mut()
+    ///     .expect("The cell should contain a value")
+    ///     .push_str("er");
+    ///
+    /// assert_eq!(text.take(Relaxed), Some("Pointer".to_string()))
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        let leak = *self.value.get_mut();
+
+        non_null(leak).map(|ptr| unsafe { &mut *ptr })
+    }
+
+    /// Inserts a value into the cell, then mutably borrows it
+    ///
+    /// The previous value is dropped. No atomic operations are performed, as the cell is borrowed
+    /// exclusively
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let mut cell = PtrCell::from(1);
+    /// *cell.insert(2) += 1;
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(3))
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, value: T) -> &mut T {
+        let new_leak = Self::heap_leak(Some(value));
+        let old_leak = core::mem::replace(self.value.get_mut(), new_leak);
+
+        unsafe {
+            Self::heap_reclaim(old_leak);
+            &mut *new_leak
+        }
+    }
+
+    /// Mutably borrows the cell's value, inserting `value` first if the cell is empty
+    ///
+    /// No atomic operations are performed, as the cell is borrowed exclusively
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::PtrCell;
+    ///
+    /// let mut cell = PtrCell::default();
+    ///
+    /// assert_eq!(*cell.get_or_insert(1), 1);
+    /// assert_eq!(*cell.get_or_insert(2), 1)
+    /// ```
+    #[inline]
+    pub fn get_or_insert(&mut self, value: T) -> &mut T {
+        self.get_or_insert_with(|| value)
+    }
+
+    /// Mutably borrows the cell's value, inserting the result of `f` first if the cell is empty
+    ///
+    /// No atomic operations are performed, as the cell is borrowed exclusively
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::PtrCell;
+    ///
+    /// let mut cell = PtrCell::default();
+    /// cell.get_or_insert_with(Vec::new).push('a');
+    /// cell.get_or_insert_with(Vec::new).push('b');
+    ///
+    /// assert_eq!(cell.into_inner(), Some(vec!['a', 'b']))
+    /// ```
+    #[inline]
+    pub fn get_or_insert_with<F>(&mut self, f: F) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        let leak = self.value.get_mut();
+
+        if leak.is_null() {
+            *leak = Self::heap_leak(Some(f()));
+        }
+
+        unsafe { &mut **leak }
+    }
+
+    /// Consumes the cell, returning its value
+    ///
+    /// No atomic operations are performed, as the cell is owned
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::PtrCell;
+    ///
+    /// let cell = PtrCell::from("Dopamine");
+    ///
+    /// assert_eq!(cell.into_inner(), Some("Dopamine"))
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> Option<T> {
+        let ptr = self.leak();
+
+        unsafe { Self::heap_reclaim(ptr) }
+    }
+
+    /// Consumes the cell, giving up ownership of its value
+    ///
+    /// [`None`] is represented by a null pointer
+    ///
+    /// The memory will conform to the [memory layout][1] used by [`Box`]. It can be reclaimed with
+    /// [`heap_reclaim`](Self::heap_reclaim)
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::PtrCell;
+    ///
+    /// let cell = PtrCell::from(1155);
+    /// let ptr = cell.leak();
+    ///
+    /// assert_eq!(unsafe { PtrCell::heap_reclaim(ptr) }, Some(1155))
+    /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    #[inline]
+    #[must_use = "losing the pointer will leak memory"]
+    pub fn leak(self) -> *mut T {
+        let mut cell = core::mem::ManuallyDrop::new(self);
+
+        *cell.value.get_mut()
+    }
+
+    /// Returns a pointer to the cell's value
+    ///
+    /// # Safety
+    ///
+    /// Not inherently unsafe. See [Pointer Safety][1]
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::<[u8; 3]>::default();
+    ///
+    /// assert_eq!(cell.get_ptr(Relaxed), std::ptr::null_mut())
+    /// ```
+    ///
+    /// [1]: https://docs.rs/ptr_cell/latest/ptr_cell/struct.PtrCell.html#pointer-safety
+    #[inline]
+    pub fn get_ptr(&self, order: Semantics) -> *mut T {
+        self.value.load(order.read())
+    }
+
+    /// Returns the atomic pointer that backs the cell
+    ///
+    /// Meant for integrations that need to operate on the cell's storage directly, like hazard
+    /// pointer or RCU libraries
+    ///
+    /// Only available with the native atomics, where the cell is backed by the
+    /// [`AtomicPtr`](core::sync::atomic::AtomicPtr) of [`core`]. The `portable-atomic`, `critical-section` and `shuttle` features, as well as
+    /// single-threaded WebAssembly, back the cell with something else, so the method doesn't
+    /// exist there
+    ///
+    /// # Safety
+    ///
+    /// Every pointer stored through the returned reference must conform to the
+    /// [memory layout][1] used by [`Box`]. The cell owns the pointed-to value, so it must not be
+    /// freed or used after being replaced, unless ownership is taken over in the process
+    ///
+    /// See also: [Pointer Safety][2]
     ///
     /// # Usage
     ///
     /// ```rust
     /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    /// use std::sync::atomic::Ordering;
     ///
-    /// let cell: PtrCell<u8> = 45.into();
-    /// let ptr = cell.take_ptr(Relaxed);
+    /// let cell = PtrCell::from(2048);
+    /// let atomic = unsafe { cell.as_atomic_ptr() };
     ///
-    /// assert_eq!(unsafe { ptr_cell::PtrCell::heap_reclaim(ptr) }, Some(45));
-    /// assert_eq!(cell.take_ptr(Relaxed), std::ptr::null_mut())
+    /// assert_eq!(atomic.load(Ordering::Relaxed), cell.get_ptr(Relaxed))
     /// ```
     ///
-    /// [1]: https://docs.rs/ptr_cell/latest/ptr_cell/struct.PtrCell.html#pointer-safety
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    /// [2]: https://docs.rs/ptr_cell/latest/ptr_cell/struct.PtrCell.html#pointer-safety
     #[inline]
-    pub fn take_ptr(&self, order: Semantics) -> *mut T {
-        self.replace_ptr(core::ptr::null_mut(), order)
+    #[cfg(not(any(
+        feature = "portable-atomic",
+        feature = "critical-section",
+        feature = "shuttle",
+        all(target_arch = "wasm32", not(target_feature = "atomics"))
+    )))]
+    pub const unsafe fn as_atomic_ptr(&self) -> &core::sync::atomic::AtomicPtr<T> {
+        &self.value
     }
 
-    /// Inserts a value into the cell
+    /// Issues a memory fence that upgrades the [`Relaxed`](Semantics::Relaxed) operations on this
+    /// cell around it to `order`
+    ///
+    /// This is the [fence](Semantics::fence) of `order`. A release fence must come before the
+    /// relaxed write it upgrades, and an acquire fence after the relaxed read. One fence can then
+    /// cover a whole batch of operations, or only be issued when a read turns out to be relevant
+    ///
+    /// Only pointers can be published this way. [`set`](Self::set) writes the value to the heap
+    /// after a preceding fence, and [`take`](Self::take) reads it before a following one, so both
+    /// would race with the other side. Leak the value ahead of time and publish its pointer with
+    /// [`set_ptr`](Self::set_ptr) and [`take_ptr`](Self::take_ptr) instead
     ///
     /// # Usage
     ///
-    /// ```rust
-    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::{AcquireOnly, Relaxed, ReleaseOnly}};
     ///
-    /// let cell = PtrCell::default();
-    /// cell.set(Some(1776), Relaxed);
+    /// static MAILBOX: PtrCell<String> = PtrCell::empty();
     ///
-    /// assert_eq!(cell.take(Relaxed), Some(1776))
+    /// let writer = std::thread::spawn(|| {
+    ///     let letter = PtrCell::heap_leak(Some(String::from("Dear reader")));
+    ///
+    ///     // Makes the letter visible to whoever acquires the pointer
+    ///     MAILBOX.fence_for(ReleaseOnly);
+    ///     unsafe { MAILBOX.set_ptr(letter, Relaxed) };
+    /// });
+    ///
+    /// let letter = loop {
+    ///     let ptr = MAILBOX.take_ptr(Relaxed);
+    ///
+    ///     if !ptr.is_null() {
+    ///         // Synchronizes with the writer's fence before the letter is read
+    ///         MAILBOX.fence_for(AcquireOnly);
+    ///         break unsafe { PtrCell::heap_reclaim(ptr) };
+    ///     }
+    ///
+    ///     std::hint::spin_loop()
+    /// };
+    ///
+    /// writer.join().expect("The writer shouldn't panic");
+    /// assert_eq!(letter.as_deref(), Some("Dear reader"))
     /// ```
     #[inline]
-    pub fn set(&self, slot: Option<T>, order: Semantics) {
-        let _ = self.replace(slot, order);
+    pub fn fence_for(&self, order: Semantics) {
+        order.fence();
     }
 
-    /// Inserts a pointer into the cell
+    /// Determines whether this cell is empty
     ///
-    /// # Safety
+    /// # Usage
     ///
-    /// The pointed-to memory must conform to the [memory layout][1] used by [`Box`]
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::<[u8; 3]>::default();
+    ///
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_empty(&self, order: Semantics) -> bool {
+        self.get_ptr(order).is_null()
+    }
+
+    /// Determines whether the cell points to `ptr`
+    ///
+    /// Only the addresses are compared, which suits values that are identified by their location,
+    /// like interned ones. Values of zero-sized types all share the same address
     ///
     /// # Usage
     ///
-    /// ```rust
+    #[doc = doctest!()]
     /// use ptr_cell::{PtrCell, Semantics::Relaxed};
     ///
-    /// let cell = PtrCell::default();
-    ///
-    /// let ptr = PtrCell::heap_leak(Some(1776));
-    /// unsafe { cell.set_ptr(ptr, Relaxed) };
+    /// let cell = PtrCell::from("interned");
+    /// let ptr = cell.get_ptr(Relaxed);
     ///
-    /// assert_eq!(cell.take(Relaxed), Some(1776))
+    /// assert!(cell.ptr_eq(ptr, Relaxed));
+    /// assert!(!cell.ptr_eq(std::ptr::null(), Relaxed))
     /// ```
-    ///
-    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
     #[inline]
-    pub unsafe fn set_ptr(&self, ptr: *mut T, order: Semantics) {
-        self.value.store(ptr, order.write());
+    pub fn ptr_eq(&self, ptr: *const T, order: Semantics) -> bool {
+        core::ptr::eq(self.get_ptr(order), ptr)
     }
 
-    /// Replaces the cell's value
+    /// Determines whether both cells point to the same value
+    ///
+    /// Only the addresses are compared, like in [`ptr_eq`](Self::ptr_eq). Two empty cells are
+    /// considered to hold the same value. The cells are read one after the other, so the result
+    /// may be outdated if either of them is modified in the meantime
     ///
     /// # Usage
     ///
-    /// ```rust
+    #[doc = doctest!()]
     /// use ptr_cell::{PtrCell, Semantics::Relaxed};
     ///
-    /// let cell = PtrCell::from('a');
+    /// let singleton = PtrCell::heap_leak(Some("singleton"));
+    /// let (first, second) = (PtrCell::default(), PtrCell::default());
     ///
-    /// assert_eq!(cell.replace(Some('b'), Relaxed), Some('a'));
-    /// assert_eq!(cell.take(Relaxed), Some('b'))
+    /// unsafe {
+    ///     first.set_ptr(singleton, Relaxed);
+    ///     second.set_ptr(singleton, Relaxed)
+    /// }
+    ///
+    /// assert!(first.holds(&second, Relaxed));
+    ///
+    /// // Only one of the cells may own the value
+    /// second.take_ptr(Relaxed);
+    /// assert!(!first.holds(&second, Relaxed))
     /// ```
     #[inline]
-    #[must_use = "use `.set()` if you don't need the old value"]
-    pub fn replace(&self, slot: Option<T>, order: Semantics) -> Option<T> {
-        let new_leak = Self::heap_leak(slot);
+    pub fn holds(&self, other: &Self, order: Semantics) -> bool {
+        self.ptr_eq(other.get_ptr(order), order)
+    }
 
-        unsafe {
-            let old_leak = self.replace_ptr(new_leak, order);
-            Self::heap_reclaim(old_leak)
+    /// Determines whether the cell's value is equal to `value`
+    ///
+    /// As values can't be borrowed from a shared cell, the current value is taken out for the
+    /// comparison and put back afterwards, leaving the cell empty in the meantime. If another
+    /// thread inserts a value before that, the inserted value is kept and the compared one is
+    /// dropped, as if it was replaced right after the comparison
+    ///
+    /// The value is always taken out with at least [`AcquireOnly`](Semantics::AcquireOnly)
+    /// semantics, even if `order` is weaker, as the comparison reads it
+    ///
+    /// # Panics
+    ///
+    /// If the comparison panics. The value is put back into the cell first
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    ///
+    /// let cell = PtrCell::from(String::from("needle"));
+    ///
+    /// assert!(cell.contains_value(&"needle".into(), Coupled));
+    /// assert!(!cell.contains_value(&"haystack".into(), Coupled));
+    /// assert_eq!(cell.take(Coupled).as_deref(), Some("needle"))
+    /// ```
+    pub fn contains_value(&self, value: &T, order: Semantics) -> bool
+    where
+        T: PartialEq,
+    {
+        /// Puts the compared value back into the cell, even if the comparison panics
+        struct Restore<'a, T> {
+            cell: &'a PtrCell<T>,
+            ptr: *mut T,
+            order: Semantics,
+        }
+
+        impl<T> Drop for Restore<'_, T> {
+            fn drop(&mut self) {
+                let restoring = self.cell.value.compare_exchange(
+                    core::ptr::null_mut(),
+                    self.ptr,
+                    self.order.read_write(),
+                    self.order.read(),
+                );
+
+                if restoring.is_err() {
+                    drop(unsafe { PtrCell::heap_reclaim(self.ptr) });
+                }
+            }
         }
+
+        let taking = self
+            .value
+            .swap(core::ptr::null_mut(), acquiring(order.read_write()));
+
+        let Some(ptr) = non_null(taking) else {
+            return false;
+        };
+
+        let _restore = Restore {
+            cell: self,
+            ptr,
+            order,
+        };
+
+        unsafe { &*ptr == value }
     }
 
-    /// Replaces the cell's pointer
+    /// Constructs a cell
     ///
-    /// **WARNING: THIS FUNCTION WAS ERRONEOUSLY LEFT SAFE. IT'S UNSAFE AND WILL BE MARKED AS SUCH
-    /// IN THE NEXT MAJOR RELEASE**
+    /// # Usage
     ///
-    /// # Safety
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
     ///
-    /// The pointed-to memory must conform to the [memory layout][1] used by [`Box`]
+    /// let cell = PtrCell::new(Some(0xFAA));
     ///
-    /// See also: [Pointer Safety][2]
+    /// assert_eq!(cell.take(Relaxed), Some(0xFAA));
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(slot: Option<T>) -> Self {
+        let ptr = Self::heap_leak(slot);
+
+        unsafe { Self::from_ptr(ptr) }
+    }
+
+    /// Constructs an empty cell
+    ///
+    /// Unlike [`new`](Self::new), this function is `const`, so it can initialize statics
     ///
     /// # Usage
     ///
-    /// ```rust
+    #[doc = doctest!()]
     /// use ptr_cell::{PtrCell, Semantics::Relaxed};
     ///
-    /// unsafe {
-    ///     let a = PtrCell::heap_leak(Some('a'));
-    ///     let b = PtrCell::heap_leak(Some('b'));
-    ///
-    ///     let cell = PtrCell::from_ptr(a);
+    /// static CELL: PtrCell<u16> = PtrCell::empty();
     ///
-    ///     assert_eq!(cell.replace_ptr(b, Relaxed), a);
-    ///     assert_eq!(cell.take_ptr(Relaxed), b);
+    /// assert!(CELL.is_empty(Relaxed));
     ///
-    ///     PtrCell::heap_reclaim(a);
-    ///     PtrCell::heap_reclaim(b);
-    /// }
+    /// CELL.set(Some(0xFAA), Relaxed);
+    /// assert_eq!(CELL.take(Relaxed), Some(0xFAA))
     /// ```
-    ///
-    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
-    /// [2]: https://docs.rs/ptr_cell/latest/ptr_cell/struct.PtrCell.html#pointer-safety
     #[inline]
-    #[must_use = "use `.set_ptr()` if you don't need the old pointer"]
-    pub fn replace_ptr(&self, ptr: *mut T, order: Semantics) -> *mut T {
-        self.value.swap(ptr, order.read_write())
+    #[must_use]
+    pub const fn empty() -> Self {
+        unsafe { Self::from_ptr(core::ptr::null_mut()) }
     }
 
-    /// Mutably borrows the cell's value
+    /// Constructs a cell, failing instead of aborting if memory can't be allocated
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AllocError`] if the allocation fails
     ///
     /// # Usage
     ///
-    /// ```rust
+    #[doc = doctest!()]
     /// use ptr_cell::{PtrCell, Semantics::Relaxed};
     ///
-    /// let mut text = PtrCell::from("Point".to_string());
-    /// text.get_mut()
-    ///     .expect("The cell should contain a value")
-    ///     .push_str("er");
+    /// let cell = PtrCell::try_new(Some(0xFAA)).expect("Allocation should succeed");
     ///
-    /// assert_eq!(text.take(Relaxed), Some("Pointer".to_string()))
+    /// assert_eq!(cell.take(Relaxed), Some(0xFAA))
     /// ```
     #[inline]
-    pub fn get_mut(&mut self) -> Option<&mut T> {
-        let leak = *self.value.get_mut();
+    pub fn try_new(slot: Option<T>) -> Result<Self, AllocError> {
+        let ptr = Self::try_heap_leak(slot)?;
 
-        non_null(leak).map(|ptr| unsafe { &mut *ptr })
+        Ok(unsafe { Self::from_ptr(ptr) })
     }
 
-    /// Returns a pointer to the cell's value
+    /// Constructs a cell that owns [leaked](Self::heap_leak) memory
+    ///
+    /// A null pointer represents [`None`]
     ///
     /// # Safety
     ///
-    /// Not inherently unsafe. See [Pointer Safety][1]
+    /// The memory must conform to the [memory layout][1] used by [`Box`]
     ///
     /// # Usage
     ///
-    /// ```rust
+    #[doc = doctest!()]
     /// use ptr_cell::{PtrCell, Semantics::Relaxed};
     ///
-    /// let cell = PtrCell::<[u8; 3]>::default();
+    /// let ptr = PtrCell::heap_leak(Some(0xFAA));
+    /// let cell = unsafe { PtrCell::from_ptr(ptr) };
     ///
-    /// assert_eq!(cell.get_ptr(Relaxed), std::ptr::null_mut())
+    /// assert_eq!(cell.take(Relaxed), Some(0xFAA));
+    /// assert!(cell.is_empty(Relaxed))
     /// ```
     ///
-    /// [1]: https://docs.rs/ptr_cell/latest/ptr_cell/struct.PtrCell.html#pointer-safety
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
     #[inline]
-    pub fn get_ptr(&self, order: Semantics) -> *mut T {
-        self.value.load(order.read())
+    pub const unsafe fn from_ptr(ptr: *mut T) -> Self {
+        let value = AtomicPtr::new(ptr);
+
+        Self {
+            value,
+            #[cfg(feature = "allocator_api")]
+            alloc: Global,
+            owns: PhantomData,
+        }
     }
 
-    /// Determines whether this cell is empty
+    /// Constructs a cell that takes over an atomic pointer to [leaked](Self::heap_leak) memory
+    ///
+    /// Only available with the native atomics, like [`as_atomic_ptr`](Self::as_atomic_ptr)
+    ///
+    /// # Safety
+    ///
+    /// The pointed-to memory must conform to the [memory layout][1] used by [`Box`]
     ///
     /// # Usage
     ///
     /// ```rust
     /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    /// use std::sync::atomic::AtomicPtr;
     ///
-    /// let cell = PtrCell::<[u8; 3]>::default();
+    /// let atomic = AtomicPtr::new(PtrCell::heap_leak(Some(0xFAA)));
+    /// let cell = unsafe { PtrCell::from_atomic(atomic) };
     ///
-    /// assert!(cell.is_empty(Relaxed))
+    /// assert_eq!(cell.take(Relaxed), Some(0xFAA))
     /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
     #[inline]
-    pub fn is_empty(&self, order: Semantics) -> bool {
-        self.get_ptr(order).is_null()
+    #[cfg(not(any(
+        feature = "portable-atomic",
+        feature = "critical-section",
+        feature = "shuttle",
+        all(target_arch = "wasm32", not(target_feature = "atomics"))
+    )))]
+    pub const unsafe fn from_atomic(atomic: core::sync::atomic::AtomicPtr<T>) -> Self {
+        Self {
+            value: atomic,
+            #[cfg(feature = "allocator_api")]
+            alloc: Global,
+            owns: PhantomData,
+        }
     }
 
-    /// Constructs a cell
+    /// Views an existing atomic pointer as a cell, without changing its layout
+    ///
+    /// Lets data structures built around an [`AtomicPtr`](core::sync::atomic::AtomicPtr) adopt
+    /// the cell's methods one by one. The view never drops the value, but values replaced or taken
+    /// out through it are reclaimed as if they had been [leaked](Self::heap_leak) by the cell
+    ///
+    /// Only available with the native atomics, like [`as_atomic_ptr`](Self::as_atomic_ptr), and
+    /// without the `allocator_api` feature, which makes the cell larger than the atomic pointer
+    ///
+    /// # Safety
+    ///
+    /// While the view is used, every pointer stored in `atomic` must be null or point to memory
+    /// that conforms to the [memory layout][1] used by [`Box`]. Values must not be freed in any
+    /// other way while they're reachable through the view
     ///
     /// # Usage
     ///
     /// ```rust
     /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    /// use std::sync::atomic::AtomicPtr;
     ///
-    /// let cell = PtrCell::new(Some(0xFAA));
+    /// struct Legacy {
+    ///     head: AtomicPtr<String>,
+    /// }
     ///
-    /// assert_eq!(cell.take(Relaxed), Some(0xFAA));
-    /// assert!(cell.is_empty(Relaxed))
+    /// let legacy = Legacy {
+    ///     head: AtomicPtr::new(std::ptr::null_mut()),
+    /// };
+    ///
+    /// let head = unsafe { PtrCell::from_atomic_ref(&legacy.head) };
+    /// head.set(Some("new".to_string()), Relaxed);
+    ///
+    /// assert_eq!(head.take(Relaxed).as_deref(), Some("new"));
+    /// assert!(legacy.head.into_inner().is_null())
     /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
     #[inline]
-    #[must_use]
-    pub fn new(slot: Option<T>) -> Self {
-        let ptr = Self::heap_leak(slot);
-
-        unsafe { Self::from_ptr(ptr) }
+    #[cfg(not(any(
+        feature = "portable-atomic",
+        feature = "critical-section",
+        feature = "shuttle",
+        feature = "allocator_api",
+        all(target_arch = "wasm32", not(target_feature = "atomics"))
+    )))]
+    pub const unsafe fn from_atomic_ref(atomic: &core::sync::atomic::AtomicPtr<T>) -> &Self {
+        // The cell is a transparent wrapper around the atomic pointer
+        unsafe { &*core::ptr::from_ref(atomic).cast::<Self>() }
     }
 
-    /// Constructs a cell that owns [leaked](Self::heap_leak) memory
+    /// Reclaims ownership of [leaked](Self::heap_leak) memory
     ///
     /// A null pointer represents [`None`]
     ///
@@ -509,79 +2363,184 @@ impl<T> PtrCell<T> {
     ///
     /// The memory must conform to the [memory layout][1] used by [`Box`]
     ///
+    /// Dereferencing `ptr` after this function has been called may cause undefined behavior
+    ///
+    /// # Panics
+    ///
+    /// With the `debug-track` feature, if `ptr` has already been reclaimed or wasn't leaked by this
+    /// crate
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::PtrCell;
+    ///
+    /// let ptr = PtrCell::heap_leak(Some(1155));
+    ///
+    /// assert_eq!(unsafe { PtrCell::heap_reclaim(ptr) }, Some(1155))
+    /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    #[inline]
+    pub unsafe fn heap_reclaim(ptr: *mut T) -> Option<T> {
+        #[cfg(feature = "debug-track")]
+        track::forget(ptr);
+
+        #[cfg(feature = "stats")]
+        stats::deallocated(ptr);
+
+        non_null(ptr).map(|ptr| *unsafe { Box::from_raw(ptr) })
+    }
+
+    /// Leaks a value to the heap
+    ///
+    /// [`None`] is represented by a null pointer. Zero-sized values are never allocated and are
+    /// represented by a dangling, non-null pointer instead
+    ///
+    /// The memory will conform to the [memory layout][1] used by [`Box`]
+    ///
     /// # Usage
     ///
     /// ```rust
+    /// use ptr_cell::PtrCell;
+    ///
+    /// let ptr = PtrCell::heap_leak(Some(1155));
+    ///
+    /// assert_eq!(unsafe { PtrCell::heap_reclaim(ptr) }, Some(1155))
+    /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    #[inline]
+    #[must_use]
+    pub fn heap_leak(slot: Option<T>) -> *mut T {
+        match slot {
+            Some(value) if core::mem::size_of::<T>() == 0 => {
+                let ptr = core::ptr::NonNull::<T>::dangling().as_ptr();
+                unsafe { ptr.write(value) };
+
+                ptr
+            }
+            Some(value) => {
+                let ptr = Box::into_raw(Box::new(value));
+
+                #[cfg(feature = "debug-track")]
+                track::record(ptr);
+
+                #[cfg(feature = "stats")]
+                stats::allocated(ptr);
+
+                ptr
+            }
+            None => core::ptr::null_mut(),
+        }
+    }
+
+    /// Leaks each of `values` to the heap, like [`heap_leak`](Self::heap_leak)
+    ///
+    /// Values are leaked as the iterator advances. Collect it to move all of the allocations to one
+    /// place, like the start of the program
+    ///
+    /// Each value still gets an allocation of its own, as every pointer must be reclaimable on its
+    /// own. To avoid allocating at runtime altogether, recycle the allocations with a [`Pool`]
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
     /// use ptr_cell::{PtrCell, Semantics::Relaxed};
     ///
-    /// let ptr = PtrCell::heap_leak(Some(0xFAA));
-    /// let cell = unsafe { PtrCell::from_ptr(ptr) };
+    /// let messages: Vec<_> = PtrCell::heap_leak_iter(["ping", "pong"]).collect();
+    /// let cell = PtrCell::default();
+    ///
+    /// for &message in &messages {
+    ///     unsafe { cell.set_ptr(message, Relaxed) };
+    ///     assert_eq!(cell.take_ptr(Relaxed), message);
+    /// }
     ///
-    /// assert_eq!(cell.take(Relaxed), Some(0xFAA));
-    /// assert!(cell.is_empty(Relaxed))
+    /// let messages = unsafe { PtrCell::heap_reclaim_iter(messages) };
+    /// assert!(messages.eq([Some("ping"), Some("pong")]))
     /// ```
-    ///
-    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
-    #[inline]
-    pub const unsafe fn from_ptr(ptr: *mut T) -> Self {
-        let value = core::sync::atomic::AtomicPtr::new(ptr);
-
-        Self { value }
+    pub fn heap_leak_iter(values: impl IntoIterator<Item = T>) -> impl Iterator<Item = *mut T> {
+        values.into_iter().map(|value| Self::heap_leak(Some(value)))
     }
 
-    /// Reclaims ownership of [leaked](Self::heap_leak) memory
+    /// Reclaims each of `ptrs`, like [`heap_reclaim`](Self::heap_reclaim)
     ///
-    /// A null pointer represents [`None`]
+    /// Pointers are reclaimed as the iterator advances. The ones that it doesn't get to are leaked
     ///
     /// # Safety
     ///
-    /// The memory must conform to the [memory layout][1] used by [`Box`]
-    ///
-    /// Dereferencing `ptr` after this function has been called may cause undefined behavior
+    /// Every pointer must meet the requirements of [`heap_reclaim`](Self::heap_reclaim) by the time
+    /// the iterator reaches it
     ///
     /// # Usage
     ///
     /// ```rust
     /// use ptr_cell::PtrCell;
     ///
-    /// let ptr = PtrCell::heap_leak(Some(1155));
+    /// let ptrs = [PtrCell::heap_leak(Some(1)), std::ptr::null_mut()];
     ///
-    /// assert_eq!(unsafe { PtrCell::heap_reclaim(ptr) }, Some(1155))
+    /// let values = unsafe { PtrCell::heap_reclaim_iter(ptrs) };
+    /// assert!(values.eq([Some(1), None]))
     /// ```
-    ///
-    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
-    #[inline]
-    pub unsafe fn heap_reclaim(ptr: *mut T) -> Option<T> {
-        non_null(ptr).map(|ptr| *unsafe { Box::from_raw(ptr) })
+    pub unsafe fn heap_reclaim_iter(
+        ptrs: impl IntoIterator<Item = *mut T>,
+    ) -> impl Iterator<Item = Option<T>> {
+        ptrs.into_iter()
+            .map(|ptr| unsafe { Self::heap_reclaim(ptr) })
     }
 
-    /// Leaks a value to the heap
+    /// Leaks a value to the heap, failing instead of aborting if memory can't be allocated
     ///
     /// [`None`] is represented by a null pointer
     ///
     /// The memory will conform to the [memory layout][1] used by [`Box`]
     ///
+    /// # Errors
+    ///
+    /// Returns [`AllocError`] if the allocation fails. The value is dropped in that case
+    ///
     /// # Usage
     ///
     /// ```rust
     /// use ptr_cell::PtrCell;
     ///
-    /// let ptr = PtrCell::heap_leak(Some(1155));
+    /// let ptr = PtrCell::try_heap_leak(Some(1155)).expect("Allocation should succeed");
     ///
     /// assert_eq!(unsafe { PtrCell::heap_reclaim(ptr) }, Some(1155))
     /// ```
     ///
     /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
-    #[inline]
-    #[must_use]
-    pub fn heap_leak(slot: Option<T>) -> *mut T {
-        match slot {
-            Some(value) => Box::into_raw(Box::new(value)),
-            None => core::ptr::null_mut(),
+    pub fn try_heap_leak(slot: Option<T>) -> Result<*mut T, AllocError> {
+        let Some(value) = slot else {
+            return Ok(core::ptr::null_mut());
+        };
+
+        let layout = core::alloc::Layout::new::<T>();
+
+        // Zero-sized values are never allocated, just like in `heap_leak`
+        let ptr: *mut T = if layout.size() == 0 {
+            core::ptr::NonNull::dangling().as_ptr()
+        } else {
+            unsafe { alloc::alloc::alloc(layout) }.cast()
+        };
+
+        if ptr.is_null() {
+            return Err(AllocError);
         }
+
+        unsafe { ptr.write(value) };
+
+        #[cfg(feature = "debug-track")]
+        track::record(ptr);
+
+        #[cfg(feature = "stats")]
+        stats::allocated(ptr);
+
+        Ok(ptr)
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<T> core::fmt::Debug for PtrCell<T> {
     fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
         formatter
@@ -591,14 +2550,19 @@ impl<T> core::fmt::Debug for PtrCell<T> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<T> Default for PtrCell<T> {
     /// Constructs an empty cell
     #[inline]
     fn default() -> Self {
-        Self::new(None)
+        Self::empty()
     }
 }
 
+#[cfg(all(
+    feature = "alloc",
+    not(any(feature = "allocator_api", feature = "may_dangle"))
+))]
 impl<T> Drop for PtrCell<T> {
     #[inline]
     fn drop(&mut self) {
@@ -608,6 +2572,58 @@ impl<T> Drop for PtrCell<T> {
     }
 }
 
+#[cfg(all(not(feature = "allocator_api"), feature = "may_dangle"))]
+unsafe impl<#[may_dangle] T> Drop for PtrCell<T> {
+    /// Drops the value without requiring borrows inside it to be alive, unless the value's own
+    /// drop code uses them
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// struct Scheduler<'a> {
+    ///     current: PtrCell<&'a mut Vec<u8>>,
+    /// }
+    ///
+    /// let scheduler;
+    /// let mut buffer = vec![0; 16];
+    ///
+    /// scheduler = Scheduler {
+    ///     current: PtrCell::from(&mut buffer),
+    /// };
+    ///
+    /// // `buffer` is dropped before `scheduler`
+    /// assert!(!scheduler.current.is_empty(Relaxed))
+    /// ```
+    ///
+    /// Values that do use the borrows when dropped are still rejected:
+    ///
+    /// ```rust,compile_fail
+    /// use ptr_cell::PtrCell;
+    ///
+    /// struct Printer<'a>(&'a str);
+    ///
+    /// impl Drop for Printer<'_> {
+    ///     fn drop(&mut self) {
+    ///         println!("{}", self.0)
+    ///     }
+    /// }
+    ///
+    /// let cell;
+    /// let text = String::from("Dropped");
+    ///
+    /// cell = PtrCell::from(Printer(&text));
+    /// ```
+    #[inline]
+    fn drop(&mut self) {
+        let ptr = *self.value.get_mut();
+
+        unsafe { Self::heap_reclaim(ptr) };
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl<T> From<T> for PtrCell<T> {
     #[inline]
     fn from(value: T) -> Self {
@@ -615,9 +2631,75 @@ impl<T> From<T> for PtrCell<T> {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<T> From<Option<T>> for PtrCell<T> {
+    /// Constructs a cell from an optional value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell: PtrCell<char> = Some('a').into();
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some('a'))
+    /// ```
+    #[inline]
+    fn from(slot: Option<T>) -> Self {
+        Self::new(slot)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> From<Box<T>> for PtrCell<T> {
+    /// Constructs a cell that takes over the allocation of `boxed` without moving its value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let boxed = Box::new([0xAB_u8; 4096]);
+    /// let address: *const [u8; 4096] = &*boxed;
+    ///
+    /// let cell: PtrCell<[u8; 4096]> = boxed.into();
+    ///
+    /// assert_eq!(cell.get_ptr(Relaxed).cast_const(), address)
+    /// ```
+    #[inline]
+    fn from(boxed: Box<T>) -> Self {
+        let ptr = Box::into_raw(boxed);
+
+        #[cfg(feature = "debug-track")]
+        track::record(ptr);
+
+        #[cfg(feature = "stats")]
+        stats::allocated(ptr);
+
+        unsafe { Self::from_ptr(ptr) }
+    }
+}
+
+/// Moves leaked memory back into a box without moving the value
+///
+/// # Safety
+///
+/// Same as [`PtrCell::heap_reclaim`]
+#[cfg(feature = "alloc")]
+unsafe fn into_box<T>(ptr: *mut T) -> Option<Box<T>> {
+    #[cfg(feature = "debug-track")]
+    track::forget(ptr);
+
+    #[cfg(feature = "stats")]
+    stats::deallocated(ptr);
+
+    non_null(ptr).map(|ptr| unsafe { Box::from_raw(ptr) })
+}
+
 /// Returns `ptr` if it's non-null
+#[cfg(feature = "alloc")]
 #[inline]
-fn non_null<T>(ptr: *mut T) -> Option<*mut T> {
+const fn non_null<T>(ptr: *mut T) -> Option<*mut T> {
     if ptr.is_null() {
         None
     } else {
@@ -625,12 +2707,27 @@ fn non_null<T>(ptr: *mut T) -> Option<*mut T> {
     }
 }
 
+/// Strengthens `ordering` to acquire, which reading through a pointer loaded with it requires
+#[cfg(feature = "alloc")]
+#[inline]
+const fn acquiring(ordering: Ordering) -> Ordering {
+    match ordering {
+        Ordering::Relaxed => Ordering::Acquire,
+        Ordering::Release => Ordering::AcqRel,
+        ordering => ordering,
+    }
+}
+
 /// Memory ordering semantics for atomic operations
 ///
-/// Each variant represents a group of compatible [orderings](Ordering). They determine how value
-/// updates are synchronized between threads
+/// Each preset variant represents a group of compatible [orderings](Ordering), while
+/// [`Custom`](Self::Custom) semantics spell them out explicitly. They determine how value updates
+/// are synchronized between threads
+///
+/// Semantics are ordered from the weakest preset to the strongest one. Custom semantics come after
+/// all presets
 #[non_exhaustive]
-#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
 pub enum Semantics {
     /// [`Relaxed`](Ordering::Relaxed) semantics
     ///
@@ -639,6 +2736,24 @@ pub enum Semantics {
     /// Set this when using a value in only one thread
     Relaxed,
 
+    /// [`Acquire`](Ordering::Acquire) semantics for reads, [`Relaxed`](Ordering::Relaxed) for
+    /// writes
+    ///
+    /// The consumer's half of [`Coupled`](Self::Coupled) semantics
+    ///
+    /// Set this on the receiving side of a single-direction handoff, where the other side uses
+    /// [`ReleaseOnly`](Self::ReleaseOnly) semantics
+    AcquireOnly,
+
+    /// [`Release`](Ordering::Release) semantics for writes, [`Relaxed`](Ordering::Relaxed) for
+    /// reads
+    ///
+    /// The producer's half of [`Coupled`](Self::Coupled) semantics
+    ///
+    /// Set this on the sending side of a single-direction handoff, where the other side uses
+    /// [`AcquireOnly`](Self::AcquireOnly) semantics
+    ReleaseOnly,
+
     /// [`Release`](Ordering::Release) - [`Acquire`](Ordering::Acquire) coupling semantics
     ///
     /// Mild synchronization constraints and fair performance
@@ -657,13 +2772,262 @@ pub enum Semantics {
     ///
     /// All memory operations will appear to be executed in a single, total order
     Ordered,
+
+    /// Explicit orderings for each type of operations
+    ///
+    /// Meant for protocols that the presets don't cover well, like [`Acquire`](Ordering::Acquire)
+    /// reads paired with [`Relaxed`](Ordering::Relaxed) writes. Prefer constructing it with
+    /// [`Semantics::custom`], which checks the orderings right away
+    ///
+    /// Operations with an ordering that's invalid for them will panic
+    Custom {
+        /// Ordering of read operations. Can't be [`Release`](Ordering::Release) or
+        /// [`AcqRel`](Ordering::AcqRel)
+        load: Ordering,
+
+        /// Ordering of write operations. Can't be [`Acquire`](Ordering::Acquire) or
+        /// [`AcqRel`](Ordering::AcqRel)
+        store: Ordering,
+
+        /// Ordering of read-write operations
+        swap: Ordering,
+    },
+}
+
+impl Semantics {
+    /// Every preset, from the weakest to the strongest
+    ///
+    /// Meant for tests that should cover all of the presets. [`Custom`](Self::Custom) semantics
+    /// aren't included, as there are too many of them
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::Semantics;
+    ///
+    /// for order in Semantics::ALL {
+    ///     assert_eq!(order.to_string().parse(), Ok(order))
+    /// }
+    /// ```
+    pub const ALL: [Self; 5] = [
+        Self::Relaxed,
+        Self::AcquireOnly,
+        Self::ReleaseOnly,
+        Self::Coupled,
+        Self::Ordered,
+    ];
+
+    /// Constructs [`Custom`](Self::Custom) semantics from the orderings of each type of operations
+    ///
+    /// # Panics
+    ///
+    /// If `load` is [`Release`](Ordering::Release) or [`AcqRel`](Ordering::AcqRel), or if `store`
+    /// is [`Acquire`](Ordering::Acquire) or [`AcqRel`](Ordering::AcqRel)
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::Semantics;
+    /// use std::sync::atomic::Ordering;
+    ///
+    /// let consumer = Semantics::custom(Ordering::Acquire, Ordering::Relaxed, Ordering::Acquire);
+    ///
+    /// assert_eq!(consumer.read(), Ordering::Acquire);
+    /// assert_eq!(consumer.read_write(), Ordering::Acquire)
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn custom(load: Ordering, store: Ordering, swap: Ordering) -> Self {
+        let custom = Self::Custom { load, store, swap };
+
+        // Checks the orderings
+        let _ = (custom.read(), custom.write());
+
+        custom
+    }
+
+    /// Issues a memory [fence](core::sync::atomic::fence) with the
+    /// [read-write](Self::read_write) ordering of these semantics
+    ///
+    /// Nothing is issued if the ordering is [`Relaxed`](Ordering::Relaxed), as there's no such
+    /// thing as a relaxed fence
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::Semantics::Coupled;
+    /// use std::sync::atomic::{AtomicU16, Ordering::Relaxed};
+    ///
+    /// let counter = AtomicU16::new(0xFAA);
+    ///
+    /// let value = counter.load(Relaxed);
+    /// Coupled.fence();
+    ///
+    /// assert_eq!(value, 0xFAA)
+    /// ```
+    #[inline]
+    pub fn fence(&self) {
+        let ordering = self.read_write();
+
+        if ordering != Ordering::Relaxed {
+            fence(ordering);
+        }
+    }
+
+    /// Returns the lowercase name of the semantics
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Relaxed => "relaxed",
+            Self::AcquireOnly => "acquire-only",
+            Self::ReleaseOnly => "release-only",
+            Self::Coupled => "coupled",
+            Self::Ordered => "ordered",
+            Self::Custom { .. } => "custom",
+        }
+    }
+
+    /// Ranks the semantics for comparisons
+    const fn rank(self) -> (u8, [u8; 3]) {
+        /// Ranks an ordering by its strength
+        const fn strength(ordering: Ordering) -> u8 {
+            match ordering {
+                Ordering::Relaxed => 0,
+                Ordering::Release => 1,
+                Ordering::Acquire => 2,
+                Ordering::AcqRel => 3,
+                _ => 4,
+            }
+        }
+
+        match self {
+            Self::Relaxed => (0, [0; 3]),
+            Self::AcquireOnly => (1, [0; 3]),
+            Self::ReleaseOnly => (2, [0; 3]),
+            Self::Coupled => (3, [0; 3]),
+            Self::Ordered => (4, [0; 3]),
+            Self::Custom { load, store, swap } => {
+                (5, [strength(load), strength(store), strength(swap)])
+            }
+        }
+    }
+}
+
+// Fails to compile if a preset is missing from `Semantics::ALL` or listed out of order. Presets are
+// ranked right before custom semantics, so a new one can't be ranked without bumping them
+const _: () = {
+    let custom = Semantics::Custom {
+        load: Ordering::Relaxed,
+        store: Ordering::Relaxed,
+        swap: Ordering::Relaxed,
+    };
+
+    assert!(custom.rank().0 as usize == Semantics::ALL.len());
+
+    let mut index = 0;
+
+    while index < Semantics::ALL.len() {
+        assert!(Semantics::ALL[index].rank().0 as usize == index);
+        index += 1;
+    }
+};
+
+impl PartialOrd for Semantics {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Semantics {
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl core::fmt::Display for Semantics {
+    /// Writes the name of a preset in lowercase, like `coupled` or `acquire-only`
+    ///
+    /// Custom semantics are written with their orderings and can't be parsed back
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::Semantics;
+    ///
+    /// assert_eq!(Semantics::ReleaseOnly.to_string(), "release-only");
+    /// assert_eq!("ordered".parse::<Semantics>().map(|order| order.to_string()), Ok("ordered".into()))
+    /// ```
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Custom { load, store, swap } => {
+                write!(
+                    formatter,
+                    "custom(load: {load:?}, store: {store:?}, swap: {swap:?})"
+                )
+            }
+            preset => formatter.write_str(preset.name()),
+        }
+    }
+}
+
+impl core::str::FromStr for Semantics {
+    type Err = ParseSemanticsError;
+
+    /// Parses the name of a preset, ignoring ASCII case
+    ///
+    /// Accepts `relaxed`, `acquire-only`, `release-only`, `coupled`, and `ordered`
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::Semantics;
+    ///
+    /// assert_eq!("Coupled".parse(), Ok(Semantics::Coupled));
+    /// assert_eq!("acquire-only".parse(), Ok(Semantics::AcquireOnly))
+    /// ```
+    fn from_str(name: &str) -> Result<Self, ParseSemanticsError> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.name().eq_ignore_ascii_case(name))
+            .ok_or(ParseSemanticsError)
+    }
+}
+
+impl TryFrom<Ordering> for Semantics {
+    type Error = OrderingError;
+
+    /// Picks the preset whose [read-write](Semantics::read_write) ordering is `ordering`
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::Semantics;
+    /// use std::sync::atomic::Ordering;
+    ///
+    /// assert_eq!(Semantics::try_from(Ordering::AcqRel), Ok(Semantics::Coupled));
+    /// assert_eq!(Semantics::try_from(Ordering::Acquire), Ok(Semantics::AcquireOnly))
+    /// ```
+    fn try_from(ordering: Ordering) -> Result<Self, OrderingError> {
+        match ordering {
+            Ordering::Relaxed => Ok(Self::Relaxed),
+            Ordering::Acquire => Ok(Self::AcquireOnly),
+            Ordering::Release => Ok(Self::ReleaseOnly),
+            Ordering::AcqRel => Ok(Self::Coupled),
+            Ordering::SeqCst => Ok(Self::Ordered),
+            _ => Err(OrderingError),
+        }
+    }
 }
 
 /// Implements a method on [`Semantics`] that returns the appropriate [`Ordering`] for a type of
 /// operations
+///
+/// The orderings that are invalid for the type of operations cause a panic when set in
+/// [`Custom`](Semantics::Custom) semantics
 macro_rules! operation {
-    ($name:ident with $coupled:path:
-        { $($overview:tt)* }, { $($returns:tt)* }, { $($assert:tt)* }
+    ($name:ident with $coupled:path, $acquire:path, $release:path, $field:ident $(except $invalid:pat)?,
+        { $($overview:tt)* }, { $($returns:tt)* }, { $($panics:tt)* }, { $($assert:tt)* }
     $(,)? ) => {
         impl Semantics {
             $($overview)*
@@ -672,6 +3036,10 @@ macro_rules! operation {
             /// - [`Relaxed`](Ordering::Relaxed) for [`Relaxed`](Semantics::Relaxed) semantics
             $($returns)*
             /// - [`SeqCst`](Ordering::SeqCst) for [`Ordered`](Semantics::Ordered) semantics
+            #[doc = concat!(
+                "- The `", stringify!($field), "` ordering for [`Custom`](Semantics::Custom) semantics"
+            )]
+            $($panics)*
             ///
             /// # Usage
             ///
@@ -685,8 +3053,18 @@ macro_rules! operation {
             pub const fn $name(&self) -> Ordering {
                 match self {
                     Self::Relaxed => Ordering::Relaxed,
+                    Self::AcquireOnly => $acquire,
+                    Self::ReleaseOnly => $release,
                     Self::Coupled => $coupled,
                     Self::Ordered => Ordering::SeqCst,
+                    Self::Custom { $field, .. } => {
+                        $(assert!(
+                            !matches!($field, $invalid),
+                            concat!("invalid `", stringify!($field), "` ordering"),
+                        );)?
+
+                        *$field
+                    }
                 }
             }
         }
@@ -696,26 +3074,118 @@ macro_rules! operation {
 // Asserts are missing a space on purpose. All whitespace after `///` seems to be carried over to
 // the example
 
-operation!(read_write with Ordering::AcqRel: {
+operation!(read_write with Ordering::AcqRel, Ordering::Acquire, Ordering::Release, swap, {
     /// Returns the memory ordering for read-write operations with these semantics
 }, {
+    /// - [`Acquire`](Ordering::Acquire) for [`AcquireOnly`](Semantics::AcquireOnly) semantics
+    /// - [`Release`](Ordering::Release) for [`ReleaseOnly`](Semantics::ReleaseOnly) semantics
     /// - [`AcqRel`](Ordering::AcqRel) for [`Coupled`](Semantics::Coupled) semantics
-}, {
+}, {}, {
     ///assert_eq!(Coupled.read_write(), Ordering::AcqRel)
 });
 
-operation!(write with Ordering::Release: {
+operation!(write with Ordering::Release, Ordering::Relaxed, Ordering::Release, store except Ordering::Acquire | Ordering::AcqRel, {
     /// Returns the memory ordering for write operations with these semantics
 }, {
+    /// - [`Relaxed`](Ordering::Relaxed) for [`AcquireOnly`](Semantics::AcquireOnly) semantics
+    /// - [`Release`](Ordering::Release) for [`ReleaseOnly`](Semantics::ReleaseOnly) semantics
     /// - [`Release`](Ordering::Release) for [`Coupled`](Semantics::Coupled) semantics
+}, {
+    ///
+    /// # Panics
+    ///
+    /// If the `store` ordering of [`Custom`](Semantics::Custom) semantics is
+    /// [`Acquire`](Ordering::Acquire) or [`AcqRel`](Ordering::AcqRel)
 }, {
     ///assert_eq!(Coupled.write(), Ordering::Release)
 });
 
-operation!(read with Ordering::Acquire: {
+operation!(read with Ordering::Acquire, Ordering::Acquire, Ordering::Relaxed, load except Ordering::Release | Ordering::AcqRel, {
     /// Returns the memory ordering for read operations with these semantics
 }, {
+    /// - [`Acquire`](Ordering::Acquire) for [`AcquireOnly`](Semantics::AcquireOnly) semantics
+    /// - [`Relaxed`](Ordering::Relaxed) for [`ReleaseOnly`](Semantics::ReleaseOnly) semantics
     /// - [`Acquire`](Ordering::Acquire) for [`Coupled`](Semantics::Coupled) semantics
+}, {
+    ///
+    /// # Panics
+    ///
+    /// If the `load` ordering of [`Custom`](Semantics::Custom) semantics is
+    /// [`Release`](Ordering::Release) or [`AcqRel`](Ordering::AcqRel)
 }, {
     ///assert_eq!(Coupled.read(), Ordering::Acquire)
 });
+
+/// Semantics of a compare-exchange operation
+///
+/// Unlike the other operations, a compare-exchange can fail. When it does, it only reads the value,
+/// so its semantics on failure can be chosen separately, like in
+/// [`compare_exchange`](core::sync::atomic::AtomicPtr::compare_exchange)
+///
+/// Plain [`Semantics`] convert into an exchange that uses them on both success and failure
+///
+/// # Usage
+///
+#[doc = alloc_doctest!()]
+/// use ptr_cell::{node::Node, Exchange, PtrCell, Semantics};
+///
+/// let list = PtrCell::default();
+/// let order = Exchange::new(Semantics::Coupled, Semantics::Relaxed);
+///
+/// list.map_owner(|next| Node { value: 1, next }, order);
+///
+/// assert!(!list.is_empty(Semantics::Relaxed))
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Exchange {
+    /// Semantics of the operation if the value is replaced
+    pub success: Semantics,
+
+    /// Semantics of the operation if the value is only read
+    pub failure: Semantics,
+}
+
+impl Exchange {
+    /// Constructs an exchange from its semantics on success and on failure
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::{Exchange, Semantics::{Coupled, Relaxed}};
+    /// use std::sync::atomic::Ordering;
+    ///
+    /// let order = Exchange::new(Coupled, Relaxed);
+    ///
+    /// assert_eq!(order.success(), Ordering::AcqRel);
+    /// assert_eq!(order.failure(), Ordering::Relaxed)
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(success: Semantics, failure: Semantics) -> Self {
+        Self { success, failure }
+    }
+
+    /// Returns the memory ordering for a successful exchange
+    ///
+    /// This is the [read-write](Semantics::read_write) ordering of the success semantics
+    #[inline]
+    pub const fn success(&self) -> Ordering {
+        self.success.read_write()
+    }
+
+    /// Returns the memory ordering for a failed exchange
+    ///
+    /// This is the [read](Semantics::read) ordering of the failure semantics
+    #[inline]
+    pub const fn failure(&self) -> Ordering {
+        self.failure.read()
+    }
+}
+
+impl From<Semantics> for Exchange {
+    /// Uses the same semantics on success and on failure
+    #[inline]
+    fn from(order: Semantics) -> Self {
+        Self::new(order, order)
+    }
+}
@@ -0,0 +1,161 @@
+//! Thread-safe cell that combines values instead of replacing them
+
+use crate::{PtrCell, Semantics};
+
+/// Thread-safe cell that merges every pushed value into its current one
+///
+/// Where [`replace`](PtrCell::replace) would drop a value that hasn't been taken out yet, this
+/// cell folds new values into it with a user-supplied function, like summing deltas or uniting
+/// sets. Nothing is lost: every pushed value ends up either in the cell or in a value taken out of
+/// it
+///
+/// Values are merged in no particular order, so the function should be commutative and
+/// associative. A push never waits for other threads. If it finds the cell occupied, it takes the
+/// value out, merges it into its own, and retries the insertion
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{MergeCell, Semantics::Coupled};
+///
+/// let requests = MergeCell::new(|total: &mut u64, count| *total += count);
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             for _ in 0..100 {
+///                 requests.push(1, Coupled);
+///             }
+///         });
+///     }
+/// });
+///
+/// assert_eq!(requests.take(Coupled), Some(400))
+/// ```
+pub struct MergeCell<T, F> {
+    /// The merged value
+    cell: PtrCell<T>,
+
+    /// Merges the second value into the first
+    merge: F,
+}
+
+impl<T, F> MergeCell<T, F>
+where
+    F: Fn(&mut T, T),
+{
+    /// Inserts a value into the cell, merging it with the value that's already there
+    ///
+    /// If the merge function panics, the value being pushed is dropped, along with whatever was
+    /// merged into it
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{MergeCell, Semantics::Relaxed};
+    /// use std::collections::BTreeSet;
+    ///
+    /// let seen = MergeCell::new(|seen: &mut BTreeSet<_>, new| seen.extend(new));
+    ///
+    /// seen.push(BTreeSet::from(["a", "b"]), Relaxed);
+    /// seen.push(BTreeSet::from(["b", "c"]), Relaxed);
+    ///
+    /// let seen = seen.take(Relaxed).unwrap_or_default();
+    /// assert!(seen.into_iter().eq(["a", "b", "c"]))
+    /// ```
+    pub fn push(&self, value: T, order: Semantics) {
+        let mut pending = PtrCell::new(Some(value));
+
+        loop {
+            let inserting = self.cell.value.compare_exchange(
+                core::ptr::null_mut(),
+                pending.get_ptr(Semantics::Relaxed),
+                order.read_write(),
+                order.read(),
+            );
+
+            if inserting.is_ok() {
+                let _ = pending.leak();
+                return;
+            }
+
+            // The cell may have been emptied in the meantime, so the insertion is simply retried
+            if let Some(current) = self.cell.take(order) {
+                if let Some(pending) = pending.get_mut() {
+                    (self.merge)(pending, current);
+                }
+            }
+        }
+    }
+
+    /// Takes out the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{MergeCell, Semantics::Relaxed};
+    ///
+    /// let cell = MergeCell::new(|a: &mut i32, b| *a += b);
+    ///
+    /// cell.push(2, Relaxed);
+    /// cell.push(-5, Relaxed);
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(-3));
+    /// assert_eq!(cell.take(Relaxed), None)
+    /// ```
+    #[inline]
+    pub fn take(&self, order: Semantics) -> Option<T> {
+        self.cell.take(order)
+    }
+
+    /// Determines whether the cell is empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{MergeCell, Semantics::Relaxed};
+    ///
+    /// let cell = MergeCell::new(|a: &mut u8, b| *a |= b);
+    ///
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_empty(&self, order: Semantics) -> bool {
+        self.cell.is_empty(order)
+    }
+
+    /// Constructs an empty cell that merges values with `merge`
+    ///
+    /// `merge(current, new)` folds `new` into `current`
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{MergeCell, Semantics::Relaxed};
+    ///
+    /// static ERRORS: MergeCell<Vec<u16>, fn(&mut Vec<u16>, Vec<u16>)> =
+    ///     MergeCell::new(|errors, new| errors.extend(new));
+    ///
+    /// ERRORS.push(vec![408], Relaxed);
+    /// ERRORS.push(vec![503], Relaxed);
+    ///
+    /// assert_eq!(ERRORS.take(Relaxed).map(|errors| errors.len()), Some(2))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(merge: F) -> Self {
+        Self {
+            cell: PtrCell::empty(),
+            merge,
+        }
+    }
+}
+
+impl<T, F> core::fmt::Debug for MergeCell<T, F> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("MergeCell")
+            .field("cell", &self.cell)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,426 @@
+//! Reference model of the cell for differential testing, enabled by the `test-util` feature
+//!
+//! [`ModelCell`] implements the cell's operations on top of a [`Mutex`], where each of them is
+//! trivially correct. [`replay`] runs a log of operations against a cell under test and a fresh
+//! model side by side, and reports the first operation whose outcomes differ. Any type that
+//! implements [`CellApi`] can be tested this way, which includes wrappers around [`PtrCell`]
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ptr_cell::{model::{self, Op}, PtrCell, Semantics::Coupled};
+//!
+//! let cell = PtrCell::default();
+//! let log = [Op::Set(Some(1)), Op::Replace(Some(2)), Op::IsEmpty, Op::Take, Op::Take];
+//!
+//! assert_eq!(model::replay(&cell, log, Coupled), Ok(()))
+//! ```
+
+use crate::{PtrCell, Semantics};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Operations shared by the cell, its reference model, and the wrappers to be tested against it
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::{model::{self, CellApi, Op}, PtrCell, Semantics};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// /// Cell that counts the values taken out of it
+/// #[derive(Default)]
+/// struct Counting {
+///     cell: PtrCell<u8>,
+///     taken: AtomicUsize,
+/// }
+///
+/// impl CellApi<u8> for Counting {
+///     fn take(&self, order: Semantics) -> Option<u8> {
+///         let value = self.cell.take(order);
+///         self.taken.fetch_add(usize::from(value.is_some()), Ordering::Relaxed);
+///         value
+///     }
+///
+///     fn set(&self, slot: Option<u8>, order: Semantics) {
+///         self.cell.set(slot, order)
+///     }
+///
+///     fn replace(&self, slot: Option<u8>, order: Semantics) -> Option<u8> {
+///         self.cell.replace(slot, order)
+///     }
+///
+///     fn is_empty(&self, order: Semantics) -> bool {
+///         self.cell.is_empty(order)
+///     }
+/// }
+///
+/// let counting = Counting::default();
+/// let log = [Op::Set(Some(3)), Op::Take, Op::Take];
+///
+/// assert_eq!(model::replay(&counting, log, Semantics::Relaxed), Ok(()));
+/// assert_eq!(counting.taken.into_inner(), 1)
+/// ```
+pub trait CellApi<T> {
+    /// Takes out the cell's value
+    fn take(&self, order: Semantics) -> Option<T>;
+
+    /// Inserts a value into the cell, dropping the previous one
+    fn set(&self, slot: Option<T>, order: Semantics);
+
+    /// Inserts a value into the cell and returns the previous one
+    fn replace(&self, slot: Option<T>, order: Semantics) -> Option<T>;
+
+    /// Returns whether the cell is empty
+    fn is_empty(&self, order: Semantics) -> bool;
+}
+
+impl<T> CellApi<T> for PtrCell<T> {
+    #[inline]
+    fn take(&self, order: Semantics) -> Option<T> {
+        Self::take(self, order)
+    }
+
+    #[inline]
+    fn set(&self, slot: Option<T>, order: Semantics) {
+        Self::set(self, slot, order);
+    }
+
+    #[inline]
+    fn replace(&self, slot: Option<T>, order: Semantics) -> Option<T> {
+        Self::replace(self, slot, order)
+    }
+
+    #[inline]
+    fn is_empty(&self, order: Semantics) -> bool {
+        Self::is_empty(self, order)
+    }
+}
+
+/// Reference implementation of the cell built on a [`Mutex`]
+///
+/// Semantics are accepted for compatibility, but ignored. The mutex puts every operation in a
+/// single total order, which is at least as strong as any of them
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::{model::ModelCell, Semantics::Relaxed};
+///
+/// let model = ModelCell::new(Some("reference"));
+///
+/// assert_eq!(model.replace(None, Relaxed), Some("reference"));
+/// assert!(model.is_empty(Relaxed))
+/// ```
+#[derive(Debug)]
+pub struct ModelCell<T> {
+    /// The model's value
+    slot: Mutex<Option<T>>,
+}
+
+impl<T> ModelCell<T> {
+    /// Constructs a model with a value
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::{model::ModelCell, Semantics::Relaxed};
+    ///
+    /// let model = ModelCell::new(Some(0.5));
+    ///
+    /// assert_eq!(model.take(Relaxed), Some(0.5))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(slot: Option<T>) -> Self {
+        Self {
+            slot: Mutex::new(slot),
+        }
+    }
+
+    /// Takes out the model's value
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::{model::ModelCell, Semantics::Relaxed};
+    ///
+    /// let model = ModelCell::new(Some('m'));
+    ///
+    /// assert_eq!(model.take(Relaxed), Some('m'));
+    /// assert_eq!(model.take(Relaxed), None)
+    /// ```
+    #[inline]
+    pub fn take(&self, _order: Semantics) -> Option<T> {
+        self.lock().take()
+    }
+
+    /// Inserts a value into the model, dropping the previous one
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::{model::ModelCell, Semantics::Relaxed};
+    ///
+    /// let model = ModelCell::default();
+    /// model.set(Some(8), Relaxed);
+    ///
+    /// assert_eq!(model.into_inner(), Some(8))
+    /// ```
+    #[inline]
+    pub fn set(&self, slot: Option<T>, _order: Semantics) {
+        *self.lock() = slot;
+    }
+
+    /// Inserts a value into the model and returns the previous one
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::{model::ModelCell, Semantics::Relaxed};
+    ///
+    /// let model = ModelCell::new(Some(1));
+    ///
+    /// assert_eq!(model.replace(Some(2), Relaxed), Some(1));
+    /// assert_eq!(model.take(Relaxed), Some(2))
+    /// ```
+    #[inline]
+    pub fn replace(&self, slot: Option<T>, _order: Semantics) -> Option<T> {
+        core::mem::replace(&mut *self.lock(), slot)
+    }
+
+    /// Returns whether the model is empty
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::{model::ModelCell, Semantics::Relaxed};
+    ///
+    /// assert!(ModelCell::<u8>::default().is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_empty(&self, _order: Semantics) -> bool {
+        self.lock().is_none()
+    }
+
+    /// Consumes the model, returning its value
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::model::ModelCell;
+    ///
+    /// assert_eq!(ModelCell::new(Some([1, 2])).into_inner(), Some([1, 2]))
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> Option<T> {
+        self.slot
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the model's value. A panic during an earlier operation can't leave the value in an
+    /// invalid state, so poisoning is ignored
+    fn lock(&self) -> MutexGuard<'_, Option<T>> {
+        self.slot.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Default for ModelCell<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl<T> CellApi<T> for ModelCell<T> {
+    #[inline]
+    fn take(&self, order: Semantics) -> Option<T> {
+        Self::take(self, order)
+    }
+
+    #[inline]
+    fn set(&self, slot: Option<T>, order: Semantics) {
+        Self::set(self, slot, order);
+    }
+
+    #[inline]
+    fn replace(&self, slot: Option<T>, order: Semantics) -> Option<T> {
+        Self::replace(self, slot, order)
+    }
+
+    #[inline]
+    fn is_empty(&self, order: Semantics) -> bool {
+        Self::is_empty(self, order)
+    }
+}
+
+/// Entry of an operation log
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum Op<T> {
+    /// [`CellApi::take`]
+    Take,
+
+    /// [`CellApi::set`] with a value
+    Set(Option<T>),
+
+    /// [`CellApi::replace`] with a value
+    Replace(Option<T>),
+
+    /// [`CellApi::is_empty`]
+    IsEmpty,
+}
+
+impl<T> Op<T> {
+    /// Performs the operation on a cell and returns its outcome
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::{model::{Op, Outcome}, PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::from(5);
+    ///
+    /// assert_eq!(Op::Replace(Some(6)).apply(&cell, Relaxed), Outcome::Value(Some(5)));
+    /// assert_eq!(Op::IsEmpty.apply(&cell, Relaxed), Outcome::Empty(false))
+    /// ```
+    pub fn apply(self, cell: &(impl CellApi<T> + ?Sized), order: Semantics) -> Outcome<T> {
+        match self {
+            Self::Take => Outcome::Value(cell.take(order)),
+            Self::Set(slot) => {
+                cell.set(slot, order);
+                Outcome::Unit
+            }
+            Self::Replace(slot) => Outcome::Value(cell.replace(slot, order)),
+            Self::IsEmpty => Outcome::Empty(cell.is_empty(order)),
+        }
+    }
+}
+
+/// Outcome of an [`Op`]
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum Outcome<T> {
+    /// The operation doesn't return anything
+    Unit,
+
+    /// The value that was taken out or replaced
+    Value(Option<T>),
+
+    /// Whether the cell was empty
+    Empty(bool),
+}
+
+/// Error returned when a cell under test and the model disagree
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::{model::{self, CellApi, Op, Outcome}, PtrCell, Semantics};
+///
+/// /// Cell that forgets to hand back replaced values
+/// #[derive(Default)]
+/// struct Forgetful(PtrCell<u8>);
+///
+/// impl CellApi<u8> for Forgetful {
+///     fn take(&self, order: Semantics) -> Option<u8> {
+///         self.0.take(order)
+///     }
+///
+///     fn set(&self, slot: Option<u8>, order: Semantics) {
+///         self.0.set(slot, order)
+///     }
+///
+///     fn replace(&self, slot: Option<u8>, order: Semantics) -> Option<u8> {
+///         self.0.set(slot, order);
+///         None
+///     }
+///
+///     fn is_empty(&self, order: Semantics) -> bool {
+///         self.0.is_empty(order)
+///     }
+/// }
+///
+/// let log = [Op::Set(Some(1)), Op::Replace(Some(2))];
+/// let divergence = model::replay(&Forgetful::default(), log, Semantics::Relaxed)
+///     .expect_err("The replaced value should've been missed");
+///
+/// assert_eq!(divergence.index, 1);
+/// assert_eq!(divergence.expected, Outcome::Value(Some(1)));
+/// assert_eq!(divergence.actual, Outcome::Value(None))
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct Divergence<T> {
+    /// The position of the operation in the log
+    pub index: usize,
+
+    /// The operation
+    pub op: Op<T>,
+
+    /// What the model returned
+    pub expected: Outcome<T>,
+
+    /// What the cell under test returned
+    pub actual: Outcome<T>,
+}
+
+impl<T: core::fmt::Debug> core::fmt::Display for Divergence<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            formatter,
+            "operation {} ({:?}) returned {:?} instead of {:?}",
+            self.index, self.op, self.actual, self.expected
+        )
+    }
+}
+
+impl<T: core::fmt::Debug> core::error::Error for Divergence<T> {}
+
+/// Replays a log of operations against `cell` and a fresh model, comparing their outcomes
+///
+/// `cell` must be empty at the start, like the model. The replay stops at the first operation
+/// whose outcomes differ
+///
+/// # Errors
+///
+/// Returns a [`Divergence`] that describes the first differing operation
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::{model::{self, Op}, PtrCell, Semantics::Relaxed};
+///
+/// let cell = PtrCell::default();
+/// let log = (0..100).map(|value| match value % 3 {
+///     0 => Op::Set(Some(value)),
+///     1 => Op::Replace(None),
+///     _ => Op::Take,
+/// });
+///
+/// assert_eq!(model::replay(&cell, log, Relaxed), Ok(()))
+/// ```
+pub fn replay<T, C>(
+    cell: &C,
+    log: impl IntoIterator<Item = Op<T>>,
+    order: Semantics,
+) -> Result<(), Divergence<T>>
+where
+    T: Clone + PartialEq,
+    C: CellApi<T> + ?Sized,
+{
+    let model = ModelCell::default();
+
+    for (index, op) in log.into_iter().enumerate() {
+        let expected = op.clone().apply(&model, order);
+        let actual = op.clone().apply(cell, order);
+
+        if expected != actual {
+            return Err(Divergence {
+                index,
+                op,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    Ok(())
+}
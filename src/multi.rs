@@ -0,0 +1,172 @@
+//! Thread-safe cell with room for several values
+
+use crate::{Exchange, PtrCell, Semantics};
+
+/// Thread-safe cell that holds up to `N` values at once
+///
+/// Where a [`PtrCell`] drops its value when another one is inserted before it's taken out, this
+/// cell keeps inserting into vacant slots until all `N` are occupied, so short bursts of
+/// producers don't lose data. Values are taken out in no particular order
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{MultiCell, Semantics::Coupled};
+///
+/// let events: MultiCell<&str, 2> = MultiCell::new();
+///
+/// assert_eq!(events.set("connected", Coupled), Ok(()));
+/// assert_eq!(events.set("ready", Coupled), Ok(()));
+/// assert_eq!(events.set("overflow", Coupled), Err("overflow"));
+///
+/// assert!(events.take_any(Coupled).is_some());
+/// assert_eq!(events.len(Coupled), 1)
+/// ```
+pub struct MultiCell<T, const N: usize> {
+    /// The slots. Empty ones are free for the next value
+    slots: [PtrCell<T>; N],
+}
+
+impl<T, const N: usize> MultiCell<T, N> {
+    /// Slot without a value
+    #[allow(clippy::declare_interior_mutable_const)]
+    const VACANT: PtrCell<T> = PtrCell::empty();
+
+    /// Inserts a value into the first empty slot
+    ///
+    /// # Errors
+    ///
+    /// Hands `value` back if all slots are occupied
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{MultiCell, Semantics::Relaxed};
+    ///
+    /// let cell: MultiCell<u8, 1> = MultiCell::new();
+    ///
+    /// assert_eq!(cell.set(1, Relaxed), Ok(()));
+    /// assert_eq!(cell.set(2, Relaxed), Err(2))
+    /// ```
+    pub fn set(&self, value: T, order: impl Into<Exchange>) -> Result<(), T> {
+        let order = order.into();
+        let ptr = PtrCell::heap_leak(Some(value));
+
+        for slot in &self.slots {
+            let inserting = slot.value.compare_exchange(
+                core::ptr::null_mut(),
+                ptr,
+                order.success(),
+                order.failure(),
+            );
+
+            if inserting.is_ok() {
+                return Ok(());
+            }
+        }
+
+        // The pointer isn't null, so the value always comes back
+        let value = unsafe { PtrCell::heap_reclaim(ptr) };
+
+        value.map_or(Ok(()), Err)
+    }
+
+    /// Takes out the value of any occupied slot
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{MultiCell, Semantics::Relaxed};
+    ///
+    /// let cell: MultiCell<char, 4> = MultiCell::new();
+    /// let _ = cell.set('a', Relaxed);
+    ///
+    /// assert_eq!(cell.take_any(Relaxed), Some('a'));
+    /// assert_eq!(cell.take_any(Relaxed), None)
+    /// ```
+    pub fn take_any(&self, order: Semantics) -> Option<T> {
+        self.slots.iter().find_map(|slot| {
+            if slot.is_empty(Semantics::Relaxed) {
+                return None;
+            }
+
+            slot.take(order)
+        })
+    }
+
+    /// Returns the number of occupied slots
+    ///
+    /// The slots are inspected one by one, so the result may already be outdated when other
+    /// threads are modifying the cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{MultiCell, Semantics::Relaxed};
+    ///
+    /// let cell: MultiCell<u8, 4> = MultiCell::new();
+    /// let _ = cell.set(1, Relaxed);
+    /// let _ = cell.set(2, Relaxed);
+    ///
+    /// assert_eq!(cell.len(Relaxed), 2)
+    /// ```
+    pub fn len(&self, order: Semantics) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| !slot.is_empty(order))
+            .count()
+    }
+
+    /// Determines whether all slots are empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{MultiCell, Semantics::Relaxed};
+    ///
+    /// let cell: MultiCell<u8, 4> = MultiCell::new();
+    ///
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_empty(&self, order: Semantics) -> bool {
+        self.slots.iter().all(|slot| slot.is_empty(order))
+    }
+
+    /// Constructs a cell with all slots empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{MultiCell, Semantics::Relaxed};
+    ///
+    /// static REQUESTS: MultiCell<u32, 8> = MultiCell::new();
+    ///
+    /// assert!(REQUESTS.is_empty(Relaxed))
+    /// ```
+    #[must_use]
+    #[allow(clippy::borrow_interior_mutable_const)]
+    pub const fn new() -> Self {
+        Self {
+            slots: [Self::VACANT; N],
+        }
+    }
+}
+
+impl<T, const N: usize> core::fmt::Debug for MultiCell<T, N> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("MultiCell")
+            .field("len", &self.len(Semantics::Relaxed))
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+impl<T, const N: usize> Default for MultiCell<T, N> {
+    /// Constructs a cell with all slots empty
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
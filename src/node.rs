@@ -0,0 +1,227 @@
+//! Intrusive linked lists built with [`map_owner`](PtrCell::map_owner)
+//!
+//! A [`PtrCell`] that contains a [`Node`] acts as the head of a lock-free stack. New nodes are
+//! pushed with [`Node::push`] and the whole chain can be taken out and walked afterwards
+//!
+//! # Usage
+//!
+#![doc = doctest!()]
+//! use ptr_cell::{node::Node, PtrCell, Semantics::Relaxed};
+//!
+//! let list = PtrCell::default();
+//!
+//! for word in "Hachó en México".split_whitespace().rev() {
+//!     Node::push(&list, word, Relaxed);
+//! }
+//!
+//! let head = list.take(Relaxed).expect("Some words should've been pushed");
+//! let words: Vec<_> = head.into_iter().collect();
+//!
+//! assert_eq!(words.join(" "), "Hachó en México")
+//! ```
+
+use crate::{PtrCell, Semantics};
+use alloc::boxed::Box;
+use core::mem::ManuallyDrop;
+
+/// Node of a singly linked list
+///
+/// Each node owns the rest of the list through its [`next`](Self::next) cell
+pub struct Node<T> {
+    /// The value stored in this node
+    pub value: T,
+
+    /// The rest of the list
+    pub next: PtrCell<Self>,
+}
+
+impl<T> Node<T> {
+    /// Pushes a value to the front of the list in `head`
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, PtrCell, Semantics::Relaxed};
+    ///
+    /// let list = PtrCell::default();
+    ///
+    /// Node::push(&list, 1, Relaxed);
+    /// Node::push(&list, 2, Relaxed);
+    ///
+    /// let head = list.take(Relaxed).expect("The list shouldn't be empty");
+    ///
+    /// assert!(head.into_iter().eq([2, 1]))
+    /// ```
+    #[inline]
+    pub fn push(head: &PtrCell<Self>, value: T, order: Semantics) {
+        head.map_owner(|next| Self { value, next }, order);
+    }
+
+    /// Reverses the list that starts at this node, returning the new first node
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, PtrCell, Semantics::Relaxed};
+    ///
+    /// let list = PtrCell::default();
+    ///
+    /// for value in 1..=3 {
+    ///     Node::push(&list, value, Relaxed);
+    /// }
+    ///
+    /// let head = list.take(Relaxed).expect("The list shouldn't be empty");
+    ///
+    /// assert!(head.reverse().into_iter().eq([1, 2, 3]))
+    /// ```
+    #[must_use]
+    pub fn reverse(self) -> Self {
+        let mut previous = core::ptr::null_mut();
+        let mut current = PtrCell::heap_leak(Some(self));
+
+        loop {
+            let node = unsafe { &mut *current };
+            let next = core::mem::replace(node.next.value.get_mut(), previous);
+
+            if next.is_null() {
+                break *unsafe { Box::from_raw(current) };
+            }
+
+            previous = current;
+            current = next;
+        }
+    }
+
+    /// Counts the nodes in the list that starts at this node
+    ///
+    /// No atomic operations are performed, as the list is borrowed exclusively
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, PtrCell, Semantics::Relaxed};
+    ///
+    /// let list = PtrCell::default();
+    ///
+    /// for value in 1..=3 {
+    ///     Node::push(&list, value, Relaxed);
+    /// }
+    ///
+    /// let mut head = list.take(Relaxed).expect("The list shouldn't be empty");
+    ///
+    /// assert_eq!(head.len(), 3)
+    /// ```
+    #[allow(clippy::len_without_is_empty)] // A list with a node in it is never empty
+    pub fn len(&mut self) -> usize {
+        let mut count = 1;
+        let mut node = self;
+
+        while let Some(next) = node.next.get_mut() {
+            count += 1;
+            node = next;
+        }
+
+        count
+    }
+
+    /// Constructs the last node of a list
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, Semantics::Relaxed};
+    ///
+    /// let node = Node::new('a');
+    ///
+    /// assert!(node.next.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(value: T) -> Self {
+        let next = PtrCell::default();
+
+        Self { value, next }
+    }
+}
+
+impl<T> AsMut<PtrCell<Self>> for Node<T> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut PtrCell<Self> {
+        &mut self.next
+    }
+}
+
+impl<T> Drop for Node<T> {
+    /// Drops the rest of the list one node at a time, so long lists don't overflow the stack
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, PtrCell, Semantics::Relaxed};
+    ///
+    /// let list = PtrCell::default();
+    ///
+    /// for value in 0..100_000 {
+    ///     Node::push(&list, value, Relaxed);
+    /// }
+    ///
+    /// drop(list)
+    /// ```
+    #[inline]
+    fn drop(&mut self) {
+        self.next.drop_chain_iteratively();
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for Node<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("Node")
+            .field("value", &self.value)
+            .field("next", &self.next)
+            .finish()
+    }
+}
+
+impl<T> IntoIterator for Node<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Consumes the list that starts at this node, returning an iterator over its values
+    #[inline]
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { head: Some(self) }
+    }
+}
+
+/// Iterator over the values of a list
+///
+/// Created by [`Node::into_iter`]
+pub struct IntoIter<T> {
+    /// The first node that hasn't been yielded yet
+    head: Option<Node<T>>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let mut node = ManuallyDrop::new(self.head.take()?);
+
+        let next = node.next.value.get_mut();
+        let next = core::mem::replace(next, core::ptr::null_mut());
+        self.head = unsafe { PtrCell::heap_reclaim(next) };
+
+        // The emptied link doesn't own anything, so only the value has to be moved out
+        Some(unsafe { core::ptr::read(core::ptr::addr_of!(node.value)) })
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for IntoIter<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("IntoIter")
+            .field("head", &self.head)
+            .finish()
+    }
+}
@@ -0,0 +1,98 @@
+//! Custom allocation of the owners inserted by the `map_owner` family
+
+use crate::{backoff::Spin, Exchange, Pool, PtrCell};
+use core::convert::Infallible;
+
+/// Source of allocations for the owners that [`map_owner_with_alloc`] inserts into a chain
+///
+/// Building a long chain with [`map_owner`](PtrCell::map_owner) allocates every owner separately.
+/// A node allocator can move that cost elsewhere, like a [`Pool`] that was [filled](Pool::fill)
+/// before the chain is built
+///
+/// # Safety
+///
+/// [`leak`](Self::leak) must behave like [`PtrCell::heap_leak`]. [`None`] must be represented by a
+/// null pointer, and values must be moved into memory that conforms to the [memory layout][1] used
+/// by [`Box`](alloc::boxed::Box), as the chain frees its owners one by one when it's dropped. This
+/// rules out bump arenas that hand out parts of a single allocation
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{node::Node, NodeAlloc, PtrCell, Semantics::Relaxed};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// /// Counts the allocated owners
+/// struct Counting(AtomicUsize);
+///
+/// unsafe impl<T> NodeAlloc<T> for Counting {
+///     fn leak(&self, slot: Option<T>) -> *mut T {
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///         PtrCell::heap_leak(slot)
+///     }
+/// }
+///
+/// let counting = Counting(AtomicUsize::new(0));
+/// let list = PtrCell::default();
+///
+/// for value in 0..3 {
+///     list.map_owner_with_alloc(|next| Node { value, next }, &counting, Relaxed);
+/// }
+///
+/// assert_eq!(counting.0.load(Ordering::Relaxed), 3)
+/// ```
+///
+/// [`map_owner_with_alloc`]: PtrCell::map_owner_with_alloc
+/// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+pub unsafe trait NodeAlloc<T> {
+    /// Moves a value into an allocation and returns a pointer to it
+    ///
+    /// [`None`] is represented by a null pointer
+    fn leak(&self, slot: Option<T>) -> *mut T;
+}
+
+// Pooled allocations conform to the memory layout used by `Box`
+unsafe impl<T, const N: usize> NodeAlloc<T> for Pool<T, N> {
+    #[inline]
+    fn leak(&self, slot: Option<T>) -> *mut T {
+        Pool::leak(self, slot)
+    }
+}
+
+impl<T> PtrCell<T> {
+    /// Like [`map_owner`](Self::map_owner), but allocates the owner with `alloc`
+    ///
+    /// # Panics
+    ///
+    /// If `new` panics. The cell is left as it was and nothing is allocated
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{node::Node, Pool, PtrCell, Semantics::Relaxed};
+    ///
+    /// static NODES: Pool<Node<u32>, 512> = Pool::new();
+    ///
+    /// // Allocates every owner before the chain is built
+    /// NODES.fill();
+    ///
+    /// let list = PtrCell::default();
+    ///
+    /// for value in 0..512 {
+    ///     list.map_owner_with_alloc(|next| Node { value, next }, &NODES, Relaxed);
+    /// }
+    ///
+    /// assert_eq!(NODES.available(), 0)
+    /// ```
+    pub fn map_owner_with_alloc<F, A>(&self, new: F, alloc: &A, order: impl Into<Exchange>)
+    where
+        F: FnOnce(Self) -> T,
+        T: AsMut<Self>,
+        A: NodeAlloc<T>,
+    {
+        let infallible = |value| Ok::<_, (Self, Infallible)>(new(value));
+        let leak = |slot| alloc.leak(slot);
+
+        let Ok(_) = self.insert_owner(infallible, order.into(), Spin, leak);
+    }
+}
@@ -0,0 +1,122 @@
+//! Pointer API based on [`NonNull`]
+
+use crate::{PtrCell, Semantics};
+use core::ptr::NonNull;
+
+impl<T> PtrCell<T> {
+    /// Returns a pointer to the cell's value, or [`None`] if the cell is empty
+    ///
+    /// # Safety
+    ///
+    /// Not inherently unsafe. See [Pointer Safety][1]
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::<[u8; 3]>::default();
+    ///
+    /// assert_eq!(cell.get_ptr_nonnull(Relaxed), None)
+    /// ```
+    ///
+    /// [1]: https://docs.rs/ptr_cell/latest/ptr_cell/struct.PtrCell.html#pointer-safety
+    #[inline]
+    pub fn get_ptr_nonnull(&self, order: Semantics) -> Option<NonNull<T>> {
+        NonNull::new(self.get_ptr(order))
+    }
+
+    /// Takes out the cell's pointer, or [`None`] if the cell is empty
+    ///
+    /// # Safety
+    ///
+    /// Not inherently unsafe. See [Pointer Safety][1]
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell: PtrCell<u8> = 45.into();
+    /// let ptr = cell.take_ptr_nonnull(Relaxed).expect("The cell should contain a value");
+    ///
+    /// assert_eq!(unsafe { PtrCell::heap_reclaim(ptr.as_ptr()) }, Some(45));
+    /// assert_eq!(cell.take_ptr_nonnull(Relaxed), None)
+    /// ```
+    ///
+    /// [1]: https://docs.rs/ptr_cell/latest/ptr_cell/struct.PtrCell.html#pointer-safety
+    #[inline]
+    pub fn take_ptr_nonnull(&self, order: Semantics) -> Option<NonNull<T>> {
+        NonNull::new(self.take_ptr(order))
+    }
+
+    /// Inserts a pointer into the cell. [`None`] empties the cell
+    ///
+    /// # Safety
+    ///
+    /// The pointed-to memory must conform to the [memory layout][1] used by [`Box`]
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    /// use std::ptr::NonNull;
+    ///
+    /// let cell = PtrCell::default();
+    ///
+    /// let ptr = NonNull::new(PtrCell::heap_leak(Some(1776)));
+    /// unsafe { cell.set_ptr_nonnull(ptr, Relaxed) };
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(1776))
+    /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    #[inline]
+    pub unsafe fn set_ptr_nonnull(&self, ptr: Option<NonNull<T>>, order: Semantics) {
+        unsafe { self.set_ptr(into_raw(ptr), order) };
+    }
+
+    /// Replaces the cell's pointer. [`None`] represents an empty cell on both sides
+    ///
+    /// Unlike [`replace_ptr`](Self::replace_ptr), this method is correctly marked as `unsafe`
+    ///
+    /// # Safety
+    ///
+    /// The pointed-to memory must conform to the [memory layout][1] used by [`Box`]
+    ///
+    /// See also: [Pointer Safety][2]
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    /// use std::ptr::NonNull;
+    ///
+    /// let cell = PtrCell::from('a');
+    ///
+    /// let b = NonNull::new(PtrCell::heap_leak(Some('b')));
+    /// let a = unsafe { cell.replace_ptr_nonnull(b, Relaxed) };
+    ///
+    /// let a = a.expect("The cell should have contained a value");
+    /// assert_eq!(unsafe { PtrCell::heap_reclaim(a.as_ptr()) }, Some('a'));
+    /// assert_eq!(cell.take(Relaxed), Some('b'))
+    /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    /// [2]: https://docs.rs/ptr_cell/latest/ptr_cell/struct.PtrCell.html#pointer-safety
+    #[inline]
+    #[must_use = "use `.set_ptr_nonnull()` if you don't need the old pointer"]
+    pub unsafe fn replace_ptr_nonnull(
+        &self,
+        ptr: Option<NonNull<T>>,
+        order: Semantics,
+    ) -> Option<NonNull<T>> {
+        NonNull::new(self.replace_ptr(into_raw(ptr), order))
+    }
+}
+
+/// Converts an optional pointer into a nullable one
+#[inline]
+fn into_raw<T>(ptr: Option<NonNull<T>>) -> *mut T {
+    ptr.map_or(core::ptr::null_mut(), NonNull::as_ptr)
+}
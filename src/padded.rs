@@ -0,0 +1,148 @@
+//! Cache line padding
+
+/// Wrapper that aligns and pads a value to the length of a cache line
+///
+/// Values that are written by different threads but located on the same cache line cause [false
+/// sharing][1], which can seriously degrade performance. Wrapping each of them in this type places
+/// them on separate lines
+///
+/// The alignment depends on the target architecture. It's 128 bytes on most modern 64-bit
+/// platforms, as their prefetchers pull cache lines in pairs
+///
+/// # Usage
+///
+#[doc = alloc_doctest!()]
+/// use ptr_cell::{CachePadded, PtrCell, Semantics::Relaxed};
+///
+/// let cells: [CachePadded<PtrCell<u8>>; 2] = Default::default();
+///
+/// cells[0].set(Some(1), Relaxed);
+/// cells[1].set(Some(2), Relaxed);
+///
+/// assert!(std::mem::align_of_val(&cells[0]) >= 32);
+/// assert_eq!(cells[1].take(Relaxed), Some(2))
+/// ```
+///
+/// [1]: https://en.wikipedia.org/wiki/False_sharing
+#[cfg_attr(
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "arm64ec",
+        target_arch = "powerpc64",
+    ),
+    repr(align(128))
+)]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
+#[cfg_attr(
+    any(
+        target_arch = "arm",
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "sparc",
+        target_arch = "hexagon",
+    ),
+    repr(align(32))
+)]
+#[cfg_attr(target_arch = "m68k", repr(align(16)))]
+#[cfg_attr(
+    not(any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "arm64ec",
+        target_arch = "powerpc64",
+        target_arch = "s390x",
+        target_arch = "arm",
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "sparc",
+        target_arch = "hexagon",
+        target_arch = "m68k",
+    )),
+    repr(align(64))
+)]
+#[derive(Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+pub struct CachePadded<T> {
+    /// The padded value
+    value: T,
+}
+
+/// [`PtrCell`](crate::PtrCell) that occupies an entire cache line
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{PaddedPtrCell, Semantics::Relaxed};
+///
+/// let cell = PaddedPtrCell::new(0x81D.into());
+///
+/// assert_eq!(cell.take(Relaxed), Some(0x81D))
+/// ```
+#[cfg(feature = "alloc")]
+pub type PaddedPtrCell<T> = CachePadded<crate::PtrCell<T>>;
+
+impl<T> CachePadded<T> {
+    /// Unwraps the padded value
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::CachePadded;
+    ///
+    /// let padded = CachePadded::new(7);
+    ///
+    /// assert_eq!(padded.into_inner(), 7)
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Pads a value
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::CachePadded;
+    ///
+    /// let padded = CachePadded::new(7);
+    ///
+    /// assert_eq!(*padded, 7)
+    /// ```
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self { value }
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for CachePadded<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("CachePadded")
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<T> core::ops::Deref for CachePadded<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> core::ops::DerefMut for CachePadded<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> From<T> for CachePadded<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
@@ -0,0 +1,184 @@
+//! Thread-safe cell for pinned values
+
+use crate::{PtrCell, Semantics};
+use alloc::boxed::Box;
+use core::pin::Pin;
+
+/// Thread-safe cell for [pinned](core::pin) values, like self-referential futures
+///
+/// Values go in and out as `Pin<Box<T>>`, and the cell keeps them in the very same allocation in
+/// between. As a value can never be moved out of its box through this type, the pinning guarantee
+/// holds without any `unsafe` code on the caller's side
+///
+/// This is a thin wrapper around a [`PtrCell`], so it's the same size as a pointer
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{PinnedPtrCell, Semantics::Relaxed};
+///
+/// let task = PinnedPtrCell::from(Box::pin(async { 0xFAA }));
+/// let future = task.take_pinned(Relaxed).expect("The task should be there");
+///
+/// assert_eq!(pollster::block_on(future), 0xFAA)
+/// ```
+pub struct PinnedPtrCell<T> {
+    /// The cell. Its value is never moved out of its allocation
+    cell: PtrCell<T>,
+}
+
+impl<T> PinnedPtrCell<T> {
+    /// Inserts a pinned value into the cell. [`None`] empties the cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PinnedPtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PinnedPtrCell::empty();
+    /// cell.set_pinned(Some(Box::pin(0xFAA)), Relaxed);
+    ///
+    /// assert_eq!(cell.take_pinned(Relaxed).as_deref(), Some(&0xFAA))
+    /// ```
+    #[inline]
+    pub fn set_pinned(&self, slot: Option<Pin<Box<T>>>, order: Semantics) {
+        drop(self.replace_pinned(slot, order));
+    }
+
+    /// Takes out the cell's value without moving it out of its allocation
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PinnedPtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PinnedPtrCell::from(Box::pin('a'));
+    ///
+    /// assert_eq!(cell.take_pinned(Relaxed).as_deref(), Some(&'a'));
+    /// assert!(cell.take_pinned(Relaxed).is_none())
+    /// ```
+    #[inline]
+    pub fn take_pinned(&self, order: Semantics) -> Option<Pin<Box<T>>> {
+        self.replace_pinned(None, order)
+    }
+
+    /// Replaces the cell's value, returning the previous one without moving it out of its
+    /// allocation
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PinnedPtrCell, Semantics::Relaxed};
+    ///
+    /// let pinned = Box::pin(1);
+    /// let address: *const i32 = &*pinned;
+    ///
+    /// let cell = PinnedPtrCell::from(pinned);
+    /// let old = cell.replace_pinned(Some(Box::pin(2)), Relaxed);
+    ///
+    /// let old = old.expect("The cell should have contained a value");
+    /// assert_eq!(&*old as *const i32, address)
+    /// ```
+    #[must_use = "use `.set_pinned()` if you don't need the old value"]
+    pub fn replace_pinned(
+        &self,
+        slot: Option<Pin<Box<T>>>,
+        order: Semantics,
+    ) -> Option<Pin<Box<T>>> {
+        // The box is only ever turned back into a pinned box, so the value never moves
+        let new_ptr = slot.map_or(core::ptr::null_mut(), |pinned| {
+            PtrCell::from(unsafe { Pin::into_inner_unchecked(pinned) }).leak()
+        });
+
+        let old_ptr = self.cell.replace_ptr(new_ptr, order);
+
+        unsafe { crate::into_box(old_ptr) }.map(Box::into_pin)
+    }
+
+    /// Returns a pinned mutable reference to the value
+    ///
+    /// No synchronization is needed, as the cell is borrowed exclusively
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PinnedPtrCell, Semantics::Relaxed};
+    ///
+    /// let mut cell = PinnedPtrCell::from(Box::pin(1));
+    ///
+    /// if let Some(mut value) = cell.get_pin_mut() {
+    ///     *value += 1;
+    /// }
+    ///
+    /// assert_eq!(cell.take_pinned(Relaxed).as_deref(), Some(&2))
+    /// ```
+    #[inline]
+    pub fn get_pin_mut(&mut self) -> Option<Pin<&mut T>> {
+        let value = self.cell.get_mut()?;
+
+        // The value stays in its allocation for as long as it's in the cell
+        Some(unsafe { Pin::new_unchecked(value) })
+    }
+
+    /// Determines whether the cell is empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PinnedPtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PinnedPtrCell::<u8>::empty();
+    ///
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_empty(&self, order: Semantics) -> bool {
+        self.cell.is_empty(order)
+    }
+
+    /// Constructs an empty cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PinnedPtrCell, Semantics::Relaxed};
+    ///
+    /// static TASK: PinnedPtrCell<[u8; 64]> = PinnedPtrCell::empty();
+    ///
+    /// assert!(TASK.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self {
+            cell: PtrCell::empty(),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for PinnedPtrCell<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("PinnedPtrCell")
+            .field("cell", &self.cell)
+            .finish()
+    }
+}
+
+impl<T> Default for PinnedPtrCell<T> {
+    /// Constructs an empty cell
+    #[inline]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T> From<Pin<Box<T>>> for PinnedPtrCell<T> {
+    /// Constructs a cell that takes over the allocation of `pinned` without moving its value
+    #[inline]
+    fn from(pinned: Pin<Box<T>>) -> Self {
+        let boxed = unsafe { Pin::into_inner_unchecked(pinned) };
+
+        Self { cell: boxed.into() }
+    }
+}
@@ -0,0 +1,346 @@
+//! Allocation reuse for high-frequency updates
+
+use crate::{non_null, AtomicPtr, PtrCell, Semantics};
+use alloc::boxed::Box;
+use core::{mem::MaybeUninit, sync::atomic::Ordering};
+
+/// Lock-free pool of up to `N` recycled heap allocations
+///
+/// Inserting a value into a [`PtrCell`] normally requires allocating a fresh [`Box`], which is then
+/// freed when the value is taken out. The pooled methods of the cell, like
+/// [`set_pooled`](PtrCell::set_pooled) and [`take_pooled`](PtrCell::take_pooled), instead return
+/// emptied allocations to a pool and draw new ones from it. The allocator is only involved when the
+/// pool runs dry or overflows
+///
+/// All allocations conform to the [memory layout][1] used by [`Box`], so pooled and regular values
+/// can be mixed freely within the same cell
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{Pool, PtrCell, Semantics::Relaxed};
+///
+/// let pool: Pool<u64, 4> = Pool::new();
+/// let cell = PtrCell::default();
+///
+/// for number in 0..1000 {
+///     cell.set_pooled(Some(number), &pool, Relaxed);
+/// }
+///
+/// assert_eq!(cell.take_pooled(&pool, Relaxed), Some(999));
+/// assert_eq!(pool.available(), 2)
+/// ```
+///
+/// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+pub struct Pool<T, const N: usize> {
+    /// Recycled allocations. Null pointers mark vacant slots
+    ///
+    /// #### Invariants
+    ///
+    /// - **If non-null**: Must point to _uninitialized_ memory that conforms to the [memory
+    ///   layout][1] used by [`Box`]
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    buffers: [AtomicPtr<T>; N],
+}
+
+impl<T, const N: usize> Pool<T, N> {
+    /// Vacant slot of the pool
+    #[allow(clippy::declare_interior_mutable_const)]
+    const VACANT: AtomicPtr<T> = AtomicPtr::new(core::ptr::null_mut());
+
+    /// Returns the number of allocations ready for reuse
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::Pool;
+    ///
+    /// let pool: Pool<u8, 3> = Pool::new();
+    /// assert_eq!(pool.available(), 0);
+    ///
+    /// pool.fill();
+    /// assert_eq!(pool.available(), 3)
+    /// ```
+    pub fn available(&self) -> usize {
+        self.buffers
+            .iter()
+            .filter(|buffer| !buffer.load(Ordering::Relaxed).is_null())
+            .count()
+    }
+
+    /// Allocates a buffer for every vacant slot of the pool
+    ///
+    /// Useful to move the cost of allocation out of a hot loop
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::Pool;
+    ///
+    /// let pool: Pool<[u8; 64], 8> = Pool::new();
+    /// pool.fill();
+    ///
+    /// assert_eq!(pool.available(), 8)
+    /// ```
+    pub fn fill(&self) {
+        for buffer in &self.buffers {
+            if !buffer.load(Ordering::Relaxed).is_null() {
+                continue;
+            }
+
+            let fresh = Self::allocate();
+            let null = core::ptr::null_mut();
+
+            if buffer
+                .compare_exchange(null, fresh, Ordering::Release, Ordering::Relaxed)
+                .is_err()
+            {
+                unsafe { Self::deallocate(fresh) };
+            }
+        }
+    }
+
+    /// Moves a value into an allocation from the pool, or into a new one if the pool is empty
+    ///
+    /// [`None`] is represented by a null pointer
+    ///
+    /// Works just like [`PtrCell::heap_leak`]
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::Pool;
+    ///
+    /// let pool: Pool<u16, 1> = Pool::new();
+    /// let ptr = pool.leak(Some(1155));
+    ///
+    /// assert_eq!(unsafe { pool.reclaim(ptr) }, Some(1155));
+    /// assert_eq!(pool.available(), 1)
+    /// ```
+    #[must_use]
+    pub fn leak(&self, slot: Option<T>) -> *mut T {
+        let Some(value) = slot else {
+            return core::ptr::null_mut();
+        };
+
+        let buffer = self.acquire().unwrap_or_else(Self::allocate);
+        unsafe { buffer.write(value) };
+
+        #[cfg(feature = "debug-track")]
+        crate::track::record(buffer);
+
+        #[cfg(feature = "stats")]
+        crate::stats::allocated(buffer);
+
+        buffer
+    }
+
+    /// Moves the value out of [leaked](Self::leak) memory and returns the allocation to the pool
+    ///
+    /// If the pool is full, the memory is deallocated instead. A null pointer represents [`None`]
+    ///
+    /// Works just like [`PtrCell::heap_reclaim`]
+    ///
+    /// # Safety
+    ///
+    /// The memory must conform to the [memory layout][1] used by [`Box`]. It doesn't have to come
+    /// from this pool
+    ///
+    /// Dereferencing `ptr` after this function has been called may cause undefined behavior
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Pool, PtrCell};
+    ///
+    /// let pool: Pool<u16, 1> = Pool::new();
+    /// let ptr = PtrCell::heap_leak(Some(1155));
+    ///
+    /// assert_eq!(unsafe { pool.reclaim(ptr) }, Some(1155));
+    /// assert_eq!(pool.available(), 1)
+    /// ```
+    ///
+    /// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+    pub unsafe fn reclaim(&self, ptr: *mut T) -> Option<T> {
+        #[cfg(feature = "debug-track")]
+        crate::track::forget(ptr);
+
+        #[cfg(feature = "stats")]
+        crate::stats::deallocated(ptr);
+
+        let ptr = non_null(ptr)?;
+        let value = unsafe { ptr.read() };
+
+        unsafe { self.recycle(ptr) };
+
+        Some(value)
+    }
+
+    /// Constructs an empty pool
+    ///
+    /// No memory is allocated until the pool is [filled](Self::fill) or values are reclaimed into it
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::Pool;
+    ///
+    /// static POOL: Pool<u32, 16> = Pool::new();
+    ///
+    /// assert_eq!(POOL.available(), 0)
+    /// ```
+    #[must_use]
+    #[allow(clippy::borrow_interior_mutable_const)]
+    pub const fn new() -> Self {
+        let buffers = [Self::VACANT; N];
+
+        Self { buffers }
+    }
+
+    /// Takes an allocation out of the pool, if there's one
+    fn acquire(&self) -> Option<*mut T> {
+        self.buffers.iter().find_map(|buffer| {
+            if buffer.load(Ordering::Relaxed).is_null() {
+                return None;
+            }
+
+            non_null(buffer.swap(core::ptr::null_mut(), Ordering::Acquire))
+        })
+    }
+
+    /// Puts an allocation into a vacant slot of the pool, or deallocates it if there's none
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must be non-null, uninitialized, and conform to the memory layout used by [`Box`]
+    unsafe fn recycle(&self, buffer: *mut T) {
+        for slot in &self.buffers {
+            if !slot.load(Ordering::Relaxed).is_null() {
+                continue;
+            }
+
+            let null = core::ptr::null_mut();
+
+            if slot
+                .compare_exchange(null, buffer, Ordering::Release, Ordering::Relaxed)
+                .is_ok()
+            {
+                return;
+            }
+        }
+
+        unsafe { Self::deallocate(buffer) };
+    }
+
+    /// Allocates an uninitialized buffer
+    fn allocate() -> *mut T {
+        Box::into_raw(Box::<MaybeUninit<T>>::new(MaybeUninit::uninit())).cast()
+    }
+
+    /// Frees an uninitialized buffer
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must be non-null and conform to the memory layout used by [`Box`]
+    unsafe fn deallocate(buffer: *mut T) {
+        drop(unsafe { Box::from_raw(buffer.cast::<MaybeUninit<T>>()) });
+    }
+}
+
+impl<T, const N: usize> core::fmt::Debug for Pool<T, N> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("Pool")
+            .field("available", &self.available())
+            .finish()
+    }
+}
+
+impl<T, const N: usize> Default for Pool<T, N> {
+    /// Constructs an empty pool
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for Pool<T, N> {
+    fn drop(&mut self) {
+        for buffer in &mut self.buffers {
+            if let Some(buffer) = non_null(*buffer.get_mut()) {
+                unsafe { Self::deallocate(buffer) };
+            }
+        }
+    }
+}
+
+impl<T> PtrCell<T> {
+    /// Takes out the cell's value and returns its allocation to `pool`
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Pool, PtrCell, Semantics::Relaxed};
+    ///
+    /// let pool: Pool<u8, 1> = Pool::new();
+    /// let cell: PtrCell<u8> = 45.into();
+    ///
+    /// assert_eq!(cell.take_pooled(&pool, Relaxed), Some(45));
+    /// assert_eq!(pool.available(), 1)
+    /// ```
+    #[inline]
+    pub fn take_pooled<const N: usize>(&self, pool: &Pool<T, N>, order: Semantics) -> Option<T> {
+        self.replace_pooled(None, pool, order)
+    }
+
+    /// Inserts a value into the cell, drawing and returning allocations from and to `pool`
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Pool, PtrCell, Semantics::Relaxed};
+    ///
+    /// let pool: Pool<u16, 1> = Pool::new();
+    /// let cell = PtrCell::default();
+    ///
+    /// cell.set_pooled(Some(1776), &pool, Relaxed);
+    /// cell.set_pooled(Some(1789), &pool, Relaxed);
+    ///
+    /// assert_eq!(pool.available(), 1);
+    /// assert_eq!(cell.take(Relaxed), Some(1789))
+    /// ```
+    #[inline]
+    pub fn set_pooled<const N: usize>(&self, slot: Option<T>, pool: &Pool<T, N>, order: Semantics) {
+        let _ = self.replace_pooled(slot, pool, order);
+    }
+
+    /// Replaces the cell's value, drawing and returning allocations from and to `pool`
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Pool, PtrCell, Semantics::Relaxed};
+    ///
+    /// let pool: Pool<char, 1> = Pool::new();
+    /// let cell = PtrCell::from('a');
+    ///
+    /// assert_eq!(cell.replace_pooled(Some('b'), &pool, Relaxed), Some('a'));
+    /// assert_eq!(cell.take_pooled(&pool, Relaxed), Some('b'))
+    /// ```
+    #[inline]
+    #[must_use = "use `.set_pooled()` if you don't need the old value"]
+    pub fn replace_pooled<const N: usize>(
+        &self,
+        slot: Option<T>,
+        pool: &Pool<T, N>,
+        order: Semantics,
+    ) -> Option<T> {
+        let new_leak = pool.leak(slot);
+
+        unsafe {
+            let old_leak = self.replace_ptr(new_leak, order);
+            pool.reclaim(old_leak)
+        }
+    }
+}
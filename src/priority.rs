@@ -0,0 +1,189 @@
+//! Thread-safe cell that keeps the greatest value offered to it
+
+use crate::{Semantics, StateCell};
+use core::cmp::Ordering;
+
+/// Thread-safe cell that keeps whichever value compares greatest
+///
+/// Values are [offered](Self::offer) to the cell and compared with its current value by a
+/// user-supplied function. The greater value stays in the cell, and the other one is handed back.
+/// This generalizes the maximum-finding example from the [crate documentation](crate), and covers
+/// patterns like "the latest value by sequence number wins"
+///
+/// The cell is briefly locked while a value is being offered, so the comparison always sees the
+/// value that it replaces. Meanwhile, other operations on the cell spin
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{PriorityCell, Semantics::Coupled};
+///
+/// struct Update {
+///     sequence: u64,
+///     text: &'static str,
+/// }
+///
+/// let latest = PriorityCell::new(|a: &Update, b: &Update| a.sequence.cmp(&b.sequence));
+///
+/// std::thread::scope(|scope| {
+///     for (sequence, text) in [(3, "third"), (1, "first"), (2, "second")] {
+///         let latest = &latest;
+///         scope.spawn(move || latest.offer(Update { sequence, text }, Coupled));
+///     }
+/// });
+///
+/// let update = latest.take(Coupled).expect("An update should have won");
+/// assert_eq!(update.text, "third")
+/// ```
+pub struct PriorityCell<T, F> {
+    /// The cell. Locked while a value is being offered or taken out
+    cell: StateCell<T>,
+
+    /// Compares an offered value with the current one
+    compare: F,
+}
+
+impl<T, F> PriorityCell<T, F>
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    /// Offers a value to the cell, keeping it only if it compares greater than the current one
+    ///
+    /// Returns the value that lost, or [`None`] if the cell was empty. On a tie, the current value
+    /// is kept. If the comparison panics, both values are dropped and the cell is left empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PriorityCell, Semantics::Relaxed};
+    ///
+    /// let maximum = PriorityCell::new(u8::cmp);
+    ///
+    /// assert_eq!(maximum.offer(5, Relaxed), None);
+    /// assert_eq!(maximum.offer(9, Relaxed), Some(5));
+    /// assert_eq!(maximum.offer(7, Relaxed), Some(7));
+    /// assert_eq!(maximum.take(Relaxed), Some(9))
+    /// ```
+    pub fn offer(&self, value: T, order: Semantics) -> Option<T> {
+        let Some(current) = self.lock_take(order) else {
+            self.cell.store_and_unlock(Some(value), order);
+            return None;
+        };
+
+        let unlock = Unlock {
+            cell: &self.cell,
+            order,
+        };
+
+        let ordering = (self.compare)(&value, &current);
+        core::mem::forget(unlock);
+
+        let (winner, loser) = match ordering {
+            Ordering::Greater => (value, current),
+            Ordering::Less | Ordering::Equal => (current, value),
+        };
+
+        self.cell.store_and_unlock(Some(winner), order);
+
+        Some(loser)
+    }
+
+    /// Takes out the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PriorityCell, Semantics::Relaxed};
+    ///
+    /// let cell = PriorityCell::new(char::cmp);
+    /// let _ = cell.offer('a', Relaxed);
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some('a'));
+    /// assert_eq!(cell.take(Relaxed), None)
+    /// ```
+    pub fn take(&self, order: Semantics) -> Option<T> {
+        let value = self.lock_take(order);
+        self.cell.store_and_unlock(None, order);
+
+        value
+    }
+
+    /// Determines whether the cell is empty
+    ///
+    /// A cell in the middle of an offer counts as occupied
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PriorityCell, Semantics::Relaxed};
+    ///
+    /// let cell = PriorityCell::new(u32::cmp);
+    ///
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_empty(&self, order: Semantics) -> bool {
+        self.cell.state(order) == crate::State::Empty
+    }
+
+    /// Constructs an empty cell that compares values with `compare`
+    ///
+    /// `compare(offered, current)` decides whether the offered value replaces the current one
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PriorityCell, Semantics::Relaxed};
+    ///
+    /// // Keeps the shortest string
+    /// let shortest = PriorityCell::new(|a: &String, b: &String| b.len().cmp(&a.len()));
+    ///
+    /// let _ = shortest.offer("longer".into(), Relaxed);
+    /// let _ = shortest.offer("short".into(), Relaxed);
+    ///
+    /// assert_eq!(shortest.take(Relaxed).as_deref(), Some("short"))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(compare: F) -> Self {
+        Self {
+            cell: StateCell::new(),
+            compare,
+        }
+    }
+
+    /// Spins until the cell is locked, then returns its value
+    fn lock_take(&self, order: Semantics) -> Option<T> {
+        loop {
+            if let Ok(value) = self.cell.try_lock_take(order) {
+                return value;
+            }
+
+            core::hint::spin_loop();
+        }
+    }
+}
+
+impl<T, F> core::fmt::Debug for PriorityCell<T, F> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("PriorityCell")
+            .field("cell", &self.cell)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Unlocks the cell empty if the comparison panics
+struct Unlock<'a, T> {
+    /// The locked cell
+    cell: &'a StateCell<T>,
+
+    /// Semantics of the unlock
+    order: Semantics,
+}
+
+impl<T> Drop for Unlock<'_, T> {
+    fn drop(&mut self) {
+        self.cell.store_and_unlock(None, self.order);
+    }
+}
@@ -0,0 +1,162 @@
+//! Global registry of named cells, enabled by the `registry` feature
+//!
+//! Static cells can be [registered](register) under string keys and [looked up](get) from anywhere
+//! in the program. This lets modules share cells without depending on each other, as long as they
+//! agree on the key and the value's type
+//!
+//! The registry is a lock-free [list](crate::node) of entries. Entries are never removed, so
+//! lookups don't need any synchronization beyond loading the list's pointers
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ptr_cell::{registry, PtrCell, Semantics::Relaxed};
+//!
+//! static QUEUE: PtrCell<Vec<u32>> = PtrCell::empty();
+//!
+//! registry::register("render.queue", &QUEUE).expect("The key should be free");
+//!
+//! // Somewhere else
+//! let queue = registry::get::<Vec<u32>>("render.queue").expect("The cell should be registered");
+//! queue.set(Some(vec![1, 2, 3]), Relaxed);
+//!
+//! assert_eq!(QUEUE.take(Relaxed), Some(vec![1, 2, 3]))
+//! ```
+
+use crate::{node::Node, PtrCell, Semantics};
+use core::{any::Any, sync::atomic::Ordering};
+
+/// The registered entries, most recent first
+static ENTRIES: PtrCell<Node<Entry>> = PtrCell::empty();
+
+/// Registered cell
+struct Entry {
+    /// The key that the cell is registered under
+    key: &'static str,
+
+    /// The cell, with its value's type erased
+    cell: &'static (dyn Any + Sync),
+}
+
+/// Error returned when a key is already taken by another cell
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::{registry, PtrCell};
+///
+/// static FIRST: PtrCell<u8> = PtrCell::empty();
+/// static SECOND: PtrCell<u8> = PtrCell::empty();
+///
+/// assert_eq!(registry::register("errors.first", &FIRST), Ok(()));
+/// assert_eq!(registry::register("errors.first", &SECOND), Err(registry::AlreadyRegistered))
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+pub struct AlreadyRegistered;
+
+impl core::fmt::Display for AlreadyRegistered {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("a cell is already registered under this key")
+    }
+}
+
+impl core::error::Error for AlreadyRegistered {}
+
+/// Registers `cell` under `key`
+///
+/// # Errors
+///
+/// Returns [`AlreadyRegistered`] if another cell is registered under `key`. The registry is left
+/// unchanged in that case
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::{registry, PtrCell, Semantics::Relaxed};
+///
+/// static LEVEL: PtrCell<u8> = PtrCell::empty();
+///
+/// registry::register("audio.level", &LEVEL).expect("The key should be free");
+///
+/// assert!(registry::get::<u8>("audio.level").is_some())
+/// ```
+pub fn register<T: Send + 'static>(
+    key: &'static str,
+    cell: &'static PtrCell<T>,
+) -> Result<(), AlreadyRegistered> {
+    let node_ptr = PtrCell::heap_leak(Some(Node::new(Entry { key, cell })));
+    let node = unsafe { &mut *node_ptr };
+
+    let mut head = ENTRIES.get_ptr(Semantics::Coupled);
+    let mut scanned = core::ptr::null_mut();
+
+    loop {
+        // Only the entries registered since the last attempt need to be checked
+        if find(head, scanned, key).is_some() {
+            *node.next.value.get_mut() = core::ptr::null_mut();
+            drop(unsafe { PtrCell::heap_reclaim(node_ptr) });
+
+            return Err(AlreadyRegistered);
+        }
+
+        *node.next.value.get_mut() = head;
+
+        let inserting =
+            ENTRIES
+                .value
+                .compare_exchange(head, node_ptr, Ordering::AcqRel, Ordering::Acquire);
+
+        match inserting {
+            Ok(_) => return Ok(()),
+            Err(current) => {
+                scanned = head;
+                head = current;
+            }
+        }
+    }
+}
+
+/// Returns the cell registered under `key`
+///
+/// Returns [`None`] if no cell is registered under `key`, or if the registered cell holds values
+/// of a type other than `T`
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::{registry, PtrCell};
+///
+/// static NAME: PtrCell<String> = PtrCell::empty();
+///
+/// registry::register("user.name", &NAME).expect("The key should be free");
+///
+/// assert!(registry::get::<String>("user.name").is_some());
+/// assert!(registry::get::<u64>("user.name").is_none());
+/// assert!(registry::get::<String>("user.email").is_none())
+/// ```
+pub fn get<T: Send + 'static>(key: &str) -> Option<&'static PtrCell<T>> {
+    let head = ENTRIES.get_ptr(Semantics::Coupled);
+    let entry = find(head, core::ptr::null_mut(), key)?;
+
+    let cell: &'static dyn Any = entry.cell;
+
+    cell.downcast_ref()
+}
+
+/// Walks the entries from `head` until `end`, returning the one registered under `key`
+fn find(head: *mut Node<Entry>, end: *mut Node<Entry>, key: &str) -> Option<&'static Entry> {
+    let mut current = head;
+
+    while current != end {
+        // Entries are never removed, so every reachable node lives forever
+        let node: &'static Node<Entry> = unsafe { &*current };
+
+        if node.value.key == key {
+            return Some(&node.value);
+        }
+
+        current = node.next.get_ptr(Semantics::Coupled);
+    }
+
+    None
+}
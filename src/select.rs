@@ -0,0 +1,64 @@
+//! Waiting on multiple cells at once
+
+use crate::{
+    backoff::{Backoff, SpinThenYield},
+    AtomicUsize, PtrCell, Semantics,
+};
+use core::sync::atomic::Ordering;
+
+/// Position at which the next [`select`] starts scanning
+///
+/// Updated without synchronization, as lost updates only make the rotation less even
+static NEXT_START: AtomicUsize = AtomicUsize::new(0);
+
+/// Blocks until one of `cells` holds a value, then takes it out
+///
+/// Returns the index of the cell that the value was taken from along with the value
+///
+/// The cells are polled in rounds, backing off with [`SpinThenYield`] between them. Every round
+/// starts at a different cell, so a cell that is always full can't starve the others
+///
+/// # Panics
+///
+/// If `cells` is empty, as the call would block forever
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{PtrCell, Semantics::Coupled};
+///
+/// let results: [PtrCell<&str>; 3] = Default::default();
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| results[1].set(Some("done"), Coupled));
+///
+///     assert_eq!(ptr_cell::select(&[&results[0], &results[1], &results[2]], Coupled), (1, "done"))
+/// })
+/// ```
+pub fn select<T>(cells: &[&PtrCell<T>], order: Semantics) -> (usize, T) {
+    assert!(!cells.is_empty(), "can't select from zero cells");
+
+    let mut backoff = SpinThenYield::new();
+    let mut start = NEXT_START.load(Ordering::Relaxed);
+
+    loop {
+        start = start.wrapping_add(1);
+
+        let indices = (0..cells.len()).map(|offset| start.wrapping_add(offset) % cells.len());
+
+        for index in indices {
+            let cell = cells[index];
+
+            if cell.is_empty(Semantics::Relaxed) {
+                continue;
+            }
+
+            if let Some(value) = cell.take(order) {
+                NEXT_START.store(start, Ordering::Relaxed);
+                return (index, value);
+            }
+        }
+
+        backoff.backoff();
+    }
+}
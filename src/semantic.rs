@@ -0,0 +1,219 @@
+//! Cells with their own default semantics
+
+use crate::{PtrCell, Semantics};
+
+/// [`PtrCell`] that remembers which semantics to use
+///
+/// Code that uses the same semantics everywhere can call the `_default` methods instead of
+/// threading them through every call. The methods of the underlying cell are still available
+/// through [`Deref`](core::ops::Deref), so any single operation can override the default
+///
+/// The semantics are stored next to the cell, so this type is larger than a plain `PtrCell`
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{PtrCell, Semantics::{Coupled, Relaxed}};
+///
+/// let cell = PtrCell::with_semantics(Some(0x81D), Coupled);
+///
+/// assert_eq!(cell.replace_default(Some(2047)), Some(0x81D));
+/// assert_eq!(cell.take(Relaxed), Some(2047))
+/// ```
+pub struct SemanticPtrCell<T> {
+    /// The underlying cell
+    cell: PtrCell<T>,
+
+    /// Semantics of the `_default` methods
+    order: Semantics,
+}
+
+impl<T> SemanticPtrCell<T> {
+    /// Takes out the cell's value with its default semantics
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::with_semantics(Some(45), Relaxed);
+    ///
+    /// assert_eq!(cell.take_default(), Some(45));
+    /// assert_eq!(cell.take_default(), None)
+    /// ```
+    #[inline]
+    pub fn take_default(&self) -> Option<T> {
+        self.cell.take(self.order)
+    }
+
+    /// Inserts a value into the cell with its default semantics
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::with_semantics(None, Relaxed);
+    /// cell.set_default(Some(1776));
+    ///
+    /// assert_eq!(cell.take_default(), Some(1776))
+    /// ```
+    #[inline]
+    pub fn set_default(&self, slot: Option<T>) {
+        self.cell.set(slot, self.order);
+    }
+
+    /// Replaces the cell's value with its default semantics
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::with_semantics(Some('a'), Relaxed);
+    ///
+    /// assert_eq!(cell.replace_default(Some('b')), Some('a'));
+    /// assert_eq!(cell.take_default(), Some('b'))
+    /// ```
+    #[inline]
+    #[must_use = "use `.set_default()` if you don't need the old value"]
+    pub fn replace_default(&self, slot: Option<T>) -> Option<T> {
+        self.cell.replace(slot, self.order)
+    }
+
+    /// Determines whether the cell is empty with its default semantics
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::<u8>::with_semantics(None, Relaxed);
+    ///
+    /// assert!(cell.is_empty_default())
+    /// ```
+    #[inline]
+    pub fn is_empty_default(&self) -> bool {
+        self.cell.is_empty(self.order)
+    }
+
+    /// Returns the cell's default semantics
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Ordered};
+    ///
+    /// let cell = PtrCell::<u8>::with_semantics(None, Ordered);
+    ///
+    /// assert_eq!(cell.semantics(), Ordered)
+    /// ```
+    #[inline]
+    pub const fn semantics(&self) -> Semantics {
+        self.order
+    }
+
+    /// Changes the cell's default semantics
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::{Coupled, Relaxed}};
+    ///
+    /// let mut cell = PtrCell::<u8>::with_semantics(None, Coupled);
+    /// cell.set_semantics(Relaxed);
+    ///
+    /// assert_eq!(cell.semantics(), Relaxed)
+    /// ```
+    #[inline]
+    pub const fn set_semantics(&mut self, order: Semantics) {
+        self.order = order;
+    }
+
+    /// Unwraps the underlying cell, forgetting the default semantics
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::with_semantics(Some(7), Relaxed).into_cell();
+    ///
+    /// assert_eq!(cell.into_inner(), Some(7))
+    /// ```
+    #[inline]
+    pub fn into_cell(self) -> PtrCell<T> {
+        self.cell
+    }
+
+    /// Wraps a cell, using `order` as its default semantics
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, SemanticPtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = SemanticPtrCell::new(PtrCell::from(0xFA), Relaxed);
+    ///
+    /// assert_eq!(cell.take_default(), Some(0xFA))
+    /// ```
+    #[inline]
+    pub const fn new(cell: PtrCell<T>, order: Semantics) -> Self {
+        Self { cell, order }
+    }
+}
+
+impl<T> PtrCell<T> {
+    /// Constructs a cell that uses `order` as its default semantics
+    ///
+    /// See [`SemanticPtrCell`] for the methods that use them
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    ///
+    /// let cell = PtrCell::with_semantics(Some(0xFAA), Coupled);
+    ///
+    /// assert_eq!(cell.take_default(), Some(0xFAA))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_semantics(slot: Option<T>, order: Semantics) -> SemanticPtrCell<T> {
+        SemanticPtrCell::new(Self::new(slot), order)
+    }
+}
+
+impl<T> core::fmt::Debug for SemanticPtrCell<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("SemanticPtrCell")
+            .field("cell", &self.cell)
+            .field("order", &self.order)
+            .finish()
+    }
+}
+
+impl<T> Default for SemanticPtrCell<T> {
+    /// Constructs an empty cell with the default semantics
+    #[inline]
+    fn default() -> Self {
+        Self::new(PtrCell::default(), Semantics::default())
+    }
+}
+
+impl<T> core::ops::Deref for SemanticPtrCell<T> {
+    type Target = PtrCell<T>;
+
+    #[inline]
+    fn deref(&self) -> &PtrCell<T> {
+        &self.cell
+    }
+}
+
+impl<T> core::ops::DerefMut for SemanticPtrCell<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut PtrCell<T> {
+        &mut self.cell
+    }
+}
@@ -0,0 +1,304 @@
+//! Thread-safe cell for copyable values guarded by a sequence lock
+
+use crate::{fence, AtomicUsize};
+use core::{cell::UnsafeCell, mem::MaybeUninit, sync::atomic::Ordering};
+
+/// Thread-safe cell that stores a [`Copy`] value inline under a sequence counter
+///
+/// This is the read-heavy complement to [`PtrCell`](crate::PtrCell). Instead of moving values in
+/// and out, readers copy the value out optimistically and retry if a writer was active at the same
+/// time. Reads are lock-free rather than wait-free: they never write to shared memory, so many
+/// threads can read at once without contending, but a steady stream of writes keeps them retrying.
+/// Writers briefly lock each other out
+///
+/// The cell doesn't accept [`Semantics`](crate::Semantics). Every write is visible to the reads
+/// that start after it, as if with [`Coupled`](crate::Semantics::Coupled) semantics
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::SeqCell;
+///
+/// let position = SeqCell::new((0.0, 0.0));
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         for step in 1..=100 {
+///             let step = f64::from(step);
+///             position.set((step, -step));
+///         }
+///     });
+///
+///     for _ in 0..100 {
+///         let (x, y) = position.get();
+///         assert_eq!(x, -y)
+///     }
+/// })
+/// ```
+pub struct SeqCell<T> {
+    /// Version of the value
+    ///
+    /// #### Invariants
+    ///
+    /// - **If odd**: A writer is modifying the value
+    /// - **Otherwise**: The value is initialized and only read
+    sequence: AtomicUsize,
+
+    /// The stored value
+    value: UnsafeCell<T>,
+}
+
+// Values are copied between threads, never shared
+unsafe impl<T: Copy + Send> Send for SeqCell<T> {}
+unsafe impl<T: Copy + Send> Sync for SeqCell<T> {}
+
+impl<T: Copy> SeqCell<T> {
+    /// Returns a copy of the cell's value
+    ///
+    /// Retries while a writer is active, so it's lock-free but not wait-free
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::SeqCell;
+    ///
+    /// let cell = SeqCell::new(0x81D);
+    ///
+    /// assert_eq!(cell.get(), 0x81D);
+    /// assert_eq!(cell.get(), 0x81D)
+    /// ```
+    pub fn get(&self) -> T {
+        loop {
+            let before = self.sequence.load(Ordering::Acquire);
+
+            if before & 1 == 1 {
+                core::hint::spin_loop();
+                continue;
+            }
+
+            // A writer may race with this read. The copy is only trusted if the sequence didn't
+            // change in the meantime
+            let value = self.read_racy();
+
+            fence(Ordering::Acquire);
+
+            if self.sequence.load(Ordering::Relaxed) == before {
+                break unsafe { value.assume_init() };
+            }
+        }
+    }
+
+    /// Overwrites the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::SeqCell;
+    ///
+    /// let cell = SeqCell::new(false);
+    /// cell.set(true);
+    ///
+    /// assert!(cell.get())
+    /// ```
+    #[inline]
+    pub fn set(&self, value: T) {
+        let _ = self.replace(value);
+    }
+
+    /// Overwrites the cell's value, returning the previous one
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::SeqCell;
+    ///
+    /// let cell = SeqCell::new('a');
+    ///
+    /// assert_eq!(cell.replace('b'), 'a');
+    /// assert_eq!(cell.get(), 'b')
+    /// ```
+    #[must_use = "use `.set()` if you don't need the old value"]
+    pub fn replace(&self, value: T) -> T {
+        self.update(|_| value)
+    }
+
+    /// Replaces the cell's value with the result of `f`, returning the previous value
+    ///
+    /// Other writers wait until `f` returns, so keep it short
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::SeqCell;
+    ///
+    /// let counter = SeqCell::new(1_u64);
+    ///
+    /// assert_eq!(counter.update(|count| count + 1), 1);
+    /// assert_eq!(counter.get(), 2)
+    /// ```
+    pub fn update(&self, f: impl FnOnce(T) -> T) -> T {
+        let sequence = self.lock();
+
+        let value = self.value.get();
+        let old = unsafe { value.read_volatile() };
+
+        // Unlocks the cell even if `f` panics
+        let unlock = Unlock {
+            sequence: &self.sequence,
+            next: sequence.wrapping_add(2),
+        };
+
+        unsafe { value.write_volatile(f(old)) };
+        drop(unlock);
+
+        old
+    }
+
+    /// Returns a mutable reference to the value
+    ///
+    /// No synchronization is needed, as the cell is borrowed exclusively
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::SeqCell;
+    ///
+    /// let mut cell = SeqCell::new(1);
+    /// *cell.get_mut() += 1;
+    ///
+    /// assert_eq!(cell.get(), 2)
+    /// ```
+    #[inline]
+    pub const fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Unwraps the value
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::SeqCell;
+    ///
+    /// let cell = SeqCell::new(7);
+    ///
+    /// assert_eq!(cell.into_inner(), 7)
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    /// Constructs a cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::SeqCell;
+    ///
+    /// static CELL: SeqCell<u16> = SeqCell::new(0xFAA);
+    ///
+    /// assert_eq!(CELL.get(), 0xFAA)
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(value: T) -> Self {
+        Self {
+            sequence: AtomicUsize::new(0),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Copies the value out while a writer may be overwriting it
+    ///
+    /// This is the race every sequence lock relies on, crossbeam's `AtomicCell` included. The copy
+    /// may be torn, but it's only used once the sequence proves that no writer was active. Copying
+    /// through atomic bytes instead would read the uninitialized padding of some values, which is
+    /// undefined behavior as well
+    #[cfg(not(miri))]
+    #[inline]
+    fn read_racy(&self) -> MaybeUninit<T> {
+        unsafe { self.value.get().cast::<MaybeUninit<T>>().read_volatile() }
+    }
+
+    /// Copies the value out under the writers' lock
+    ///
+    /// Miri reports the race of the optimistic copy even though the copy is discarded, so reads
+    /// take the lock instead. The sequence is restored unchanged, as nothing was written
+    #[cfg(miri)]
+    fn read_racy(&self) -> MaybeUninit<T> {
+        let sequence = self.lock();
+        let value = unsafe { self.value.get().cast::<MaybeUninit<T>>().read() };
+        self.sequence.store(sequence, Ordering::Release);
+
+        value
+    }
+
+    /// Spins until the sequence is made odd by this thread. Returns the even sequence before that
+    fn lock(&self) -> usize {
+        let mut sequence = self.sequence.load(Ordering::Relaxed);
+
+        loop {
+            if sequence & 1 == 0 {
+                let locking = self.sequence.compare_exchange_weak(
+                    sequence,
+                    sequence | 1,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                );
+
+                match locking {
+                    Ok(_) => break,
+                    Err(current) => sequence = current,
+                }
+            } else {
+                core::hint::spin_loop();
+                sequence = self.sequence.load(Ordering::Relaxed);
+            }
+        }
+
+        // Keeps the writes to the value from being seen before the odd sequence
+        fence(Ordering::Release);
+
+        sequence
+    }
+}
+
+impl<T: Copy + core::fmt::Debug> core::fmt::Debug for SeqCell<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("SeqCell")
+            .field("value", &self.get())
+            .finish()
+    }
+}
+
+impl<T: Copy + Default> Default for SeqCell<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Copy> From<T> for SeqCell<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Makes the sequence even again when dropped, publishing the new value
+struct Unlock<'a> {
+    /// The locked sequence
+    sequence: &'a AtomicUsize,
+
+    /// The even sequence to publish
+    next: usize,
+}
+
+impl Drop for Unlock<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.sequence.store(self.next, Ordering::Release);
+    }
+}
@@ -0,0 +1,119 @@
+//! Async-signal-safe subset of the [`PtrCell`] API
+//!
+//! The functions in this module may be called from POSIX signal handlers. Each one compiles down
+//! to a single atomic operation on the cell's pointer: it never allocates or frees memory, takes
+//! locks, panics, or reports to the `stats`, `tracing`, and `debug-track` features
+//!
+//! Values can't be moved in or out of a cell without the allocator, so a handler works with
+//! pointers. Leak the value ahead of time with [`heap_leak`](PtrCell::heap_leak), and reclaim the
+//! pointers the handler produces with [`heap_reclaim`](PtrCell::heap_reclaim) outside of it
+//!
+//! The guarantee relies on the atomics backend being lock-free. That always holds for the default
+//! backend. With the `portable-atomic` feature, it holds on targets where
+//! [`AtomicPtr::is_lock_free`][1] returns `true`. The module isn't available with the
+//! `critical-section` feature, whose critical sections may be guarded by a lock
+//!
+//! # Usage
+//!
+#![doc = doctest!()]
+//! use ptr_cell::{signal_safe, PtrCell, Semantics::Coupled};
+//!
+//! static PENDING: PtrCell<[u8; 64]> = PtrCell::empty();
+//!
+//! // Prepared outside of the handler
+//! let report = PtrCell::heap_leak(Some([0; 64]));
+//!
+//! // Inside the handler
+//! let replaced = signal_safe::take_ptr(&PENDING, Coupled);
+//! unsafe { signal_safe::set_ptr(&PENDING, report, Coupled) };
+//!
+//! // Back outside of the handler
+//! drop(unsafe { PtrCell::heap_reclaim(replaced) });
+//! assert_eq!(PENDING.take(Coupled), Some([0; 64]))
+//! ```
+//!
+//! [1]: https://docs.rs/portable-atomic/latest/portable_atomic/struct.AtomicPtr.html#method.is_lock_free
+
+use crate::{PtrCell, Semantics};
+
+/// Takes out the cell's pointer
+///
+/// Same as [`PtrCell::take_ptr`]
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{signal_safe, PtrCell, Semantics::Relaxed};
+///
+/// let cell = PtrCell::<u8>::default();
+///
+/// assert!(signal_safe::take_ptr(&cell, Relaxed).is_null())
+/// ```
+#[inline]
+pub fn take_ptr<T>(cell: &PtrCell<T>, order: Semantics) -> *mut T {
+    cell.take_ptr(order)
+}
+
+/// Inserts a pointer into the cell
+///
+/// Same as [`PtrCell::set_ptr`]. The pointer previously in the cell is overwritten, so the cell
+/// should be empty or hold a pointer that's owned elsewhere
+///
+/// # Safety
+///
+/// The pointed-to memory must conform to the [memory layout][1] used by `Box`
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{signal_safe, PtrCell, Semantics::Relaxed};
+///
+/// let cell = PtrCell::default();
+/// let ptr = PtrCell::heap_leak(Some('s'));
+///
+/// unsafe { signal_safe::set_ptr(&cell, ptr, Relaxed) };
+///
+/// assert_eq!(cell.take(Relaxed), Some('s'))
+/// ```
+///
+/// [1]: https://doc.rust-lang.org/std/boxed/index.html#memory-layout
+#[inline]
+pub unsafe fn set_ptr<T>(cell: &PtrCell<T>, ptr: *mut T, order: Semantics) {
+    unsafe { cell.set_ptr(ptr, order) }
+}
+
+/// Returns a pointer to the cell's value
+///
+/// Same as [`PtrCell::get_ptr`]
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{signal_safe, PtrCell, Semantics::Relaxed};
+///
+/// let cell = PtrCell::from(0x5157);
+///
+/// assert_eq!(signal_safe::get_ptr(&cell, Relaxed), cell.get_ptr(Relaxed))
+/// ```
+#[inline]
+pub fn get_ptr<T>(cell: &PtrCell<T>, order: Semantics) -> *mut T {
+    cell.get_ptr(order)
+}
+
+/// Determines whether the cell is empty
+///
+/// Same as [`PtrCell::is_empty`]
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{signal_safe, PtrCell, Semantics::Relaxed};
+///
+/// let cell = PtrCell::<u8>::default();
+///
+/// assert!(signal_safe::is_empty(&cell, Relaxed))
+/// ```
+#[inline]
+pub fn is_empty<T>(cell: &PtrCell<T>, order: Semantics) -> bool {
+    cell.is_empty(order)
+}
@@ -0,0 +1,131 @@
+//! Asynchronous sink that sets a cell's value, enabled by the `async` feature
+
+use crate::{PtrCell, Semantics};
+use alloc::sync::Arc;
+use core::{
+    convert::Infallible,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_sink::Sink;
+
+/// What a [`PtrCellSink`] does when the cell still holds the previous item
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+pub enum SinkPolicy {
+    /// Waits until the consumer has taken the previous item out. No item is ever lost
+    #[default]
+    Wait,
+
+    /// Replaces the previous item right away, dropping it. The consumer only sees the latest item
+    Overwrite,
+}
+
+/// Sink that sets the value of a shared cell
+///
+/// This is the counterpart of [`IntoStream`](crate::IntoStream). Setting a value doesn't notify
+/// anyone, and neither does taking one out. So with the [`Wait`](SinkPolicy::Wait) policy, the
+/// sink asks to be polled again right away while the cell is occupied, yielding to other tasks in
+/// between
+///
+/// Created by [`PtrCell::into_sink`]
+pub struct PtrCellSink<T> {
+    /// The cell to set the values of
+    cell: Arc<PtrCell<T>>,
+
+    /// Semantics of every operation on the cell
+    order: Semantics,
+
+    /// What to do if the cell is occupied
+    policy: SinkPolicy,
+}
+
+impl<T> Sink<T> for PtrCellSink<T> {
+    type Error = Infallible;
+
+    fn poll_ready(self: Pin<&mut Self>, context: &mut Context) -> Poll<Result<(), Infallible>> {
+        if self.policy == SinkPolicy::Overwrite || self.cell.is_empty(self.order) {
+            return Poll::Ready(Ok(()));
+        }
+
+        context.waker().wake_by_ref();
+        Poll::Pending
+    }
+
+    #[inline]
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Infallible> {
+        self.cell.set(Some(item), self.order);
+        Ok(())
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T> core::fmt::Debug for PtrCellSink<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("PtrCellSink")
+            .field("cell", &self.cell)
+            .field("order", &self.order)
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+impl<T> PtrCell<T> {
+    /// Returns a [`Sink`] that sets the cell's value
+    ///
+    /// See [`PtrCellSink`] for the exact behavior
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use futures_util::SinkExt;
+    /// use ptr_cell::{PtrCell, Semantics::Coupled, SinkPolicy};
+    /// use std::sync::Arc;
+    ///
+    /// let cell = Arc::new(PtrCell::default());
+    /// let mut sink = Arc::clone(&cell).into_sink(Coupled, SinkPolicy::Wait);
+    ///
+    /// let consumer = std::thread::spawn(move || {
+    ///     let mut received = Vec::new();
+    ///
+    ///     while received.len() < 3 {
+    ///         match cell.take(Coupled) {
+    ///             Some(item) => received.push(item),
+    ///             None => std::thread::yield_now(),
+    ///         }
+    ///     }
+    ///
+    ///     received
+    /// });
+    ///
+    /// pollster::block_on(async {
+    ///     for item in 1..=3 {
+    ///         let Ok(()) = sink.send(item).await;
+    ///     }
+    /// });
+    ///
+    /// let received = consumer.join().expect("The consumer shouldn't panic");
+    /// assert_eq!(received, [1, 2, 3])
+    /// ```
+    #[inline]
+    pub const fn into_sink(
+        self: Arc<Self>,
+        order: Semantics,
+        policy: SinkPolicy,
+    ) -> PtrCellSink<T> {
+        PtrCellSink {
+            cell: self,
+            order,
+            policy,
+        }
+    }
+}
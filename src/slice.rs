@@ -0,0 +1,423 @@
+//! Thread-safe cell for slices and strings stored in a single allocation
+
+use crate::{AtomicPtr, Semantics};
+use alloc::{string::String, vec::Vec};
+use core::{alloc::Layout, marker::PhantomData, ptr::NonNull};
+
+/// Dynamically sized type that a [`ThinBox`] can hold: either `[T]` or [`str`]
+///
+/// Both are stored as their length followed by their items. This trait is sealed and can't be
+/// implemented outside of the crate
+pub trait Slice: private::Sealed {
+    /// Type of the items, like `u8` for [`str`]
+    type Item;
+
+    /// Reinterprets items as a slice
+    ///
+    /// # Safety
+    ///
+    /// The items must be valid for `Self`, like UTF-8 for [`str`]
+    #[doc(hidden)]
+    unsafe fn from_items(items: &[Self::Item]) -> &Self;
+
+    /// Reinterprets items as a mutable slice
+    ///
+    /// # Safety
+    ///
+    /// The items must be valid for `Self`, like UTF-8 for [`str`]
+    #[doc(hidden)]
+    unsafe fn from_items_mut(items: &mut [Self::Item]) -> &mut Self;
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl<T> Sealed for [T] {}
+
+    impl Sealed for str {}
+}
+
+impl<T> Slice for [T] {
+    type Item = T;
+
+    #[inline]
+    unsafe fn from_items(items: &[T]) -> &Self {
+        items
+    }
+
+    #[inline]
+    unsafe fn from_items_mut(items: &mut [T]) -> &mut Self {
+        items
+    }
+}
+
+impl Slice for str {
+    type Item = u8;
+
+    #[inline]
+    unsafe fn from_items(items: &[u8]) -> &Self {
+        unsafe { core::str::from_utf8_unchecked(items) }
+    }
+
+    #[inline]
+    unsafe fn from_items_mut(items: &mut [u8]) -> &mut Self {
+        unsafe { core::str::from_utf8_unchecked_mut(items) }
+    }
+}
+
+/// Owned slice or string stored as its length followed by its items, in a single allocation
+///
+/// Unlike a `Box<[T]>`, which is a pointer and a length, a thin box is a single pointer. That's
+/// what lets a [`SlicePtrCell`] swap it atomically, without boxing the box
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::ThinBox;
+///
+/// let message = ThinBox::<str>::from("disk almost full");
+/// assert_eq!(&*message, "disk almost full");
+///
+/// let mut samples = ThinBox::<[u16]>::from(&[3, 1, 2][..]);
+/// samples.sort();
+/// assert_eq!(&*samples, [1, 2, 3])
+/// ```
+pub struct ThinBox<S: ?Sized + Slice> {
+    /// The length, followed by the items
+    ///
+    /// #### Invariants
+    ///
+    /// - Must point to an allocation with the layout returned by [`layout`] for the stored length
+    /// - The items must be initialized and valid for `S`
+    header: NonNull<usize>,
+
+    /// Marks the box as the owner of the items
+    owns: PhantomData<S::Item>,
+}
+
+// The box owns its items like a `Box<[T]>` does
+unsafe impl<S: ?Sized + Slice> Send for ThinBox<S> where S::Item: Send {}
+unsafe impl<S: ?Sized + Slice> Sync for ThinBox<S> where S::Item: Sync {}
+
+impl<S: ?Sized + Slice> ThinBox<S> {
+    /// Moves `items` into a new allocation along with their length
+    ///
+    /// # Safety
+    ///
+    /// The items must be valid for `S`. They're moved out of, so they must not be used or dropped
+    /// afterwards
+    unsafe fn from_items(items: *const [S::Item]) -> Self {
+        let len = items.len();
+        let (layout, offset) = layout::<S::Item>(len);
+
+        // The layout starts with the length, so it's aligned for it
+        #[allow(clippy::cast_ptr_alignment)]
+        let header = unsafe { alloc::alloc::alloc(layout) }.cast::<usize>();
+        let Some(header) = NonNull::new(header) else {
+            alloc::alloc::handle_alloc_error(layout)
+        };
+
+        unsafe {
+            header.as_ptr().write(len);
+
+            let data = header.as_ptr().cast::<u8>().add(offset).cast::<S::Item>();
+            core::ptr::copy_nonoverlapping(items.cast::<S::Item>(), data, len);
+        }
+
+        Self {
+            header,
+            owns: PhantomData,
+        }
+    }
+
+    /// Returns the number of items
+    const fn len(&self) -> usize {
+        unsafe { self.header.as_ptr().read() }
+    }
+
+    /// Returns a pointer to the first item
+    fn data(&self) -> *mut S::Item {
+        let (_, offset) = layout::<S::Item>(self.len());
+
+        unsafe { self.header.as_ptr().cast::<u8>().add(offset).cast() }
+    }
+
+    /// Gives up ownership of the allocation, returning a pointer to it
+    pub(crate) const fn into_raw(self) -> *mut usize {
+        let header = self.header.as_ptr();
+        core::mem::forget(self);
+
+        header
+    }
+
+    /// Takes ownership of an allocation given up by [`into_raw`](Self::into_raw)
+    ///
+    /// # Safety
+    ///
+    /// `header` must have come from [`into_raw`](Self::into_raw) of a box of the same type and
+    /// must not be used afterwards
+    pub(crate) const unsafe fn from_raw(header: NonNull<usize>) -> Self {
+        Self {
+            header,
+            owns: PhantomData,
+        }
+    }
+}
+
+impl<S: ?Sized + Slice> core::ops::Deref for ThinBox<S> {
+    type Target = S;
+
+    #[inline]
+    fn deref(&self) -> &S {
+        unsafe { S::from_items(core::slice::from_raw_parts(self.data(), self.len())) }
+    }
+}
+
+impl<S: ?Sized + Slice> core::ops::DerefMut for ThinBox<S> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut S {
+        unsafe { S::from_items_mut(core::slice::from_raw_parts_mut(self.data(), self.len())) }
+    }
+}
+
+impl<S: ?Sized + Slice + core::fmt::Debug> core::fmt::Debug for ThinBox<S> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        (**self).fmt(formatter)
+    }
+}
+
+impl<S: ?Sized + Slice> Drop for ThinBox<S> {
+    fn drop(&mut self) {
+        /// Frees the allocation even if dropping an item panics
+        struct Deallocate(*mut u8, Layout);
+
+        impl Drop for Deallocate {
+            fn drop(&mut self) {
+                unsafe { alloc::alloc::dealloc(self.0, self.1) };
+            }
+        }
+
+        let len = self.len();
+        let (layout, _) = layout::<S::Item>(len);
+
+        let _deallocate = Deallocate(self.header.as_ptr().cast(), layout);
+
+        let items = core::ptr::slice_from_raw_parts_mut(self.data(), len);
+        unsafe { core::ptr::drop_in_place(items) };
+    }
+}
+
+impl<T> From<Vec<T>> for ThinBox<[T]> {
+    /// Moves the items of `vec` into a thin box
+    fn from(mut vec: Vec<T>) -> Self {
+        let items = core::ptr::slice_from_raw_parts(vec.as_ptr(), vec.len());
+        let thin = unsafe { Self::from_items(items) };
+
+        // The items were moved out
+        unsafe { vec.set_len(0) };
+
+        thin
+    }
+}
+
+impl<T: Copy> From<&[T]> for ThinBox<[T]> {
+    /// Copies the items of `slice` into a thin box
+    #[inline]
+    fn from(slice: &[T]) -> Self {
+        unsafe { Self::from_items(slice) }
+    }
+}
+
+impl From<&str> for ThinBox<str> {
+    /// Copies `string` into a thin box
+    #[inline]
+    fn from(string: &str) -> Self {
+        unsafe { Self::from_items(string.as_bytes()) }
+    }
+}
+
+impl From<String> for ThinBox<str> {
+    /// Copies `string` into a thin box
+    #[inline]
+    fn from(string: String) -> Self {
+        Self::from(string.as_str())
+    }
+}
+
+/// Returns the layout of a thin box with `len` items of type `T`, along with the offset of the
+/// first item
+fn layout<T>(len: usize) -> (Layout, usize) {
+    let layout = Layout::array::<T>(len).and_then(|items| Layout::new::<usize>().extend(items));
+
+    let Ok((layout, offset)) = layout else {
+        panic!("A thin box of {len} items would be too large")
+    };
+
+    (layout.pad_to_align(), offset)
+}
+
+/// Thread-safe cell for slices and strings, like `PtrCell<[T]>` and `PtrCell<str>` would be
+///
+/// Values are held in [`ThinBox`]es, which keep the length in the same allocation as the items.
+/// Publishing a variable-length message then takes a single allocation and a single atomic swap
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{Semantics::Coupled, SlicePtrCell};
+///
+/// let last_line: SlicePtrCell<str> = SlicePtrCell::new();
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| last_line.set(Some("connection reset".into()), Coupled));
+/// });
+///
+/// assert_eq!(last_line.take(Coupled).as_deref(), Some("connection reset"))
+/// ```
+pub struct SlicePtrCell<S: ?Sized + Slice> {
+    /// Pointer to the header of the contained box
+    ///
+    /// #### Invariants
+    ///
+    /// - **If non-null**: Must have come from [`ThinBox::into_raw`]
+    value: AtomicPtr<usize>,
+
+    /// Marks the cell as the owner of a box
+    owns: PhantomData<ThinBox<S>>,
+}
+
+// Like in `PtrCell`, the boxes are only moved in and out, and no method hands out a reference
+unsafe impl<S: ?Sized + Slice> Sync for SlicePtrCell<S> where S::Item: Send {}
+
+impl<S: ?Sized + Slice> SlicePtrCell<S> {
+    /// Takes out the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, SlicePtrCell};
+    ///
+    /// let cell = SlicePtrCell::<[u8]>::from(vec![1, 2, 3]);
+    ///
+    /// assert_eq!(cell.take(Relaxed).as_deref(), Some(&[1, 2, 3][..]));
+    /// assert!(cell.take(Relaxed).is_none())
+    /// ```
+    #[inline]
+    pub fn take(&self, order: Semantics) -> Option<ThinBox<S>> {
+        self.replace(None, order)
+    }
+
+    /// Inserts a value into the cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, SlicePtrCell};
+    ///
+    /// let cell = SlicePtrCell::<str>::new();
+    /// cell.set(Some(String::from("ready").into()), Relaxed);
+    ///
+    /// assert!(!cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn set(&self, slot: Option<ThinBox<S>>, order: Semantics) {
+        drop(self.replace(slot, order));
+    }
+
+    /// Replaces the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, SlicePtrCell};
+    ///
+    /// let cell = SlicePtrCell::<str>::from("old");
+    /// let old = cell.replace(Some("new".into()), Relaxed);
+    ///
+    /// assert_eq!(old.as_deref(), Some("old"));
+    /// assert_eq!(cell.take(Relaxed).as_deref(), Some("new"))
+    /// ```
+    #[must_use = "use `.set()` if you don't need the old value"]
+    pub fn replace(&self, slot: Option<ThinBox<S>>, order: Semantics) -> Option<ThinBox<S>> {
+        let new = slot.map_or(core::ptr::null_mut(), ThinBox::into_raw);
+        let old = self.value.swap(new, order.read_write());
+
+        NonNull::new(old).map(|header| unsafe { ThinBox::from_raw(header) })
+    }
+
+    /// Determines whether the cell is empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, SlicePtrCell};
+    ///
+    /// let cell = SlicePtrCell::<[u64]>::new();
+    ///
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_empty(&self, order: Semantics) -> bool {
+        self.value.load(order.read()).is_null()
+    }
+
+    /// Constructs an empty cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, SlicePtrCell};
+    ///
+    /// static STATUS: SlicePtrCell<str> = SlicePtrCell::new();
+    ///
+    /// STATUS.set(Some("booting".into()), Relaxed);
+    /// assert_eq!(STATUS.take(Relaxed).as_deref(), Some("booting"))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            value: AtomicPtr::new(core::ptr::null_mut()),
+            owns: PhantomData,
+        }
+    }
+}
+
+impl<S: ?Sized + Slice> core::fmt::Debug for SlicePtrCell<S> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("SlicePtrCell")
+            .field("value", &self.value)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: ?Sized + Slice> Default for SlicePtrCell<S> {
+    /// Constructs an empty cell
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: ?Sized + Slice> Drop for SlicePtrCell<S> {
+    #[inline]
+    fn drop(&mut self) {
+        let header = *self.value.get_mut();
+
+        if let Some(header) = NonNull::new(header) {
+            drop(unsafe { ThinBox::<S>::from_raw(header) });
+        }
+    }
+}
+
+impl<S: ?Sized + Slice, V: Into<ThinBox<S>>> From<V> for SlicePtrCell<S> {
+    /// Constructs a cell holding `value`
+    #[inline]
+    fn from(value: V) -> Self {
+        let cell = Self::new();
+        cell.set(Some(value.into()), Semantics::Relaxed);
+
+        cell
+    }
+}
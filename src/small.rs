@@ -0,0 +1,302 @@
+//! Thread-safe cell for values that fit into an atomic word
+
+use crate::{AtomicUsize, Semantics};
+
+/// Value that can be stored directly in an atomic word
+///
+/// The bits of the value must fit into a [`usize`] with the top bit to spare. [`SmallPtrCell`]
+/// uses the freed-up space to distinguish an empty cell from a full one
+///
+/// # Safety
+///
+/// [`into_bits`](Self::into_bits) must leave the top bit of the word clear, and
+/// [`from_bits`](Self::from_bits) must reconstruct the original value from its output
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{Semantics::Relaxed, Small, SmallPtrCell};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Light {
+///     Red,
+///     Green,
+/// }
+///
+/// unsafe impl Small for Light {
+///     fn into_bits(self) -> usize {
+///         self as usize
+///     }
+///
+///     unsafe fn from_bits(bits: usize) -> Self {
+///         match bits {
+///             0 => Light::Red,
+///             _ => Light::Green,
+///         }
+///     }
+/// }
+///
+/// let cell = SmallPtrCell::from(Light::Green);
+///
+/// assert_eq!(cell.replace(Some(Light::Red), Relaxed), Some(Light::Green))
+/// ```
+pub unsafe trait Small: Copy {
+    /// Converts the value into its bits
+    fn into_bits(self) -> usize;
+
+    /// Reconstructs a value from its bits
+    ///
+    /// # Safety
+    ///
+    /// `bits` must have been returned by [`into_bits`](Self::into_bits)
+    unsafe fn from_bits(bits: usize) -> Self;
+}
+
+/// Implements [`Small`] for integer types that are narrower than [`usize`]
+macro_rules! small_integers {
+    ($($integer:ty as $unsigned:ty),* $(,)?) => {
+        $(
+            unsafe impl Small for $integer {
+                #[inline]
+                #[allow(clippy::cast_sign_loss)]
+                fn into_bits(self) -> usize {
+                    self as $unsigned as usize
+                }
+
+                #[inline]
+                #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+                unsafe fn from_bits(bits: usize) -> Self {
+                    bits as $unsigned as Self
+                }
+            }
+        )*
+    };
+}
+
+small_integers!(u8 as u8, i8 as u8);
+
+#[cfg(not(target_pointer_width = "16"))]
+small_integers!(u16 as u16, i16 as u16);
+
+#[cfg(target_pointer_width = "64")]
+small_integers!(u32 as u32, i32 as u32);
+
+unsafe impl Small for bool {
+    #[inline]
+    fn into_bits(self) -> usize {
+        usize::from(self)
+    }
+
+    #[inline]
+    unsafe fn from_bits(bits: usize) -> Self {
+        bits != 0
+    }
+}
+
+#[cfg(not(target_pointer_width = "16"))]
+unsafe impl Small for char {
+    #[inline]
+    fn into_bits(self) -> usize {
+        u32::from(self) as usize
+    }
+
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    unsafe fn from_bits(bits: usize) -> Self {
+        unsafe { char::from_u32_unchecked(bits as u32) }
+    }
+}
+
+unsafe impl Small for () {
+    #[inline]
+    fn into_bits(self) -> usize {
+        0
+    }
+
+    #[inline]
+    unsafe fn from_bits(_: usize) -> Self {}
+}
+
+/// Thread-safe cell that stores [small](Small) values directly in an atomic word
+///
+/// A sibling of [`PtrCell`](crate::PtrCell) for values like counters, flags, and small enums.
+/// Instead of allocating, the value is packed into the word that would otherwise hold the pointer.
+/// Every operation is a single atomic instruction on most platforms
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{Semantics::Relaxed, SmallPtrCell};
+///
+/// let cell: SmallPtrCell<u16> = 0x81D.into();
+///
+/// assert_eq!(cell.replace(Some(2047), Relaxed), Some(0x81D));
+/// assert_eq!(cell.is_empty(Relaxed), false);
+/// assert_eq!(cell.take(Relaxed), Some(2047))
+/// ```
+#[repr(transparent)]
+pub struct SmallPtrCell<T> {
+    /// The encoded value
+    ///
+    /// #### Invariants
+    ///
+    /// - **If zero**: The cell is empty
+    /// - **Otherwise**: The word is the [bits](Small::into_bits) of the value shifted left by one,
+    ///   with the lowest bit set
+    word: AtomicUsize,
+
+    /// Marks the cell as owning a `T`
+    _value: core::marker::PhantomData<T>,
+}
+
+impl<T: Small> SmallPtrCell<T> {
+    /// Takes out the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, SmallPtrCell};
+    ///
+    /// let cell: SmallPtrCell<u8> = 45.into();
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(45));
+    /// assert_eq!(cell.take(Relaxed), None)
+    /// ```
+    #[inline]
+    pub fn take(&self, order: Semantics) -> Option<T> {
+        self.replace(None, order)
+    }
+
+    /// Inserts a value into the cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, SmallPtrCell};
+    ///
+    /// let cell = SmallPtrCell::default();
+    /// cell.set(Some(true), Relaxed);
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(true))
+    /// ```
+    #[inline]
+    pub fn set(&self, slot: Option<T>, order: Semantics) {
+        self.word.store(encode(slot), order.write());
+    }
+
+    /// Replaces the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, SmallPtrCell};
+    ///
+    /// let cell = SmallPtrCell::from('a');
+    ///
+    /// assert_eq!(cell.replace(Some('b'), Relaxed), Some('a'));
+    /// assert_eq!(cell.take(Relaxed), Some('b'))
+    /// ```
+    #[inline]
+    #[must_use = "use `.set()` if you don't need the old value"]
+    pub fn replace(&self, slot: Option<T>, order: Semantics) -> Option<T> {
+        let word = self.word.swap(encode(slot), order.read_write());
+
+        unsafe { decode(word) }
+    }
+
+    /// Returns a copy of the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, SmallPtrCell};
+    ///
+    /// let cell = SmallPtrCell::from(-7_i8);
+    ///
+    /// assert_eq!(cell.get(Relaxed), Some(-7));
+    /// assert_eq!(cell.get(Relaxed), Some(-7))
+    /// ```
+    #[inline]
+    pub fn get(&self, order: Semantics) -> Option<T> {
+        let word = self.word.load(order.read());
+
+        unsafe { decode(word) }
+    }
+
+    /// Determines whether this cell is empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, SmallPtrCell};
+    ///
+    /// let cell = SmallPtrCell::<()>::default();
+    ///
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_empty(&self, order: Semantics) -> bool {
+        self.word.load(order.read()) == 0
+    }
+
+    /// Constructs a cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, SmallPtrCell};
+    ///
+    /// let cell = SmallPtrCell::new(Some(0xFA));
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(0xFA_u8));
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(slot: Option<T>) -> Self {
+        Self {
+            word: AtomicUsize::new(encode(slot)),
+            _value: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: Small + core::fmt::Debug> core::fmt::Debug for SmallPtrCell<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("SmallPtrCell")
+            .field("value", &self.get(Semantics::Relaxed))
+            .finish()
+    }
+}
+
+impl<T: Small> Default for SmallPtrCell<T> {
+    /// Constructs an empty cell
+    #[inline]
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl<T: Small> From<T> for SmallPtrCell<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(Some(value))
+    }
+}
+
+/// Packs an optional value into a word
+#[inline]
+fn encode<T: Small>(slot: Option<T>) -> usize {
+    slot.map_or(0, |value| value.into_bits() << 1 | 1)
+}
+
+/// Unpacks an optional value from a word
+///
+/// # Safety
+///
+/// `word` must have been returned by [`encode`]
+#[inline]
+unsafe fn decode<T: Small>(word: usize) -> Option<T> {
+    (word != 0).then(|| unsafe { T::from_bits(word >> 1) })
+}
@@ -0,0 +1,68 @@
+//! Consistent takes from multiple cells
+
+use crate::{Exchange, PtrCell};
+
+/// Takes out the values of two cells as a consistent pair
+///
+/// Unlike two separate takes, the returned values were held by the cells at the same point in
+/// time. The value of `b` is read first, then the value of `a` is taken out, and finally the value
+/// of `b` is taken out only if it hasn't changed since it was read. Otherwise, the value of `a` is
+/// put back and the steps are retried
+///
+/// Between the steps, other threads may see `a` empty. If another thread inserts a value into `a`
+/// before the value taken from it is put back, the inserted value is kept and the taken one is
+/// dropped, as if it was replaced right after the attempt
+///
+/// Changes to `b` are detected by comparing its pointers. A value that is taken out of `b`, dropped,
+/// and followed by a new value at the same address in between goes unnoticed
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{PtrCell, Semantics::Coupled};
+///
+/// let command = PtrCell::from("write");
+/// let payload = PtrCell::from(vec![0xFA, 0xAA]);
+///
+/// let (command, payload) = ptr_cell::snapshot2(&command, &payload, Coupled);
+///
+/// assert_eq!(command, Some("write"));
+/// assert_eq!(payload, Some(vec![0xFA, 0xAA]))
+/// ```
+pub fn snapshot2<A, B>(
+    a: &PtrCell<A>,
+    b: &PtrCell<B>,
+    order: impl Into<Exchange>,
+) -> (Option<A>, Option<B>) {
+    let order = order.into();
+
+    loop {
+        let b_ptr = b.get_ptr(order.failure);
+        let a_ptr = a.take_ptr(order.success);
+
+        let taking = b.value.compare_exchange(
+            b_ptr,
+            core::ptr::null_mut(),
+            order.success(),
+            order.failure(),
+        );
+
+        if taking.is_ok() {
+            return unsafe { (PtrCell::heap_reclaim(a_ptr), PtrCell::heap_reclaim(b_ptr)) };
+        }
+
+        // `b` changed after `a` was taken from, so the values may not belong together
+        let restoring = a.value.compare_exchange(
+            core::ptr::null_mut(),
+            a_ptr,
+            order.success(),
+            order.failure(),
+        );
+
+        if restoring.is_err() {
+            drop(unsafe { PtrCell::heap_reclaim(a_ptr) });
+        }
+
+        core::hint::spin_loop();
+    }
+}
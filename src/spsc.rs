@@ -0,0 +1,365 @@
+//! Bounded single-producer, single-consumer ring buffer
+//!
+//! A [`RingBuffer`] is split into a [`Producer`], which pushes values, and a [`Consumer`], which
+//! pops them in the same order. Unlike a single [`PtrCell`](crate::PtrCell), which drops a value
+//! that is overwritten before it's taken, the ring holds up to `N` values and hands new ones back
+//! when it's full. Both ends are wait-free, and values are stored inline, so nothing is allocated
+//!
+//! The ring performs its operations with fixed memory orderings, as values are accessed directly
+//!
+//! # Usage
+//!
+#![doc = doctest!()]
+//! use ptr_cell::spsc::RingBuffer;
+//!
+//! let mut ring: RingBuffer<u32, 8> = RingBuffer::new();
+//! let (producer, consumer) = ring.split();
+//!
+//! std::thread::scope(|scope| {
+//!     scope.spawn(move || {
+//!         for reading in 0..100 {
+//!             while producer.push(reading).is_err() {
+//!                 std::hint::spin_loop();
+//!             }
+//!         }
+//!     });
+//!
+//!     let mut expected = 0;
+//!
+//!     while expected < 100 {
+//!         if let Some(reading) = consumer.pop() {
+//!             assert_eq!(reading, expected);
+//!             expected += 1;
+//!         }
+//!     }
+//! })
+//! ```
+
+use crate::AtomicUsize;
+use core::{
+    cell::{Cell, UnsafeCell},
+    marker::PhantomData,
+    mem::MaybeUninit,
+    sync::atomic::Ordering,
+};
+
+/// Fixed-capacity queue of up to `N` values for one producer and one consumer
+///
+/// See the [module documentation](self) for an overview
+pub struct RingBuffer<T, const N: usize> {
+    /// Storage for the values
+    ///
+    /// #### Invariants
+    ///
+    /// - The slots from `head` up to `tail` are initialized and may only be accessed by the
+    ///   consumer. The other slots may only be accessed by the producer
+    slots: [UnsafeCell<MaybeUninit<T>>; N],
+
+    /// Index of the next value to be popped, below [`WRAP`](Self::WRAP). Only modified by the
+    /// consumer
+    head: AtomicUsize,
+
+    /// Index of the next slot to be pushed to, below [`WRAP`](Self::WRAP). Only modified by the
+    /// producer
+    tail: AtomicUsize,
+}
+
+// Each slot is only ever accessed by one end at a time, so sharing the ring amounts to sending the
+// values between threads
+unsafe impl<T: Send, const N: usize> Send for RingBuffer<T, N> {}
+unsafe impl<T: Send, const N: usize> Sync for RingBuffer<T, N> {}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    /// Slot that holds no value
+    #[allow(clippy::declare_interior_mutable_const)]
+    const VACANT: UnsafeCell<MaybeUninit<T>> = UnsafeCell::new(MaybeUninit::uninit());
+
+    /// Bound that the indices wrap around
+    ///
+    /// Twice the capacity, so that a full ring can be told apart from an empty one. Wrapping the
+    /// indices here rather than at `usize::MAX` keeps consecutive indices on consecutive slots for
+    /// any `N`, not only powers of two
+    const WRAP: usize = 2 * N;
+
+    /// Splits the ring into its producer and consumer
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::spsc::RingBuffer;
+    ///
+    /// let mut ring: RingBuffer<char, 2> = RingBuffer::new();
+    /// let (producer, consumer) = ring.split();
+    ///
+    /// let _ = producer.push('a');
+    ///
+    /// assert_eq!(consumer.pop(), Some('a'))
+    /// ```
+    #[inline]
+    pub const fn split(&mut self) -> (Producer<'_, T, N>, Consumer<'_, T, N>) {
+        let ring = &*self;
+
+        let producer = Producer {
+            ring,
+            unshared: PhantomData,
+        };
+
+        let consumer = Consumer {
+            ring,
+            unshared: PhantomData,
+        };
+
+        (producer, consumer)
+    }
+
+    /// Returns the number of values in the ring
+    ///
+    /// The result may be outdated if the ring is being modified at the same time
+    fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Acquire);
+
+        Self::distance(head, tail)
+    }
+
+    /// Returns the number of indices from `head` up to `tail`
+    #[inline]
+    const fn distance(head: usize, tail: usize) -> usize {
+        if tail >= head {
+            tail - head
+        } else {
+            tail + Self::WRAP - head
+        }
+    }
+
+    /// Returns the index that follows `index`
+    #[inline]
+    const fn advance(index: usize) -> usize {
+        if index + 1 == Self::WRAP {
+            0
+        } else {
+            index + 1
+        }
+    }
+
+    /// Returns the slot at `index`, wrapped around the capacity
+    #[inline]
+    const fn slot(&self, index: usize) -> *mut MaybeUninit<T> {
+        self.slots[index % N].get()
+    }
+
+    /// Constructs an empty ring
+    ///
+    /// # Panics
+    ///
+    /// If `N` is zero
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::spsc::RingBuffer;
+    ///
+    /// let mut ring: RingBuffer<u8, 16> = RingBuffer::new();
+    /// let (_, consumer) = ring.split();
+    ///
+    /// assert!(consumer.is_empty())
+    /// ```
+    #[must_use]
+    pub const fn new() -> Self {
+        assert!(N > 0, "a ring buffer needs room for at least one value");
+
+        Self {
+            slots: [Self::VACANT; N],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<T, const N: usize> core::fmt::Debug for RingBuffer<T, N> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("RingBuffer")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+    /// Constructs an empty ring
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for RingBuffer<T, N> {
+    fn drop(&mut self) {
+        let tail = *self.tail.get_mut();
+        let mut index = *self.head.get_mut();
+
+        while index != tail {
+            unsafe { self.slots[index % N].get_mut().assume_init_drop() };
+
+            index = Self::advance(index);
+        }
+    }
+}
+
+/// The pushing end of a [`RingBuffer`]
+///
+/// There is only one producer per ring, and it can't be shared between threads, though it can be
+/// moved to another one
+///
+/// Created by [`RingBuffer::split`]
+pub struct Producer<'a, T, const N: usize> {
+    /// The ring
+    ring: &'a RingBuffer<T, N>,
+
+    /// Keeps the producer from being shared, as only one thread may push
+    unshared: PhantomData<Cell<()>>,
+}
+
+impl<T, const N: usize> Producer<'_, T, N> {
+    /// Pushes a value to the back of the ring
+    ///
+    /// # Errors
+    ///
+    /// Hands `value` back if the ring is full
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::spsc::RingBuffer;
+    ///
+    /// let mut ring: RingBuffer<u8, 1> = RingBuffer::new();
+    /// let (producer, _) = ring.split();
+    ///
+    /// assert_eq!(producer.push(1), Ok(()));
+    /// assert_eq!(producer.push(2), Err(2))
+    /// ```
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let ring = self.ring;
+
+        let tail = ring.tail.load(Ordering::Relaxed);
+        let head = ring.head.load(Ordering::Acquire);
+
+        if RingBuffer::<T, N>::distance(head, tail) == N {
+            return Err(value);
+        }
+
+        unsafe { (*ring.slot(tail)).write(value) };
+        ring.tail
+            .store(RingBuffer::<T, N>::advance(tail), Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Determines whether the ring is full
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::spsc::RingBuffer;
+    ///
+    /// let mut ring: RingBuffer<u8, 1> = RingBuffer::new();
+    /// let (producer, _) = ring.split();
+    ///
+    /// let _ = producer.push(1);
+    ///
+    /// assert!(producer.is_full())
+    /// ```
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.ring.len() == N
+    }
+}
+
+impl<T, const N: usize> core::fmt::Debug for Producer<'_, T, N> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("Producer")
+            .field("ring", self.ring)
+            .finish()
+    }
+}
+
+/// The popping end of a [`RingBuffer`]
+///
+/// There is only one consumer per ring, and it can't be shared between threads, though it can be
+/// moved to another one
+///
+/// Created by [`RingBuffer::split`]
+pub struct Consumer<'a, T, const N: usize> {
+    /// The ring
+    ring: &'a RingBuffer<T, N>,
+
+    /// Keeps the consumer from being shared, as only one thread may pop
+    unshared: PhantomData<Cell<()>>,
+}
+
+impl<T, const N: usize> Consumer<'_, T, N> {
+    /// Pops the value at the front of the ring
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::spsc::RingBuffer;
+    ///
+    /// let mut ring: RingBuffer<u8, 4> = RingBuffer::new();
+    /// let (producer, consumer) = ring.split();
+    ///
+    /// let _ = producer.push(1);
+    /// let _ = producer.push(2);
+    ///
+    /// assert_eq!(consumer.pop(), Some(1));
+    /// assert_eq!(consumer.pop(), Some(2));
+    /// assert_eq!(consumer.pop(), None)
+    /// ```
+    pub fn pop(&self) -> Option<T> {
+        let ring = self.ring;
+
+        let head = ring.head.load(Ordering::Relaxed);
+        let tail = ring.tail.load(Ordering::Acquire);
+
+        if head == tail {
+            return None;
+        }
+
+        let value = unsafe { (*ring.slot(head)).assume_init_read() };
+        ring.head
+            .store(RingBuffer::<T, N>::advance(head), Ordering::Release);
+
+        Some(value)
+    }
+
+    /// Determines whether the ring is empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::spsc::RingBuffer;
+    ///
+    /// let mut ring: RingBuffer<u8, 4> = RingBuffer::new();
+    /// let (producer, consumer) = ring.split();
+    ///
+    /// assert!(consumer.is_empty());
+    ///
+    /// let _ = producer.push(1);
+    /// assert!(!consumer.is_empty())
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ring.len() == 0
+    }
+}
+
+impl<T, const N: usize> core::fmt::Debug for Consumer<'_, T, N> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("Consumer")
+            .field("ring", self.ring)
+            .finish()
+    }
+}
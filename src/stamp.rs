@@ -0,0 +1,161 @@
+//! Thread-safe monotonic stamp
+
+use crate::{AtomicU64, Exchange, Semantics};
+
+/// Thread-safe stamp that only ever moves forward
+///
+/// This is meant to accompany a [`PtrCell`](crate::PtrCell) as its "last updated" mark in
+/// heartbeat and watchdog patterns. Writers [bump](Self::bump_max) the stamp to the time of their
+/// update, and the stamp keeps the latest one even if writers finish out of order. What a stamp
+/// means, like milliseconds since startup or a tick count, is up to the caller
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{Semantics::Coupled, StampCell};
+///
+/// let updated = StampCell::new(0);
+///
+/// updated.bump_max(1200, Coupled);
+///
+/// // A slower writer from earlier doesn't move the stamp back
+/// updated.bump_max(1100, Coupled);
+///
+/// assert_eq!(updated.get(Coupled), 1200);
+/// assert_eq!(updated.elapsed(1500, Coupled), 300)
+/// ```
+pub struct StampCell {
+    /// The latest stamp
+    stamp: AtomicU64,
+}
+
+impl StampCell {
+    /// Returns the cell's stamp
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, StampCell};
+    ///
+    /// let cell = StampCell::new(7);
+    ///
+    /// assert_eq!(cell.get(Relaxed), 7)
+    /// ```
+    #[inline]
+    pub fn get(&self, order: Semantics) -> u64 {
+        self.stamp.load(order.read())
+    }
+
+    /// Moves the stamp forward to `stamp`, unless it's already there or further
+    ///
+    /// Returns the previous stamp, so the cell was bumped if it's less than `stamp`
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, StampCell};
+    ///
+    /// let cell = StampCell::new(10);
+    ///
+    /// assert_eq!(cell.bump_max(15, Relaxed), 10);
+    /// assert_eq!(cell.bump_max(12, Relaxed), 15);
+    /// assert_eq!(cell.get(Relaxed), 15)
+    /// ```
+    pub fn bump_max(&self, stamp: u64, order: impl Into<Exchange>) -> u64 {
+        let order = order.into();
+        let mut current = self.stamp.load(order.failure());
+
+        while current < stamp {
+            let bumping =
+                self.stamp
+                    .compare_exchange_weak(current, stamp, order.success(), order.failure());
+
+            match bumping {
+                Ok(previous) => return previous,
+                Err(modified) => current = modified,
+            }
+        }
+
+        current
+    }
+
+    /// Returns how far `now` is past the cell's stamp, or zero if it isn't
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, StampCell};
+    ///
+    /// let heartbeat = StampCell::new(100);
+    ///
+    /// assert_eq!(heartbeat.elapsed(160, Relaxed), 60);
+    /// assert_eq!(heartbeat.elapsed(90, Relaxed), 0)
+    /// ```
+    #[inline]
+    pub fn elapsed(&self, now: u64, order: Semantics) -> u64 {
+        now.saturating_sub(self.get(order))
+    }
+
+    /// Returns a mutable reference to the cell's stamp
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, StampCell};
+    ///
+    /// let mut cell = StampCell::new(3);
+    /// *cell.get_mut() = 0;
+    ///
+    /// assert_eq!(cell.get(Relaxed), 0)
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut u64 {
+        self.stamp.get_mut()
+    }
+
+    /// Constructs a cell with the given stamp
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, StampCell};
+    ///
+    /// static LAST_SEEN: StampCell = StampCell::new(0);
+    ///
+    /// LAST_SEEN.bump_max(42, Relaxed);
+    ///
+    /// assert_eq!(LAST_SEEN.get(Relaxed), 42)
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(stamp: u64) -> Self {
+        Self {
+            stamp: AtomicU64::new(stamp),
+        }
+    }
+}
+
+impl core::fmt::Debug for StampCell {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("StampCell")
+            .field("stamp", &self.stamp)
+            .finish()
+    }
+}
+
+impl Default for StampCell {
+    /// Constructs a cell with the stamp at zero
+    #[inline]
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl From<u64> for StampCell {
+    /// Constructs a cell with the given stamp
+    #[inline]
+    fn from(stamp: u64) -> Self {
+        Self::new(stamp)
+    }
+}
@@ -0,0 +1,450 @@
+//! Thread-safe cell with sentinel states
+
+use crate::{PtrCell, Semantics};
+use core::{convert::Infallible, marker::PhantomData};
+
+/// Number of bytes whose addresses mark sentinel states. The first one marks a locked cell
+const SLOTS: usize = 8;
+
+/// Bytes whose addresses mark sentinel states. No value can ever be allocated there
+static SENTINELS: [u8; SLOTS] = [0; SLOTS];
+
+/// Returns the pointer that marks the sentinel state at `index`
+#[inline]
+fn sentinel_at<T>(index: usize) -> *mut T {
+    core::ptr::addr_of!(SENTINELS[index]).cast_mut().cast()
+}
+
+/// Returns the pointer that marks a locked cell
+#[inline]
+fn locked<T>() -> *mut T {
+    sentinel_at(0)
+}
+
+/// Returns the pointer that marks `state`
+fn sentinel<T, S: Sentinel>(state: S) -> *mut T {
+    const { assert!(S::ALL.len() < SLOTS, "too many sentinel states") };
+
+    let index = S::ALL
+        .iter()
+        .position(|&listed| listed == state)
+        .expect("every state should be listed in `Sentinel::ALL`");
+
+    sentinel_at(index + 1)
+}
+
+/// Determines the state that `ptr` represents
+fn decode<T, S: Sentinel>(ptr: *mut T) -> State<S> {
+    if ptr.is_null() {
+        return State::Empty;
+    }
+
+    if ptr == locked() {
+        return State::Locked;
+    }
+
+    S::ALL
+        .iter()
+        .enumerate()
+        .find(|&(index, _)| ptr == sentinel_at(index + 1))
+        .map_or(State::Occupied, |(_, &state)| State::Sentinel(state))
+}
+
+/// Sentinel states of a [`StateCell`]
+///
+/// Usually implemented for a field-less enum. Each state is stored as a reserved pointer in place
+/// of the cell's value, so there can be at most 7 of them
+///
+/// # Usage
+///
+/// ```rust
+/// use ptr_cell::Sentinel;
+///
+/// #[derive(PartialEq, Eq, Clone, Copy)]
+/// enum Connection {
+///     Closed,
+///     Poisoned,
+/// }
+///
+/// impl Sentinel for Connection {
+///     const ALL: &'static [Self] = &[Self::Closed, Self::Poisoned];
+/// }
+/// ```
+pub trait Sentinel: Copy + Eq + 'static {
+    /// Every state. A state that isn't listed here can't be stored in a cell
+    const ALL: &'static [Self];
+}
+
+impl Sentinel for Infallible {
+    /// No sentinel states, for cells that only need to be locked
+    const ALL: &'static [Self] = &[];
+}
+
+/// State of a [`StateCell`]
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum State<S> {
+    /// The cell holds no value
+    Empty,
+
+    /// The cell holds a value
+    Occupied,
+
+    /// The cell's value has been taken out by [`try_lock_take`](StateCell::try_lock_take), and
+    /// the cell waits for [`store_and_unlock`](StateCell::store_and_unlock)
+    Locked,
+
+    /// The cell is in one of the user-defined sentinel states
+    Sentinel(S),
+}
+
+/// Thread-safe cell with user-defined sentinel states
+///
+/// Next to holding a value or nothing, the cell can be locked, or be in one of the states of `S`.
+/// Without `S`, the cell can only be locked. Every state is a reserved pointer stored in place of
+/// the value, and transitions between states are single compare-exchange operations. This formalizes the sentinel pointer tricks that are
+/// otherwise built on top of [`PtrCell`] by hand, like the closed state of
+/// [`ClosablePtrCell`](crate::ClosablePtrCell)
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{Semantics::Coupled, Sentinel, State, StateCell};
+///
+/// #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// enum Phase {
+///     Closed,
+/// }
+///
+/// impl Sentinel for Phase {
+///     const ALL: &'static [Self] = &[Self::Closed];
+/// }
+///
+/// let counter = StateCell::<u32, Phase>::new();
+///
+/// let count = counter.try_lock_take(Coupled).expect("The cell should be unlocked");
+/// assert_eq!(counter.state(Coupled), State::Locked);
+///
+/// counter.store_and_unlock(Some(count.unwrap_or(0) + 1), Coupled);
+/// assert_eq!(counter.enter(Phase::Closed, Coupled), Ok(Some(1)));
+///
+/// assert_eq!(counter.try_lock_take(Coupled), Err(State::Sentinel(Phase::Closed)))
+/// ```
+pub struct StateCell<T, S: Sentinel = Infallible> {
+    /// The cell. Holds a pointer returned by `locked` or `sentinel` in the matching states
+    cell: PtrCell<T>,
+
+    /// The sentinel states
+    states: PhantomData<S>,
+}
+
+impl<T, S: Sentinel> StateCell<T, S> {
+    /// Takes out the cell's value and locks the cell
+    ///
+    /// The cell stays locked until [`store_and_unlock`](Self::store_and_unlock) or
+    /// [`unlock_into`](Self::unlock_into) is called. Meanwhile, other threads can't lock it or
+    /// move it into a sentinel state
+    ///
+    /// # Errors
+    ///
+    /// Returns the cell's state if it's locked or in a sentinel state
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, State, StateCell};
+    ///
+    /// let cell = StateCell::<char>::new();
+    ///
+    /// assert_eq!(cell.try_lock_take(Relaxed), Ok(None));
+    /// cell.store_and_unlock(Some('a'), Relaxed);
+    ///
+    /// assert_eq!(cell.try_lock_take(Relaxed), Ok(Some('a')));
+    /// assert_eq!(cell.try_lock_take(Relaxed), Err(State::Locked))
+    /// ```
+    pub fn try_lock_take(&self, order: Semantics) -> Result<Option<T>, State<S>> {
+        let mut current = self.cell.value.load(order.read());
+
+        loop {
+            if let state @ (State::Locked | State::Sentinel(_)) = decode(current) {
+                return Err(state);
+            }
+
+            let locking = self.cell.value.compare_exchange_weak(
+                current,
+                locked(),
+                order.read_write(),
+                order.read(),
+            );
+
+            match locking {
+                Ok(old_ptr) => return Ok(unsafe { PtrCell::heap_reclaim(old_ptr) }),
+                Err(modified) => current = modified,
+            }
+        }
+    }
+
+    /// Inserts a value into a locked cell and unlocks it
+    ///
+    /// # Panics
+    ///
+    /// If the cell isn't locked. The value is dropped first
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, State, StateCell};
+    ///
+    /// let cell = StateCell::<u8>::new();
+    ///
+    /// let _ = cell.try_lock_take(Relaxed);
+    /// cell.store_and_unlock(Some(8), Relaxed);
+    ///
+    /// assert_eq!(cell.state(Relaxed), State::Occupied)
+    /// ```
+    pub fn store_and_unlock(&self, slot: Option<T>, order: Semantics) {
+        let new_ptr = PtrCell::heap_leak(slot);
+
+        if !self.unlock_with(new_ptr, order) {
+            drop(unsafe { PtrCell::heap_reclaim(new_ptr) });
+            panic!("the cell isn't locked")
+        }
+    }
+
+    /// Moves a locked cell into a sentinel state
+    ///
+    /// # Panics
+    ///
+    /// If the cell isn't locked
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, Sentinel, State, StateCell};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    /// enum Phase {
+    ///     Poisoned,
+    /// }
+    ///
+    /// impl Sentinel for Phase {
+    ///     const ALL: &'static [Self] = &[Self::Poisoned];
+    /// }
+    ///
+    /// let cell = StateCell::<String, Phase>::new();
+    ///
+    /// let _ = cell.try_lock_take(Relaxed);
+    /// cell.unlock_into(Phase::Poisoned, Relaxed);
+    ///
+    /// assert_eq!(cell.state(Relaxed), State::Sentinel(Phase::Poisoned))
+    /// ```
+    pub fn unlock_into(&self, state: S, order: Semantics) {
+        assert!(
+            self.unlock_with(sentinel(state), order),
+            "the cell isn't locked"
+        );
+    }
+
+    /// Replaces the lock with `ptr`. Returns whether the cell was locked
+    fn unlock_with(&self, ptr: *mut T, order: Semantics) -> bool {
+        self.cell
+            .value
+            .compare_exchange(locked(), ptr, order.read_write(), order.read())
+            .is_ok()
+    }
+
+    /// Moves an unlocked cell into a sentinel state, returning the value that was left in it
+    ///
+    /// # Errors
+    ///
+    /// Returns the cell's state if it's locked or already in a sentinel state. Use
+    /// [`transition`](Self::transition) to move between sentinel states
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, Sentinel, State, StateCell};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    /// enum Phase {
+    ///     Closed,
+    /// }
+    ///
+    /// impl Sentinel for Phase {
+    ///     const ALL: &'static [Self] = &[Self::Closed];
+    /// }
+    ///
+    /// let cell = StateCell::<u8, Phase>::new();
+    ///
+    /// assert_eq!(cell.enter(Phase::Closed, Relaxed), Ok(None));
+    /// assert_eq!(cell.enter(Phase::Closed, Relaxed), Err(State::Sentinel(Phase::Closed)))
+    /// ```
+    pub fn enter(&self, state: S, order: Semantics) -> Result<Option<T>, State<S>> {
+        let new_ptr = sentinel(state);
+        let mut current = self.cell.value.load(order.read());
+
+        loop {
+            if let state @ (State::Locked | State::Sentinel(_)) = decode(current) {
+                return Err(state);
+            }
+
+            let entering = self.cell.value.compare_exchange_weak(
+                current,
+                new_ptr,
+                order.read_write(),
+                order.read(),
+            );
+
+            match entering {
+                Ok(old_ptr) => return Ok(unsafe { PtrCell::heap_reclaim(old_ptr) }),
+                Err(modified) => current = modified,
+            }
+        }
+    }
+
+    /// Moves the cell from the sentinel state `from` into the sentinel state `to`
+    ///
+    /// # Errors
+    ///
+    /// Returns the cell's state if it isn't `from`
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, Sentinel, State, StateCell};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    /// enum Phase {
+    ///     Closing,
+    ///     Closed,
+    /// }
+    ///
+    /// impl Sentinel for Phase {
+    ///     const ALL: &'static [Self] = &[Self::Closing, Self::Closed];
+    /// }
+    ///
+    /// let cell = StateCell::<u8, Phase>::new();
+    /// let _ = cell.enter(Phase::Closing, Relaxed);
+    ///
+    /// assert_eq!(cell.transition(Phase::Closing, Phase::Closed, Relaxed), Ok(()));
+    /// assert_eq!(
+    ///     cell.transition(Phase::Closing, Phase::Closed, Relaxed),
+    ///     Err(State::Sentinel(Phase::Closed))
+    /// )
+    /// ```
+    pub fn transition(&self, from: S, to: S, order: Semantics) -> Result<(), State<S>> {
+        self.cell
+            .value
+            .compare_exchange(
+                sentinel(from),
+                sentinel(to),
+                order.read_write(),
+                order.read(),
+            )
+            .map(drop)
+            .map_err(decode)
+    }
+
+    /// Moves the cell from the sentinel state `from` back to being empty
+    ///
+    /// # Errors
+    ///
+    /// Returns the cell's state if it isn't `from`
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, Sentinel, State, StateCell};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    /// enum Phase {
+    ///     Paused,
+    /// }
+    ///
+    /// impl Sentinel for Phase {
+    ///     const ALL: &'static [Self] = &[Self::Paused];
+    /// }
+    ///
+    /// let cell = StateCell::<u8, Phase>::new();
+    /// let _ = cell.enter(Phase::Paused, Relaxed);
+    ///
+    /// assert_eq!(cell.leave(Phase::Paused, Relaxed), Ok(()));
+    /// assert_eq!(cell.state(Relaxed), State::Empty)
+    /// ```
+    pub fn leave(&self, from: S, order: Semantics) -> Result<(), State<S>> {
+        self.cell
+            .value
+            .compare_exchange(
+                sentinel(from),
+                core::ptr::null_mut(),
+                order.read_write(),
+                order.read(),
+            )
+            .map(drop)
+            .map_err(decode)
+    }
+
+    /// Returns the cell's state
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, State, StateCell};
+    ///
+    /// let cell = StateCell::<u8>::new();
+    ///
+    /// assert_eq!(cell.state(Relaxed), State::Empty)
+    /// ```
+    #[inline]
+    pub fn state(&self, order: Semantics) -> State<S> {
+        decode(self.cell.get_ptr(order))
+    }
+
+    /// Constructs an empty, unlocked cell
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, State, StateCell};
+    ///
+    /// static SLOT: StateCell<&str> = StateCell::new();
+    ///
+    /// assert_eq!(SLOT.state(Relaxed), State::Empty)
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            cell: PtrCell::empty(),
+            states: PhantomData,
+        }
+    }
+}
+
+impl<T, S: Sentinel + core::fmt::Debug> core::fmt::Debug for StateCell<T, S> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("StateCell")
+            .field("state", &self.state(Semantics::Relaxed))
+            .finish()
+    }
+}
+
+impl<T, S: Sentinel> Default for StateCell<T, S> {
+    /// Constructs an empty, unlocked cell
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S: Sentinel> Drop for StateCell<T, S> {
+    #[inline]
+    fn drop(&mut self) {
+        let ptr = self.cell.value.get_mut();
+
+        // Sentinels must never be reclaimed by the inner cell
+        if !matches!(decode::<T, S>(*ptr), State::Empty | State::Occupied) {
+            *ptr = core::ptr::null_mut();
+        }
+    }
+}
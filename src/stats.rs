@@ -0,0 +1,116 @@
+//! Global operation counters, enabled by the `stats` feature
+
+use crate::{AtomicUsize, PtrCell};
+use core::sync::atomic::Ordering;
+
+/// Counters shared by every cell in the program
+static COUNTERS: Counters = Counters {
+    replaces: AtomicUsize::new(0),
+    takes: AtomicUsize::new(0),
+    failed_exchanges: AtomicUsize::new(0),
+    live_allocations: AtomicUsize::new(0),
+};
+
+/// Live versions of the fields of [`Stats`]
+struct Counters {
+    replaces: AtomicUsize,
+    takes: AtomicUsize,
+    failed_exchanges: AtomicUsize,
+    live_allocations: AtomicUsize,
+}
+
+/// Snapshot of the operations performed on all cells in the program
+///
+/// The counters are shared between cells of every type and are only ever incremented with
+/// [`Relaxed`](Ordering::Relaxed) ordering, so a snapshot taken while other threads are working
+/// may not reflect a single moment in time
+///
+/// Obtained with [`PtrCell::stats`]
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Stats {
+    /// Number of values placed into cells by [`replace`](PtrCell::replace) and the methods built on
+    /// it, like [`set`](PtrCell::set)
+    pub replaces: usize,
+
+    /// Number of calls to [`take`](PtrCell::take)
+    pub takes: usize,
+
+    /// Number of times a [`map_owner`](PtrCell::map_owner)-like method lost a race to another
+    /// thread and had to retry
+    pub failed_exchanges: usize,
+
+    /// Number of values that have been leaked to the heap but not yet reclaimed
+    ///
+    /// Zero-sized values aren't counted, as they're never allocated. Reclaiming pointers that
+    /// weren't leaked by this crate, like ones from [`Box::into_raw`](alloc::boxed::Box::into_raw),
+    /// makes the count wrap around
+    pub live_allocations: usize,
+}
+
+impl PtrCell<()> {
+    /// Returns a snapshot of the operations performed on all cells in the program
+    ///
+    /// The counters are global, so this is callable as `PtrCell::stats()` without specifying the
+    /// cell's type
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let before = PtrCell::stats();
+    ///
+    /// let cell = PtrCell::default();
+    /// cell.set(Some(0x1F_u16), Relaxed);
+    /// let _ = cell.take(Relaxed);
+    ///
+    /// let after = PtrCell::stats();
+    ///
+    /// assert!(after.replaces > before.replaces);
+    /// assert!(after.takes > before.takes)
+    /// ```
+    pub fn stats() -> Stats {
+        Stats {
+            replaces: COUNTERS.replaces.load(Ordering::Relaxed),
+            takes: COUNTERS.takes.load(Ordering::Relaxed),
+            failed_exchanges: COUNTERS.failed_exchanges.load(Ordering::Relaxed),
+            live_allocations: COUNTERS.live_allocations.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Counts a call to `replace`
+#[inline]
+pub(crate) fn replaced() {
+    COUNTERS.replaces.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counts a call to `take`
+#[inline]
+pub(crate) fn taken() {
+    COUNTERS.takes.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counts a lost compare-and-swap race
+#[inline]
+pub(crate) fn exchange_failed() {
+    COUNTERS.failed_exchanges.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counts `ptr` as a live allocation
+///
+/// Null pointers and pointers to zero-sized values aren't counted, as they don't own any memory
+#[inline]
+pub(crate) fn allocated<T>(ptr: *mut T) {
+    if !ptr.is_null() && core::mem::size_of::<T>() != 0 {
+        COUNTERS.live_allocations.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Stops counting `ptr` as a live allocation
+#[inline]
+pub(crate) fn deallocated<T>(ptr: *mut T) {
+    if !ptr.is_null() && core::mem::size_of::<T>() != 0 {
+        COUNTERS.live_allocations.fetch_sub(1, Ordering::Relaxed);
+    }
+}
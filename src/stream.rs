@@ -0,0 +1,96 @@
+//! Asynchronous stream of the values set in a cell, enabled by the `async` feature
+
+use crate::{PtrCell, Semantics};
+use alloc::sync::Arc;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_core::Stream;
+
+/// Stream that takes out a shared cell's value every time one is set
+///
+/// The stream is lossy. Values that are replaced before the stream gets to them are dropped, so it
+/// always yields the latest one. Setting a value doesn't notify anyone, so while the cell is empty
+/// the stream asks to be polled again right away, yielding to other tasks in between. For idle
+/// waiting, use the [`watch`](crate::watch) channel instead
+///
+/// The stream ends once the cell is empty and the stream holds the only reference to it, as
+/// nothing can set a value anymore
+///
+/// Created by [`PtrCell::into_stream`]
+pub struct IntoStream<T> {
+    /// The cell to take the values from
+    cell: Arc<PtrCell<T>>,
+
+    /// Semantics of every take
+    order: Semantics,
+}
+
+impl<T> Stream for IntoStream<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<T>> {
+        let this = self.get_mut();
+
+        if let Some(value) = this.cell.take(this.order) {
+            return Poll::Ready(Some(value));
+        }
+
+        // A value may have been set right before the last other reference was dropped
+        if let Some(cell) = Arc::get_mut(&mut this.cell) {
+            return Poll::Ready(cell.take(this.order));
+        }
+
+        context.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+impl<T> core::fmt::Debug for IntoStream<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("IntoStream")
+            .field("cell", &self.cell)
+            .field("order", &self.order)
+            .finish()
+    }
+}
+
+impl<T> PtrCell<T> {
+    /// Returns a [`Stream`] that yields the cell's values as they are set
+    ///
+    /// See [`IntoStream`] for the exact behavior
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use futures_util::StreamExt;
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    /// use std::sync::Arc;
+    ///
+    /// let reading = Arc::new(PtrCell::default());
+    /// let mut readings = Arc::clone(&reading).into_stream(Coupled);
+    ///
+    /// std::thread::spawn(move || {
+    ///     for value in 1..=100_u16 {
+    ///         reading.set(Some(value), Coupled);
+    ///     }
+    /// });
+    ///
+    /// pollster::block_on(async {
+    ///     let mut last = 0;
+    ///
+    ///     while let Some(value) = readings.next().await {
+    ///         assert!(value > last);
+    ///         last = value
+    ///     }
+    ///
+    ///     assert_eq!(last, 100)
+    /// })
+    /// ```
+    #[inline]
+    pub const fn into_stream(self: Arc<Self>, order: Semantics) -> IntoStream<T> {
+        IntoStream { cell: self, order }
+    }
+}
@@ -0,0 +1,22 @@
+//! Trace events for cell operations, enabled by the `tracing` feature
+
+use crate::PtrCell;
+
+/// Emits a trace-level event for an operation on `cell`
+///
+/// `was_empty` tells whether the cell was empty before the operation
+#[inline]
+pub(crate) fn event<T>(
+    cell: &PtrCell<T>,
+    operation: &'static str,
+    order: impl core::fmt::Debug,
+    was_empty: bool,
+) {
+    tracing::trace!(
+        cell = ?core::ptr::from_ref(cell),
+        operation,
+        order = ?order,
+        was_empty,
+        "ptr_cell operation"
+    );
+}
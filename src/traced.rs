@@ -0,0 +1,172 @@
+//! Replacements that report how they went, for diagnostics
+
+use crate::{Exchange, PtrCell, Semantics};
+
+/// Report of a traced replacement
+///
+/// Returned along with the old value by [`replace_traced`](PtrCell::replace_traced) and
+/// [`rcu_traced`](PtrCell::rcu_traced). Meant for soak tests and other diagnostics that need to
+/// assert on contention, not just on values
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{PtrCell, Semantics::Relaxed};
+///
+/// let cell = PtrCell::from(1);
+///
+/// let (old, info) = cell.replace_traced(Some(2), Relaxed);
+///
+/// assert_eq!(old, Some(1));
+/// assert!(!info.previous.is_null());
+/// assert_eq!(info.retries, 0)
+/// ```
+pub struct ReplaceInfo<T> {
+    /// Pointer that the cell held before the replacement
+    ///
+    /// Null if the cell was empty. The value it pointed to has already been handed back or
+    /// dropped, so the pointer is only good for comparisons
+    pub previous: *mut T,
+
+    /// Number of times the replacement lost a race to another thread and had to start over
+    ///
+    /// Always 0 for replacements that can't lose races, like [`replace`](PtrCell::replace)
+    pub retries: usize,
+}
+
+impl<T> core::fmt::Debug for ReplaceInfo<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("ReplaceInfo")
+            .field("previous", &self.previous)
+            .field("retries", &self.retries)
+            .finish()
+    }
+}
+
+impl<T> Clone for ReplaceInfo<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ReplaceInfo<T> {}
+
+impl<T> PartialEq for ReplaceInfo<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.previous == other.previous && self.retries == other.retries
+    }
+}
+
+impl<T> Eq for ReplaceInfo<T> {}
+
+impl<T> PtrCell<T> {
+    /// Like [`replace`](Self::replace), but also reports the replaced pointer
+    ///
+    /// The value is swapped in a single atomic operation, so the reported retry count is always 0
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let cell = PtrCell::default();
+    ///
+    /// let (old, info) = cell.replace_traced(Some("first"), Relaxed);
+    /// assert_eq!(old, None);
+    /// assert!(info.previous.is_null());
+    ///
+    /// let inserted = cell.get_ptr(Relaxed);
+    ///
+    /// let (old, info) = cell.replace_traced(Some("second"), Relaxed);
+    /// assert_eq!(old, Some("first"));
+    /// assert_eq!(info.previous, inserted)
+    /// ```
+    #[must_use = "use `.set()` if you don't need the old value"]
+    pub fn replace_traced(&self, slot: Option<T>, order: Semantics) -> (Option<T>, ReplaceInfo<T>) {
+        #[cfg(feature = "stats")]
+        crate::stats::replaced();
+
+        let new_leak = Self::heap_leak(slot);
+        let old_leak = self.replace_ptr(new_leak, order);
+
+        #[cfg(feature = "tracing")]
+        crate::trace::event(self, "replace", order, old_leak.is_null());
+
+        let info = ReplaceInfo {
+            previous: old_leak,
+            retries: 0,
+        };
+
+        (unsafe { Self::heap_reclaim(old_leak) }, info)
+    }
+
+    /// Like [`rcu`](Self::rcu), but also reports the replaced pointer and how many times `f` had
+    /// to be called again
+    ///
+    /// Like in `rcu`, the value is always acquired, as `f` reads it
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Coupled};
+    ///
+    /// let cell = PtrCell::from(1);
+    /// let mut calls = 0;
+    ///
+    /// let (old, info) = cell.rcu_traced(
+    ///     |value| {
+    ///         calls += 1;
+    ///
+    ///         // Simulates another thread inserting a value while the first attempt is running
+    ///         if calls == 1 {
+    ///             cell.set(Some(10), Coupled);
+    ///         }
+    ///
+    ///         value.map(|value| value + 1)
+    ///     },
+    ///     Coupled,
+    /// );
+    ///
+    /// assert_eq!(old, Some(10));
+    /// assert_eq!(info.retries, 1);
+    /// assert_eq!(cell.take(Coupled), Some(11))
+    /// ```
+    pub fn rcu_traced<F>(&self, mut f: F, order: impl Into<Exchange>) -> (Option<T>, ReplaceInfo<T>)
+    where
+        F: FnMut(Option<&T>) -> Option<T>,
+    {
+        let order = order.into();
+        let mut retries = 0;
+
+        loop {
+            let current_ptr = self.value.swap(
+                core::ptr::null_mut(),
+                crate::acquiring(order.success.read_write()),
+            );
+            let current = unsafe { Self::heap_reclaim(current_ptr) };
+            let new_ptr = Self::heap_leak(f(current.as_ref()));
+
+            let installing = self.value.compare_exchange(
+                core::ptr::null_mut(),
+                new_ptr,
+                order.success(),
+                order.failure(),
+            );
+
+            if installing.is_ok() {
+                let info = ReplaceInfo {
+                    previous: current_ptr,
+                    retries,
+                };
+
+                break (current, info);
+            }
+
+            unsafe { Self::heap_reclaim(new_ptr) };
+            retries += 1;
+        }
+    }
+}
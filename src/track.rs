@@ -0,0 +1,70 @@
+//! Ownership tracking for leaked pointers, enabled by the `debug-track` feature
+//!
+//! Every pointer that the crate leaks is recorded in a global table, and every pointer that it
+//! reclaims must be found there. This catches double frees and reclaims of foreign pointers as
+//! panics instead of undefined behavior
+
+use crate::AtomicU8;
+use alloc::collections::BTreeSet;
+use core::{cell::UnsafeCell, sync::atomic::Ordering};
+
+/// Addresses of the values that are currently leaked
+static LEAKED: Table = Table {
+    locked: AtomicU8::new(0),
+    addresses: UnsafeCell::new(BTreeSet::new()),
+};
+
+/// Set of addresses guarded by a spinlock
+struct Table {
+    /// Either 0 or 1
+    locked: AtomicU8,
+
+    /// The addresses. Only accessed while `locked` is 1
+    addresses: UnsafeCell<BTreeSet<usize>>,
+}
+
+// The addresses are only ever accessed by the thread that holds the lock
+unsafe impl Sync for Table {}
+
+impl Table {
+    /// Calls `f` with the locked addresses
+    fn with<R>(&self, f: impl FnOnce(&mut BTreeSet<usize>) -> R) -> R {
+        while self.locked.swap(1, Ordering::Acquire) == 1 {
+            core::hint::spin_loop();
+        }
+
+        let result = f(unsafe { &mut *self.addresses.get() });
+        self.locked.store(0, Ordering::Release);
+
+        result
+    }
+}
+
+/// Records `ptr` as leaked
+///
+/// Null pointers and pointers to zero-sized values aren't tracked, as they don't own any memory
+pub(crate) fn record<T>(ptr: *mut T) {
+    if ptr.is_null() || core::mem::size_of::<T>() == 0 {
+        return;
+    }
+
+    LEAKED.with(|addresses| addresses.insert(ptr.addr()));
+}
+
+/// Stops tracking `ptr`, which is about to be reclaimed
+///
+/// # Panics
+///
+/// If `ptr` isn't recorded as leaked
+pub(crate) fn forget<T>(ptr: *mut T) {
+    if ptr.is_null() || core::mem::size_of::<T>() == 0 {
+        return;
+    }
+
+    let leaked = LEAKED.with(|addresses| addresses.remove(&ptr.addr()));
+
+    assert!(
+        leaked,
+        "reclaimed {ptr:p}, which is either reclaimed already or wasn't leaked by `ptr_cell`"
+    );
+}
@@ -0,0 +1,222 @@
+//! Thread-safe cell that numbers its values
+
+use crate::{AtomicU64, PtrCell, Semantics};
+use core::sync::atomic::Ordering;
+
+/// Value stored along with its version
+struct Versioned<T> {
+    /// The value
+    value: T,
+
+    /// Number of values inserted into the cell up to and including this one
+    version: u64,
+}
+
+/// [`PtrCell`] that counts the values inserted into it
+///
+/// Every value gets the next version number when it's inserted, starting from 1, and
+/// [`take_versioned`](Self::take_versioned) returns the version along with the value. A consumer
+/// that remembers the version of the previous value it took can tell how many values were
+/// overwritten before it got to them, like dropped frames in telemetry
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::{Semantics::Coupled, VersionedPtrCell};
+///
+/// let frames = VersionedPtrCell::new();
+///
+/// frames.set(Some("first"), Coupled);
+/// let (first, _) = frames.take_versioned(Coupled);
+///
+/// frames.set(Some("second"), Coupled);
+/// frames.set(Some("third"), Coupled);
+/// let (third, _) = frames.take_versioned(Coupled);
+///
+/// let dropped = third - first - 1;
+/// assert_eq!(dropped, 1)
+/// ```
+pub struct VersionedPtrCell<T> {
+    /// The value and its version
+    cell: PtrCell<Versioned<T>>,
+
+    /// Number of values inserted so far
+    inserted: AtomicU64,
+}
+
+impl<T> VersionedPtrCell<T> {
+    /// Takes out the cell's value along with its version
+    ///
+    /// If the cell is empty, the version of the latest inserted value is returned instead, or 0
+    /// if there was none
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, VersionedPtrCell};
+    ///
+    /// let cell = VersionedPtrCell::new();
+    /// assert_eq!(cell.take_versioned(Relaxed), (0, None));
+    ///
+    /// cell.set(Some('a'), Relaxed);
+    /// cell.set(Some('b'), Relaxed);
+    ///
+    /// assert_eq!(cell.take_versioned(Relaxed), (2, Some('b')));
+    /// assert_eq!(cell.take_versioned(Relaxed), (2, None))
+    /// ```
+    pub fn take_versioned(&self, order: Semantics) -> (u64, Option<T>) {
+        match self.cell.take(order) {
+            Some(Versioned { value, version }) => (version, Some(value)),
+            None => (self.version(order), None),
+        }
+    }
+
+    /// Takes out the cell's value
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, VersionedPtrCell};
+    ///
+    /// let cell = VersionedPtrCell::from(45);
+    ///
+    /// assert_eq!(cell.take(Relaxed), Some(45));
+    /// assert_eq!(cell.take(Relaxed), None)
+    /// ```
+    #[inline]
+    pub fn take(&self, order: Semantics) -> Option<T> {
+        self.take_versioned(order).1
+    }
+
+    /// Inserts a value into the cell under the next version
+    ///
+    /// Inserting [`None`] empties the cell without counting as a new version
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, VersionedPtrCell};
+    ///
+    /// let cell = VersionedPtrCell::new();
+    ///
+    /// cell.set(Some(1776), Relaxed);
+    /// cell.set(None, Relaxed);
+    ///
+    /// assert_eq!(cell.version(Relaxed), 1)
+    /// ```
+    #[inline]
+    pub fn set(&self, slot: Option<T>, order: Semantics) {
+        drop(self.replace(slot, order));
+    }
+
+    /// Replaces the cell's value, inserting the new one under the next version
+    ///
+    /// Inserting [`None`] empties the cell without counting as a new version
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, VersionedPtrCell};
+    ///
+    /// let cell = VersionedPtrCell::from('a');
+    ///
+    /// assert_eq!(cell.replace(Some('b'), Relaxed), Some('a'));
+    /// assert_eq!(cell.take_versioned(Relaxed), (2, Some('b')))
+    /// ```
+    #[must_use = "use `.set()` if you don't need the old value"]
+    pub fn replace(&self, slot: Option<T>, order: Semantics) -> Option<T> {
+        let slot = slot.map(|value| Versioned {
+            value,
+            version: self.inserted.fetch_add(1, order.read_write()) + 1,
+        });
+
+        self.cell
+            .replace(slot, order)
+            .map(|versioned| versioned.value)
+    }
+
+    /// Returns the number of values inserted into the cell so far
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, VersionedPtrCell};
+    ///
+    /// let cell = VersionedPtrCell::new();
+    ///
+    /// for frame in 0..3 {
+    ///     cell.set(Some(frame), Relaxed);
+    /// }
+    ///
+    /// assert_eq!(cell.version(Relaxed), 3)
+    /// ```
+    #[inline]
+    pub fn version(&self, order: Semantics) -> u64 {
+        self.inserted.load(order.read())
+    }
+
+    /// Determines whether the cell is empty
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, VersionedPtrCell};
+    ///
+    /// let cell = VersionedPtrCell::<u8>::new();
+    ///
+    /// assert!(cell.is_empty(Relaxed))
+    /// ```
+    #[inline]
+    pub fn is_empty(&self, order: Semantics) -> bool {
+        self.cell.is_empty(order)
+    }
+
+    /// Constructs an empty cell with no values inserted
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{Semantics::Relaxed, VersionedPtrCell};
+    ///
+    /// static FRAME: VersionedPtrCell<[u8; 64]> = VersionedPtrCell::new();
+    ///
+    /// assert_eq!(FRAME.version(Relaxed), 0)
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            cell: PtrCell::empty(),
+            inserted: AtomicU64::new(0),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for VersionedPtrCell<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("VersionedPtrCell")
+            .field("cell", &self.cell)
+            .field("version", &self.inserted.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl<T> Default for VersionedPtrCell<T> {
+    /// Constructs an empty cell with no values inserted
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<T> for VersionedPtrCell<T> {
+    /// Constructs a cell holding `value` as its first version
+    #[inline]
+    fn from(value: T) -> Self {
+        let cell = Self::new();
+        cell.set(Some(value), Semantics::Relaxed);
+
+        cell
+    }
+}
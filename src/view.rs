@@ -0,0 +1,80 @@
+//! Views of exclusively borrowed cells
+
+use crate::PtrCell;
+
+/// Mutable view of an exclusively borrowed cell as an [`Option`]
+///
+/// The value is moved out of the cell when the view is created and moved back in when it's
+/// dropped. In between, the view dereferences to an `Option<T>`, so all of its methods are
+/// available. No atomic operations are performed
+///
+/// Created by [`PtrCell::as_option_mut`]
+pub struct OptionViewMut<'a, T> {
+    /// The viewed cell. Empty while the view exists
+    cell: &'a mut PtrCell<T>,
+
+    /// The value moved out of the cell
+    slot: Option<T>,
+}
+
+impl<T> core::ops::Deref for OptionViewMut<'_, T> {
+    type Target = Option<T>;
+
+    #[inline]
+    fn deref(&self) -> &Option<T> {
+        &self.slot
+    }
+}
+
+impl<T> core::ops::DerefMut for OptionViewMut<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Option<T> {
+        &mut self.slot
+    }
+}
+
+impl<T> Drop for OptionViewMut<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        *self.cell.value.get_mut() = PtrCell::heap_leak(self.slot.take());
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for OptionViewMut<'_, T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("OptionViewMut")
+            .field("slot", &self.slot)
+            .finish()
+    }
+}
+
+impl<T> PtrCell<T> {
+    /// Views the cell's value as a mutable [`Option`]
+    ///
+    /// Changes made through the view are written back to the cell when it's dropped. Moving the
+    /// value in and out reallocates it, so prefer [`get_mut`](Self::get_mut) when only the value
+    /// itself needs to change
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::{PtrCell, Semantics::Relaxed};
+    ///
+    /// let mut cell = PtrCell::from(5);
+    ///
+    /// let mut view = cell.as_option_mut();
+    /// *view = view.filter(|value| value % 2 == 0).or(Some(0));
+    /// drop(view);
+    ///
+    /// assert_eq!(cell.as_option_mut().replace(2), Some(0));
+    /// assert_eq!(cell.take(Relaxed), Some(2))
+    /// ```
+    #[inline]
+    pub fn as_option_mut(&mut self) -> OptionViewMut<'_, T> {
+        let leak = core::mem::replace(self.value.get_mut(), core::ptr::null_mut());
+        let slot = unsafe { Self::heap_reclaim(leak) };
+
+        OptionViewMut { cell: self, slot }
+    }
+}
@@ -0,0 +1,165 @@
+//! Cell for the waker of a task that waits for an event
+
+use crate::{PtrCell, Semantics};
+use core::task::Waker;
+
+/// Thread-safe slot for a [`Waker`], like `AtomicWaker` from `futures`
+///
+/// The waiting task [registers](Self::register) its waker and the notifying side
+/// [wakes](Self::wake) it. Both operations are single atomic exchanges with
+/// [`Coupled`](Semantics::Coupled) semantics on the same cell, which is what makes the classic race
+/// between them harmless: as long as the task registers its waker _before_ checking whether the
+/// event has happened, and the notifier wakes _after_ making the event visible, either the task
+/// sees the event or the notifier sees the waker
+///
+/// Only the most recently registered waker is kept. It's meant for a single waiting task at a time
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::WakerCell;
+/// use std::{
+///     future::Future,
+///     pin::Pin,
+///     sync::atomic::{AtomicBool, Ordering},
+///     task::{Context, Poll},
+/// };
+///
+/// struct Event {
+///     happened: AtomicBool,
+///     waker: WakerCell,
+/// }
+///
+/// struct Wait<'a>(&'a Event);
+///
+/// impl Future for Wait<'_> {
+///     type Output = ();
+///
+///     fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<()> {
+///         // Register first, then check
+///         self.0.waker.register(context.waker());
+///
+///         match self.0.happened.load(Ordering::Acquire) {
+///             true => Poll::Ready(()),
+///             false => Poll::Pending,
+///         }
+///     }
+/// }
+///
+/// let event = Event {
+///     happened: AtomicBool::new(false),
+///     waker: WakerCell::new(),
+/// };
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         event.happened.store(true, Ordering::Release);
+///         event.waker.wake();
+///     });
+///
+///     pollster::block_on(Wait(&event))
+/// })
+/// ```
+pub struct WakerCell {
+    /// The registered waker
+    cell: PtrCell<Waker>,
+}
+
+impl WakerCell {
+    /// Registers a waker, replacing the previous one
+    ///
+    /// Call this before checking whether the awaited event has happened
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::WakerCell;
+    /// use std::task::Waker;
+    ///
+    /// let cell = WakerCell::new();
+    /// cell.register(Waker::noop());
+    ///
+    /// assert!(cell.take().is_some())
+    /// ```
+    #[inline]
+    pub fn register(&self, waker: &Waker) {
+        self.cell.set(Some(waker.clone()), Semantics::Coupled);
+    }
+
+    /// Wakes the registered task, if any, and empties the cell
+    ///
+    /// Call this after making the event visible
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::WakerCell;
+    /// use std::task::Waker;
+    ///
+    /// let cell = WakerCell::new();
+    /// cell.register(Waker::noop());
+    /// cell.wake();
+    ///
+    /// assert!(cell.take().is_none())
+    /// ```
+    #[inline]
+    pub fn wake(&self) {
+        if let Some(waker) = self.take() {
+            waker.wake();
+        }
+    }
+
+    /// Takes out the registered waker without waking it
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::WakerCell;
+    /// use std::task::Waker;
+    ///
+    /// let cell = WakerCell::new();
+    /// assert!(cell.take().is_none());
+    ///
+    /// cell.register(Waker::noop());
+    /// assert!(cell.take().is_some())
+    /// ```
+    #[inline]
+    pub fn take(&self) -> Option<Waker> {
+        self.cell.take(Semantics::Coupled)
+    }
+
+    /// Constructs a cell without a waker
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::WakerCell;
+    ///
+    /// static WAKER: WakerCell = WakerCell::new();
+    ///
+    /// WAKER.wake()
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            cell: PtrCell::empty(),
+        }
+    }
+}
+
+impl core::fmt::Debug for WakerCell {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("WakerCell")
+            .field("cell", &self.cell)
+            .finish()
+    }
+}
+
+impl Default for WakerCell {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
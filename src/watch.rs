@@ -0,0 +1,347 @@
+//! Channel that broadcasts the latest value to many subscribers
+//!
+//! Every [`Subscriber`] owns a [`PtrCell`] that works as its mailbox. The [`Publisher`]
+//! [replaces](PtrCell::replace) the contents of each mailbox with a clone of the new value, so a
+//! subscriber that falls behind only ever sees the newest value. Stale ones are dropped
+//!
+//! # Usage
+//!
+#![doc = doctest!()]
+//! use ptr_cell::watch;
+//!
+//! let (publisher, mut subscriber) = watch::channel("idle");
+//!
+//! std::thread::spawn(move || {
+//!     publisher.send("loading");
+//!     publisher.send("ready");
+//! });
+//!
+//! pollster::block_on(async {
+//!     while subscriber.changed().await.is_ok() {
+//!         if subscriber.latest() == "ready" {
+//!             break;
+//!         }
+//!     }
+//! });
+//!
+//! assert_eq!(subscriber.latest(), "ready")
+//! ```
+
+use crate::{PtrCell, Semantics};
+use alloc::{
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Constructs a channel with an initial value
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::watch;
+///
+/// let (publisher, mut subscriber) = watch::channel(1);
+///
+/// publisher.send(2);
+///
+/// assert_eq!(subscriber.latest(), 2)
+/// ```
+pub fn channel<T: Clone>(initial: T) -> (Publisher<T>, Subscriber<T>) {
+    let shared = Arc::new(Shared {
+        mailboxes: Mutex::new(Some(Vec::new())),
+    });
+
+    let subscriber = Subscriber::register(&shared, initial);
+    let publisher = Publisher { shared };
+
+    (publisher, subscriber)
+}
+
+/// Error returned by [`Subscriber::changed`] when the publisher has been dropped
+///
+/// # Usage
+///
+#[doc = doctest!()]
+/// use ptr_cell::watch;
+///
+/// let (publisher, mut subscriber) = watch::channel(());
+/// drop(publisher);
+///
+/// assert_eq!(pollster::block_on(subscriber.changed()), Err(watch::Closed))
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Closed;
+
+impl core::fmt::Display for Closed {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("the publisher has been dropped")
+    }
+}
+
+impl core::error::Error for Closed {}
+
+/// State shared by both ends of a channel
+struct Shared<T> {
+    /// Mailboxes of the live subscribers, or [`None`] if the publisher has been dropped
+    mailboxes: Mutex<Option<Vec<Weak<Mailbox<T>>>>>,
+}
+
+impl<T> Shared<T> {
+    /// Locks the list of mailboxes, ignoring poisoning
+    fn lock(&self) -> MutexGuard<'_, Option<Vec<Weak<Mailbox<T>>>>> {
+        self.mailboxes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Where the publisher leaves values for a single subscriber
+struct Mailbox<T> {
+    /// The newest value that the subscriber hasn't seen yet
+    value: PtrCell<T>,
+
+    /// Waker of the task that waits for a new value
+    waker: Mutex<Option<Waker>>,
+}
+
+impl<T> Mailbox<T> {
+    /// Sets the waker of the task that waits for a new value
+    fn register(&self, waker: &Waker) {
+        let waker = Some(waker.clone());
+
+        *self.waker.lock().unwrap_or_else(PoisonError::into_inner) = waker;
+    }
+
+    /// Wakes the task that waits for a new value, if there is one
+    fn wake(&self) {
+        let waker = self
+            .waker
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Sending end of a channel
+///
+/// Created by [`channel`]. Dropping it closes the channel
+pub struct Publisher<T> {
+    /// State shared with the subscribers
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Clone> Publisher<T> {
+    /// Broadcasts a value to every subscriber, replacing the values they haven't seen yet
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::watch;
+    ///
+    /// let (publisher, mut subscriber) = watch::channel('a');
+    ///
+    /// publisher.send('b');
+    /// publisher.send('c');
+    ///
+    /// assert_eq!(subscriber.latest(), 'c')
+    /// ```
+    pub fn send(&self, value: T) {
+        let mut mailboxes = self.shared.lock();
+        let Some(mailboxes) = mailboxes.as_mut() else {
+            return;
+        };
+
+        mailboxes.retain(|mailbox| {
+            let Some(mailbox) = mailbox.upgrade() else {
+                return false;
+            };
+
+            mailbox.value.set(Some(value.clone()), Semantics::Coupled);
+            mailbox.wake();
+
+            true
+        });
+    }
+}
+
+impl<T> core::fmt::Debug for Publisher<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.debug_struct("Publisher").finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for Publisher<T> {
+    fn drop(&mut self) {
+        let mailboxes = self.shared.lock().take().unwrap_or_default();
+
+        for mailbox in mailboxes.iter().filter_map(Weak::upgrade) {
+            mailbox.wake();
+        }
+    }
+}
+
+/// Receiving end of a channel
+///
+/// Created by [`channel`]. Cloning it adds another subscriber that has seen the same values
+pub struct Subscriber<T> {
+    /// State shared with the publisher
+    shared: Arc<Shared<T>>,
+
+    /// Values sent by the publisher
+    mailbox: Arc<Mailbox<T>>,
+
+    /// The newest value that this subscriber has seen
+    latest: T,
+}
+
+impl<T: Clone> Subscriber<T> {
+    /// Returns a clone of the newest value, marking it as seen
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::watch;
+    ///
+    /// let (publisher, mut subscriber) = watch::channel(0);
+    ///
+    /// assert_eq!(subscriber.latest(), 0);
+    ///
+    /// publisher.send(1);
+    /// assert_eq!(subscriber.latest(), 1)
+    /// ```
+    pub fn latest(&mut self) -> T {
+        if let Some(value) = self.mailbox.value.take(Semantics::Coupled) {
+            self.latest = value;
+        }
+
+        self.latest.clone()
+    }
+
+    /// Determines whether a value that this subscriber hasn't seen has been sent
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::watch;
+    ///
+    /// let (publisher, mut subscriber) = watch::channel(0);
+    /// publisher.send(1);
+    ///
+    /// assert!(subscriber.has_changed());
+    ///
+    /// let _ = subscriber.latest();
+    /// assert!(!subscriber.has_changed())
+    /// ```
+    pub fn has_changed(&self) -> bool {
+        !self.mailbox.value.is_empty(Semantics::Coupled)
+    }
+
+    /// Waits until a value that this subscriber hasn't seen is sent
+    ///
+    /// The value itself is retrieved with [`latest`](Self::latest)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Closed`] if the publisher has been dropped and there are no unseen values
+    ///
+    /// # Usage
+    ///
+    #[doc = doctest!()]
+    /// use ptr_cell::watch;
+    ///
+    /// let (publisher, mut subscriber) = watch::channel(0);
+    /// std::thread::spawn(move || publisher.send(1));
+    ///
+    /// pollster::block_on(async {
+    ///     assert_eq!(subscriber.changed().await, Ok(()));
+    ///     assert_eq!(subscriber.latest(), 1)
+    /// })
+    /// ```
+    pub const fn changed(&mut self) -> Changed<'_, T> {
+        Changed { subscriber: self }
+    }
+
+    /// Constructs a subscriber and adds its mailbox to the channel
+    fn register(shared: &Arc<Shared<T>>, latest: T) -> Self {
+        let mailbox = Arc::new(Mailbox {
+            value: PtrCell::default(),
+            waker: Mutex::new(None),
+        });
+
+        if let Some(mailboxes) = shared.lock().as_mut() {
+            mailboxes.push(Arc::downgrade(&mailbox));
+        }
+
+        let shared = Arc::clone(shared);
+
+        Self {
+            shared,
+            mailbox,
+            latest,
+        }
+    }
+}
+
+impl<T: Clone> Clone for Subscriber<T> {
+    fn clone(&self) -> Self {
+        Self::register(&self.shared, self.latest.clone())
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for Subscriber<T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_struct("Subscriber")
+            .field("latest", &self.latest)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Future that resolves once a subscriber has an unseen value
+///
+/// Created by [`Subscriber::changed`]
+pub struct Changed<'a, T> {
+    /// The waiting subscriber
+    subscriber: &'a Subscriber<T>,
+}
+
+impl<T: Clone> Future for Changed<'_, T> {
+    type Output = Result<(), Closed>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Result<(), Closed>> {
+        let subscriber = self.subscriber;
+
+        if subscriber.has_changed() {
+            return Poll::Ready(Ok(()));
+        }
+
+        subscriber.mailbox.register(context.waker());
+
+        // A value may have been sent before the waker was registered
+        if subscriber.has_changed() {
+            return Poll::Ready(Ok(()));
+        }
+
+        if subscriber.shared.lock().is_none() {
+            return Poll::Ready(Err(Closed));
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<T> core::fmt::Debug for Changed<'_, T> {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.debug_struct("Changed").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,18 @@
+//! [`Zeroable`] implementation, enabled by the `bytemuck` feature
+
+use crate::PtrCell;
+use bytemuck::Zeroable;
+
+/// An all-zero cell holds a null pointer, so it's empty. This holds for every atomics backend but
+/// Shuttle's, which is why the impl is left out with the `shuttle` feature
+///
+/// Large arrays of cells can then be allocated without constructing each cell:
+///
+/// ```rust
+/// use ptr_cell::{PtrCell, Semantics::Relaxed};
+///
+/// let slots = bytemuck::zeroed_box::<[PtrCell<u64>; 65_536]>();
+///
+/// assert!(slots.iter().all(|slot| slot.is_empty(Relaxed)))
+/// ```
+unsafe impl<T> Zeroable for PtrCell<T> {}
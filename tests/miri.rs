@@ -0,0 +1,739 @@
+//! Exercises every unsafe entry point of the pointer API, meant to be run under Miri
+//!
+//! The tests pass under a plain `cargo test` as well, but only Miri checks that the documented
+//! contracts actually hold: that no pointer is used after it's freed, freed twice, or leaked, and
+//! that no data race happens between threads. Run them with:
+//!
+//! ```sh
+//! cargo +nightly miri test --test miri
+//! ```
+//!
+//! Breaking a contract is undefined behavior rather than a panic, so misuse can't be tested here.
+//! Miri reports it as an error instead, which is what this suite relies on
+
+#![cfg(all(feature = "alloc", not(feature = "shuttle")))]
+
+use ptr_cell::{
+    channel,
+    node::Node,
+    spsc::RingBuffer,
+    Pool, PtrCell, Semantics,
+    Semantics::{Coupled, Relaxed},
+    SeqCell, SlicePtrCell, ThinBox,
+};
+use std::{
+    ptr::NonNull,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+/// Number of operations per thread. Kept low under Miri, which is several orders of magnitude
+/// slower than native code
+const ROUNDS: usize = if cfg!(miri) { 20 } else { 2_000 };
+
+/// Number of threads in the multithreaded tests
+const THREADS: usize = 4;
+
+/// Value that counts how many times it was dropped
+struct Counted(Arc<AtomicUsize>);
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn heap_leak_and_reclaim() {
+    let ptr = PtrCell::heap_leak(Some(String::from("leaked")));
+    assert!(!ptr.is_null());
+
+    assert_eq!(
+        unsafe { PtrCell::heap_reclaim(ptr) }.as_deref(),
+        Some("leaked")
+    );
+}
+
+#[test]
+fn heap_leak_none_is_null() {
+    assert!(PtrCell::<u8>::heap_leak(None).is_null());
+    assert_eq!(
+        unsafe { PtrCell::<u8>::heap_reclaim(std::ptr::null_mut()) },
+        None
+    );
+}
+
+#[test]
+fn heap_leak_zero_sized() {
+    let ptr = PtrCell::heap_leak(Some(()));
+    assert!(!ptr.is_null());
+
+    assert_eq!(unsafe { PtrCell::heap_reclaim(ptr) }, Some(()));
+}
+
+#[test]
+fn heap_leak_overaligned() {
+    let ptr = PtrCell::heap_leak(Some(u128::MAX));
+    assert_eq!(ptr.align_offset(std::mem::align_of::<u128>()), 0);
+
+    assert_eq!(unsafe { PtrCell::heap_reclaim(ptr) }, Some(u128::MAX));
+}
+
+#[test]
+fn heap_leak_and_reclaim_iter() {
+    let ptrs: Vec<_> = PtrCell::heap_leak_iter((0..16).map(|value| vec![value; 4])).collect();
+    let values: Vec<_> = unsafe { PtrCell::heap_reclaim_iter(ptrs) }.collect();
+
+    assert!(values
+        .into_iter()
+        .flatten()
+        .eq((0..16).map(|value| vec![value; 4])));
+}
+
+#[test]
+fn heap_reclaim_iter_with_nulls() {
+    let ptrs = [
+        PtrCell::heap_leak(Some("a")),
+        std::ptr::null_mut(),
+        PtrCell::heap_leak(Some("b")),
+    ];
+
+    let values = unsafe { PtrCell::heap_reclaim_iter(ptrs) };
+    assert!(values.eq([Some("a"), None, Some("b")]));
+}
+
+#[test]
+fn from_ptr_takes_ownership() {
+    let cell = unsafe { PtrCell::from_ptr(PtrCell::heap_leak(Some(vec![1, 2, 3]))) };
+    assert_eq!(cell.take(Relaxed), Some(vec![1, 2, 3]));
+
+    let cell = unsafe { PtrCell::from_ptr(PtrCell::heap_leak(Some(vec![4, 5, 6]))) };
+    drop(cell);
+
+    let empty = unsafe { PtrCell::<Vec<u8>>::from_ptr(std::ptr::null_mut()) };
+    assert!(empty.is_empty(Relaxed));
+}
+
+#[test]
+fn from_ptr_in_static() {
+    static CELL: PtrCell<u16> = unsafe { PtrCell::from_ptr(std::ptr::null_mut()) };
+
+    CELL.set(Some(1024), Coupled);
+    assert_eq!(CELL.take(Coupled), Some(1024));
+}
+
+#[test]
+fn leak_and_from_ptr_round_trip() {
+    let ptr = PtrCell::from(String::from("round trip")).leak();
+    let cell = unsafe { PtrCell::from_ptr(ptr) };
+
+    assert_eq!(cell.into_inner().as_deref(), Some("round trip"));
+}
+
+#[test]
+fn set_ptr_into_empty_cell() {
+    let cell = PtrCell::default();
+
+    unsafe { cell.set_ptr(PtrCell::heap_leak(Some(Box::new(7))), Relaxed) };
+    assert_eq!(cell.take(Relaxed), Some(Box::new(7)));
+
+    // A null pointer empties the cell
+    unsafe { cell.set_ptr(std::ptr::null_mut(), Relaxed) };
+    assert!(cell.is_empty(Relaxed));
+}
+
+#[test]
+fn set_ptr_after_take_ptr() {
+    let cell = PtrCell::from(String::from("first"));
+
+    let first = cell.take_ptr(Relaxed);
+    unsafe { cell.set_ptr(PtrCell::heap_leak(Some(String::from("second"))), Relaxed) };
+
+    assert_eq!(
+        unsafe { PtrCell::heap_reclaim(first) }.as_deref(),
+        Some("first")
+    );
+    assert_eq!(cell.take(Relaxed).as_deref(), Some("second"));
+}
+
+#[test]
+fn replace_ptr_hands_back_ownership() {
+    let cell = PtrCell::from(String::from("old"));
+
+    let old = cell.replace_ptr(PtrCell::heap_leak(Some(String::from("new"))), Coupled);
+    assert_eq!(
+        unsafe { PtrCell::heap_reclaim(old) }.as_deref(),
+        Some("old")
+    );
+
+    let new = cell.replace_ptr(std::ptr::null_mut(), Coupled);
+    assert_eq!(
+        unsafe { PtrCell::heap_reclaim(new) }.as_deref(),
+        Some("new")
+    );
+}
+
+#[test]
+fn get_ptr_reads_in_place() {
+    let mut cell = PtrCell::from(42_u64);
+    let ptr = cell.get_ptr(Relaxed);
+
+    assert!(cell.ptr_eq(ptr, Relaxed));
+    assert_eq!(cell.get_mut().copied(), Some(42));
+    assert_eq!(unsafe { *ptr }, 42);
+}
+
+#[test]
+fn fetch_update_ptr_swaps_once() {
+    let cell = PtrCell::from(1_u32);
+    let two = PtrCell::heap_leak(Some(2));
+
+    let previous = unsafe { cell.fetch_update_ptr(Coupled, |_| Some(two)) };
+    let previous = previous.expect("The update never stops");
+
+    assert_eq!(unsafe { PtrCell::heap_reclaim(previous) }, Some(1));
+    assert_eq!(cell.take(Coupled), Some(2));
+}
+
+#[test]
+fn fetch_update_ptr_stops() {
+    let cell = PtrCell::from('c');
+    let current = cell.get_ptr(Relaxed);
+
+    let stopped = unsafe { cell.fetch_update_ptr(Relaxed, |_| None) };
+
+    assert_eq!(stopped, Err(current));
+    assert_eq!(cell.take(Relaxed), Some('c'));
+}
+
+#[test]
+fn nonnull_pointers() {
+    let cell = PtrCell::from(3.5_f32);
+
+    let taken = cell
+        .take_ptr_nonnull(Relaxed)
+        .expect("The cell isn't empty");
+    assert_eq!(cell.get_ptr_nonnull(Relaxed), None);
+
+    unsafe { cell.set_ptr_nonnull(Some(taken), Relaxed) };
+
+    let replaced = unsafe { cell.replace_ptr_nonnull(None, Relaxed) };
+    assert_eq!(replaced, Some(taken));
+    assert_eq!(unsafe { PtrCell::heap_reclaim(taken.as_ptr()) }, Some(3.5));
+
+    let dangling = NonNull::<f32>::dangling();
+    assert!(!cell.ptr_eq(dangling.as_ptr(), Relaxed));
+}
+
+#[test]
+#[cfg(not(any(feature = "critical-section", feature = "portable-atomic")))]
+fn as_atomic_ptr_swaps_owned_pointers() {
+    let cell = PtrCell::from(String::from("atomic"));
+    let atomic = unsafe { cell.as_atomic_ptr() };
+
+    let ptr = atomic.swap(std::ptr::null_mut(), Ordering::AcqRel);
+    assert!(cell.is_empty(Relaxed));
+
+    atomic.store(ptr, Ordering::Release);
+    assert_eq!(cell.take(Relaxed).as_deref(), Some("atomic"));
+}
+
+#[test]
+#[cfg(not(any(
+    feature = "critical-section",
+    feature = "portable-atomic",
+    feature = "allocator_api"
+)))]
+fn from_atomic_and_from_atomic_ref() {
+    let atomic = std::sync::atomic::AtomicPtr::new(PtrCell::heap_leak(Some(vec![0_u8; 3])));
+    let cell = unsafe { PtrCell::from_atomic(atomic) };
+    assert_eq!(cell.take(Relaxed), Some(vec![0; 3]));
+
+    let atomic = std::sync::atomic::AtomicPtr::new(PtrCell::heap_leak(Some(vec![1_u8; 3])));
+    {
+        let borrowed = unsafe { PtrCell::from_atomic_ref(&atomic) };
+        assert_eq!(
+            borrowed.replace(Some(vec![2; 3]), Relaxed),
+            Some(vec![1; 3])
+        );
+    }
+
+    // The atomic still owns its value, so it's reclaimed by hand
+    let ptr = atomic.into_inner();
+    assert_eq!(unsafe { PtrCell::heap_reclaim(ptr) }, Some(vec![2; 3]));
+}
+
+#[test]
+fn pool_reclaims_and_reuses() {
+    let pool: Pool<String, 2> = Pool::new();
+
+    let ptr = pool.leak(Some(String::from("pooled")));
+    assert_eq!(unsafe { pool.reclaim(ptr) }.as_deref(), Some("pooled"));
+    assert_eq!(pool.available(), 1);
+
+    // Reuses the allocation that was just reclaimed
+    let ptr = pool.leak(Some(String::from("reused")));
+    assert_eq!(pool.available(), 0);
+
+    // Pooled allocations are interchangeable with those of the cell
+    assert_eq!(
+        unsafe { PtrCell::heap_reclaim(ptr) }.as_deref(),
+        Some("reused")
+    );
+}
+
+#[test]
+fn pool_with_cell() {
+    let pool: Pool<Vec<u8>, 4> = Pool::new();
+    pool.fill();
+
+    let cell = PtrCell::default();
+    cell.set_pooled(Some(vec![1]), &pool, Relaxed);
+
+    let previous = cell.replace_pooled(Some(vec![2]), &pool, Relaxed);
+    assert_eq!(previous, Some(vec![1]));
+    assert_eq!(cell.take_pooled(&pool, Relaxed), Some(vec![2]));
+}
+
+#[test]
+fn chain_is_dropped_once() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let list = PtrCell::default();
+
+    for _ in 0..ROUNDS {
+        Node::push(&list, Counted(drops.clone()), Relaxed);
+    }
+
+    drop(list);
+    assert_eq!(drops.load(Ordering::Relaxed), ROUNDS);
+}
+
+#[test]
+fn chain_partially_consumed() {
+    let list = PtrCell::default();
+
+    for value in 0..8 {
+        Node::push(&list, value.to_string(), Relaxed);
+    }
+
+    let head = list.take(Relaxed).expect("The list shouldn't be empty");
+    let mut values = head.reverse().into_iter();
+
+    assert_eq!(values.next().as_deref(), Some("0"));
+    assert_eq!(values.next().as_deref(), Some("1"));
+}
+
+#[test]
+fn chain_unlinked_through_cursor() {
+    let mut list = PtrCell::default();
+
+    for value in 0..6 {
+        Node::push(&list, Box::new(value), Relaxed);
+    }
+
+    let mut nodes = list.iter_chain_mut();
+    while let Some(node) = nodes.next() {
+        if let Some(mut skipped) = node.next.take(Relaxed) {
+            node.next = std::mem::take(&mut skipped.next);
+        }
+    }
+
+    assert_eq!(list.chain_len(), 3);
+    list.drop_chain_iteratively();
+    assert!(list.is_empty(Relaxed));
+}
+
+#[test]
+fn map_owner_ptr_points_to_owner() {
+    let list = PtrCell::default();
+
+    let node = list.map_owner_ptr(|next| Node { value: 'a', next }, Relaxed);
+
+    assert!(list.ptr_eq(node, Relaxed));
+    assert_eq!(unsafe { (*node).value }, 'a');
+}
+
+#[test]
+fn thin_box_layouts() {
+    let strings = ThinBox::<[String]>::from(vec![String::from("a"), String::from("b")]);
+    assert_eq!(strings.len(), 2);
+
+    let empty = ThinBox::<[u128]>::from(Vec::new());
+    assert!(empty.is_empty());
+
+    let zero_sized = ThinBox::<[()]>::from(vec![(); 3]);
+    assert_eq!(zero_sized.len(), 3);
+
+    let text = ThinBox::<str>::from("ünïcödé");
+    assert_eq!(&*text, "ünïcödé");
+}
+
+#[test]
+fn slice_cell_replaces() {
+    let cell = SlicePtrCell::<[Box<u8>]>::from(vec![Box::new(1), Box::new(2)]);
+
+    let previous = cell.replace(Some(ThinBox::from(vec![Box::new(3)])), Relaxed);
+    assert_eq!(previous.as_deref(), Some(&[Box::new(1), Box::new(2)][..]));
+
+    cell.set(None, Relaxed);
+    assert!(cell.is_empty(Relaxed));
+}
+
+#[test]
+fn concurrent_replace_drops_each_value_once() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let cell = PtrCell::default();
+
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ROUNDS {
+                    drop(cell.replace(Some(Counted(drops.clone())), Coupled));
+                }
+            });
+        }
+    });
+
+    drop(cell);
+    assert_eq!(drops.load(Ordering::Relaxed), THREADS * ROUNDS);
+}
+
+#[test]
+fn concurrent_raw_pointers() {
+    let cell = PtrCell::default();
+
+    thread::scope(|scope| {
+        for thread in 0..THREADS {
+            let cell = &cell;
+
+            scope.spawn(move || {
+                for round in 0..ROUNDS {
+                    let ptr = PtrCell::heap_leak(Some(vec![thread; round % 4]));
+                    let previous = cell.replace_ptr(ptr, Coupled);
+
+                    // Only one thread can receive each pointer
+                    if let Some(previous) = unsafe { PtrCell::heap_reclaim(previous) } {
+                        assert!(previous.len() < 4);
+                    }
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn concurrent_take_and_set() {
+    let cell = PtrCell::default();
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for round in 0..ROUNDS {
+                cell.set(Some(round.to_string()), Coupled);
+            }
+        });
+
+        scope.spawn(|| {
+            for _ in 0..ROUNDS {
+                if let Some(value) = cell.take(Coupled) {
+                    assert!(value.parse::<usize>().is_ok());
+                }
+            }
+        });
+    });
+}
+
+#[test]
+fn concurrent_swap_shared_drops_overwritten_values() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let counted = || Counted(Arc::clone(&drops));
+
+    let one = PtrCell::from(counted());
+    let two = PtrCell::from(counted());
+
+    let replaced = thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 0..ROUNDS {
+                one.swap_shared(&two, Coupled);
+            }
+        });
+
+        let writer = scope.spawn(|| {
+            (0..ROUNDS)
+                .map(|_| one.replace(Some(counted()), Coupled))
+                .collect::<Vec<_>>()
+        });
+
+        writer.join().expect("The writer shouldn't panic")
+    });
+
+    // A value that's written to `one` between the steps of a swap is neither replaced nor left in
+    // a cell. It's dropped by the swap instead, which is the only way values get dropped here
+    let kept = replaced.iter().flatten().count()
+        + usize::from(!one.is_empty(Coupled))
+        + usize::from(!two.is_empty(Coupled));
+
+    assert_eq!(kept + drops.load(Ordering::Relaxed), ROUNDS + 2);
+}
+
+#[test]
+fn concurrent_map_owner() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut list = PtrCell::default();
+
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ROUNDS {
+                    Node::push(&list, Counted(drops.clone()), Semantics::Coupled);
+                }
+            });
+        }
+    });
+
+    assert_eq!(list.chain_len(), THREADS * ROUNDS);
+    drop(list);
+    assert_eq!(drops.load(Ordering::Relaxed), THREADS * ROUNDS);
+}
+
+#[test]
+fn concurrent_rcu() {
+    let cell = PtrCell::from(vec![0_usize]);
+
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ROUNDS {
+                    cell.rcu(
+                        |current| {
+                            let mut next = current.cloned().unwrap_or_default();
+                            next.push(next.len());
+                            Some(next)
+                        },
+                        Coupled,
+                    );
+                }
+            });
+        }
+
+        // Values may be inserted while `rcu` has the current one taken out
+        scope.spawn(|| {
+            for _ in 0..ROUNDS {
+                if let Some(values) = cell.take(Coupled) {
+                    assert!(values
+                        .iter()
+                        .enumerate()
+                        .all(|(index, &value)| index == value));
+                    cell.set(Some(values), Coupled);
+                }
+            }
+        });
+    });
+
+    let values = cell.take(Coupled).expect("The cell shouldn't be empty");
+    assert!(values
+        .iter()
+        .enumerate()
+        .all(|(index, &value)| index == value));
+}
+
+#[test]
+fn concurrent_contains_value() {
+    let cell = PtrCell::from(String::from("0"));
+
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for round in 0..ROUNDS {
+                    let _ = cell.contains_value(&round.to_string(), Coupled);
+                }
+            });
+        }
+
+        // Values that are being compared may be replaced and dropped at any moment
+        scope.spawn(|| {
+            for round in 0..ROUNDS {
+                cell.set(Some(round.to_string()), Coupled);
+            }
+        });
+    });
+
+    assert!(cell.contains_value(&(ROUNDS - 1).to_string(), Coupled));
+}
+
+#[test]
+fn concurrent_seq_cell() {
+    let cell = SeqCell::new((0, 0));
+
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ROUNDS {
+                    let (x, y) = cell.get();
+                    assert_eq!(x, y);
+                }
+            });
+        }
+
+        scope.spawn(|| {
+            for round in 1..=ROUNDS {
+                cell.set((round, round));
+            }
+        });
+    });
+
+    assert_eq!(cell.get(), (ROUNDS, ROUNDS));
+}
+
+#[test]
+fn fenced_publication() {
+    let cell = PtrCell::default();
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for round in 0..ROUNDS {
+                let ptr = PtrCell::heap_leak(Some(vec![round; 4]));
+
+                cell.fence_for(Semantics::ReleaseOnly);
+                let previous = cell.replace_ptr(ptr, Relaxed);
+
+                // Values that weren't taken were published by this thread, so they can be
+                // reclaimed without synchronizing with anyone
+                drop(unsafe { PtrCell::heap_reclaim(previous) });
+            }
+        });
+
+        scope.spawn(|| {
+            for _ in 0..ROUNDS {
+                let ptr = cell.take_ptr(Relaxed);
+
+                if !ptr.is_null() {
+                    cell.fence_for(Semantics::AcquireOnly);
+                    let value = unsafe { PtrCell::heap_reclaim(ptr) };
+                    assert!(value.is_some_and(|value| value.len() == 4));
+                }
+            }
+        });
+    });
+}
+
+#[test]
+fn concurrent_shared_iteration() {
+    struct Link {
+        value: Counted,
+        next: PtrCell<Arc<Self>>,
+    }
+
+    impl AsRef<PtrCell<Arc<Self>>> for Link {
+        fn as_ref(&self) -> &PtrCell<Arc<Self>> {
+            &self.next
+        }
+    }
+
+    let drops = Arc::new(AtomicUsize::new(0));
+    let list: PtrCell<Arc<Link>> = PtrCell::default();
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            let mut head = None;
+
+            for _ in 0..ROUNDS {
+                let next = PtrCell::from(head);
+                head = Some(Arc::new(Link {
+                    value: Counted(drops.clone()),
+                    next,
+                }));
+            }
+
+            list.set(head, Coupled);
+        });
+
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                while list.is_empty(Semantics::AcquireOnly) {
+                    thread::yield_now();
+                }
+
+                // The list is only written to once, before it's iterated
+                let nodes = unsafe { list.iter_shared(Semantics::AcquireOnly) };
+                let kept: Vec<_> = nodes.collect();
+
+                assert_eq!(kept.len(), ROUNDS);
+                assert!(kept.iter().all(|node| Arc::ptr_eq(&node.value.0, &drops)));
+            });
+        }
+    });
+
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(list);
+    assert_eq!(drops.load(Ordering::Relaxed), ROUNDS);
+}
+
+#[test]
+fn concurrent_channel() {
+    let (sender, mut receiver) = channel::channel();
+
+    thread::scope(|scope| {
+        for thread in 0..THREADS {
+            let sender = sender.clone();
+
+            scope.spawn(move || {
+                for round in 0..ROUNDS {
+                    let _ = sender.send(Box::new((thread, round)));
+                }
+            });
+        }
+
+        drop(sender);
+    });
+
+    // Every sender is gone, so the channel closes once it's been emptied
+    let mut received = 0;
+    while let Ok(message) = receiver.try_recv() {
+        assert!(message.0 < THREADS && message.1 < ROUNDS);
+        received += 1;
+    }
+
+    assert_eq!(received, THREADS * ROUNDS);
+}
+
+#[test]
+fn concurrent_oneshot() {
+    let (sender, mut receiver) = channel::oneshot();
+
+    thread::scope(|scope| {
+        scope.spawn(move || sender.send(String::from("once")));
+    });
+
+    assert_eq!(receiver.try_recv().as_deref(), Ok("once"));
+}
+
+#[test]
+fn ring_buffer_wraps_around() {
+    let drops = Arc::new(AtomicUsize::new(0));
+
+    // Three isn't a power of two, so the slots only line up with the indices if they wrap at a
+    // multiple of the capacity
+    let mut ring: RingBuffer<(usize, Counted), 3> = RingBuffer::new();
+    let (producer, consumer) = ring.split();
+    let (mut pushed, mut popped) = (0, 0);
+
+    for round in 0..ROUNDS {
+        while !producer.is_full() {
+            assert!(producer.push((pushed, Counted(drops.clone()))).is_ok());
+            pushed += 1;
+        }
+
+        for _ in 0..=round % 3 {
+            let (index, _) = consumer.pop().expect("The ring shouldn't be empty");
+            assert_eq!(index, popped);
+            popped += 1;
+        }
+    }
+
+    assert_eq!(drops.load(Ordering::Relaxed), popped);
+    drop(ring);
+    assert_eq!(drops.load(Ordering::Relaxed), pushed);
+}